@@ -50,7 +50,7 @@ async fn main() -> Result<(), Error> {
         print_position(&client);
 
         // After you processed the event, you need to update replication position
-        client.commit(&header, &event);
+        client.commit(&header, &event)?;
 
         println!("Replication position after event processed");
         print_position(&client);
@@ -0,0 +1,125 @@
+use crate::binlog_reader::{create_parser, is_end_of_stream, verify_magic_number};
+use crate::constants;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::event_parser::EventParser;
+use crate::reader_options::ReaderOptions;
+use crate::transaction::{Transaction, TransactionAssembler};
+use async_stream::stream;
+use constants::EVENT_HEADER_SIZE;
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Reads binlog events from an async stream, e.g. network or object storage download.
+pub struct AsyncBinlogReader<R: AsyncRead + Unpin> {
+    stream: R,
+    parser: EventParser,
+    payload_buffer: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncBinlogReader<R> {
+    pub async fn new(stream: R) -> Result<Self, Error> {
+        Self::with_options(stream, ReaderOptions::default()).await
+    }
+
+    pub async fn with_options(mut stream: R, options: ReaderOptions) -> Result<Self, Error> {
+        let mut header = [0; constants::FIRST_EVENT_POSITION];
+        stream.read_exact(&mut header).await?;
+        verify_magic_number(&header)?;
+
+        Ok(Self {
+            stream,
+            parser: create_parser(options),
+            payload_buffer: vec![0; constants::PAYLOAD_BUFFER_SIZE],
+        })
+    }
+
+    /// Reads events until the end of the stream like BinlogClient::replicate.
+    pub fn read_events(mut self) -> impl Stream<Item = Result<(EventHeader, BinlogEvent), Error>> {
+        stream! {
+            loop {
+                match self.read_event().await {
+                    Err(error) if is_end_of_stream(&error) => break,
+                    result => yield result,
+                }
+            }
+        }
+    }
+
+    /// Reads events grouped into transactions.
+    pub fn read_transactions(self) -> impl Stream<Item = Result<Transaction, Error>> {
+        let assembler = TransactionAssembler::new(self.parser.binlog_filename.clone());
+        assembler.stream(self.read_events())
+    }
+
+    pub async fn read_event(&mut self) -> Result<(EventHeader, BinlogEvent), Error> {
+        let mut header_buffer = [0; EVENT_HEADER_SIZE];
+        self.stream.read_exact(&mut header_buffer).await?;
+        let header = EventHeader::parse(&header_buffer)?;
+
+        let payload_length = header.event_length as usize - EVENT_HEADER_SIZE;
+        if payload_length > constants::PAYLOAD_BUFFER_SIZE {
+            let mut vec: Vec<u8> = vec![0; payload_length];
+
+            self.stream.read_exact(&mut vec).await?;
+            let binlog_event = self.parser.parse_event(&header, &vec)?;
+            Ok((header, binlog_event))
+        } else {
+            let slice = &mut self.payload_buffer[0..payload_length];
+
+            self.stream.read_exact(slice).await?;
+            let binlog_event = self.parser.parse_event(&header, slice)?;
+            Ok((header, binlog_event))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::AsyncBinlogReader;
+    use crate::binlog_writer::BinlogWriter;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::query_event::QueryEvent;
+    use crate::events::xid_event::XidEvent;
+    use crate::test_binlogs::{create_header, write_xid};
+
+    fn create_binlog() -> Vec<u8> {
+        let mut writer = BinlogWriter::new(Vec::new()).unwrap();
+        let begin = BinlogEvent::QueryEvent(QueryEvent {
+            thread_id: 5,
+            duration: 0,
+            error_code: 0,
+            status_variables: Vec::new(),
+            status: Default::default(),
+            database_name: String::from("shop"),
+            sql_statement: String::from("BEGIN"),
+            schema_change: None,
+        });
+        writer.write_event(&create_header(2), &begin).unwrap();
+        write_xid(&mut writer, 42);
+        writer.into_inner()
+    }
+
+    #[tokio::test]
+    async fn read_async_stream() {
+        let binlog = create_binlog();
+        let reader = AsyncBinlogReader::new(binlog.as_slice()).await.unwrap();
+        let events: Vec<_> = reader.read_events().map(|x| x.unwrap()).collect().await;
+        assert_eq!(3, events.len());
+        assert!(matches!(
+            events[0].1,
+            BinlogEvent::FormatDescriptionEvent(_)
+        ));
+        assert!(matches!(
+            events[2].1,
+            BinlogEvent::XidEvent(XidEvent { xid: 42 })
+        ));
+
+        let reader = AsyncBinlogReader::new(binlog.as_slice()).await.unwrap();
+        let transactions: Vec<_> = reader.read_transactions().collect().await;
+        assert_eq!(1, transactions.len());
+    }
+}
@@ -0,0 +1,768 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::calendar::{days_from_civil, seconds_from_civil};
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::row_events::mysql_value::MySqlValue;
+use crate::events::row_events::row_decoder::{RowChange, RowDecoder};
+use crate::events::row_events::typed_row::{ColumnValue, TypedRow};
+use crate::events::table_map_event::{ColumnInfo, TableMapEvent, BINARY_COLLATION};
+
+/// Empty value of CRC-64-AVRO fingerprint.
+const EMPTY_FINGERPRINT: u64 = 0xc15d213aa4d7a795;
+
+/// Avro type of a column.
+#[derive(Clone, Debug, PartialEq)]
+enum AvroType {
+    Int,
+    Long,
+    Float,
+    Double,
+    String,
+    Bytes,
+    Decimal { precision: u16, scale: u16 },
+    Date,
+    TimeMicros,
+    TimestampMicros,
+    StringArray,
+}
+
+impl AvroType {
+    fn from_column(column: &ColumnInfo) -> Result<Self, Error> {
+        let avro_type = match ColumnType::from_code(column.column_type)? {
+            ColumnType::Tiny | ColumnType::Short | ColumnType::Int24 | ColumnType::Year => {
+                AvroType::Int
+            }
+            ColumnType::Long if column.unsigned => AvroType::Long,
+            ColumnType::Long => AvroType::Int,
+            // Unsigned BIGINT doesn't fit long.
+            ColumnType::LongLong if column.unsigned => AvroType::Decimal {
+                precision: 20,
+                scale: 0,
+            },
+            ColumnType::LongLong | ColumnType::Bit => AvroType::Long,
+            ColumnType::Float => AvroType::Float,
+            ColumnType::Double => AvroType::Double,
+            ColumnType::NewDecimal => AvroType::Decimal {
+                precision: column.metadata & 0xFF,
+                scale: column.metadata >> 8,
+            },
+            ColumnType::Decimal | ColumnType::Json | ColumnType::Null => AvroType::String,
+            ColumnType::VarChar | ColumnType::VarString | ColumnType::String => {
+                match column.collation == Some(BINARY_COLLATION) {
+                    true => AvroType::Bytes,
+                    false => AvroType::String,
+                }
+            }
+            ColumnType::TinyBlob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+            | ColumnType::Blob => match column.collation {
+                Some(x) if x != BINARY_COLLATION => AvroType::String,
+                _ => AvroType::Bytes,
+            },
+            ColumnType::Enum => match column.labels {
+                Some(_) => AvroType::String,
+                None => AvroType::Int,
+            },
+            ColumnType::Set => match column.labels {
+                Some(_) => AvroType::StringArray,
+                None => AvroType::Long,
+            },
+            ColumnType::Date | ColumnType::NewDate => AvroType::Date,
+            ColumnType::Time | ColumnType::Time2 => AvroType::TimeMicros,
+            ColumnType::DateTime
+            | ColumnType::DateTime2
+            | ColumnType::TimeStamp
+            | ColumnType::TimeStamp2 => AvroType::TimestampMicros,
+            ColumnType::Geometry => AvroType::Bytes,
+        };
+        Ok(avro_type)
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            AvroType::Int => json!("int"),
+            AvroType::Long => json!("long"),
+            AvroType::Float => json!("float"),
+            AvroType::Double => json!("double"),
+            AvroType::String => json!("string"),
+            AvroType::Bytes => json!("bytes"),
+            AvroType::Decimal { precision, scale } => json!({
+                "type": "bytes",
+                "logicalType": "decimal",
+                "precision": precision,
+                "scale": scale,
+            }),
+            AvroType::Date => json!({ "type": "int", "logicalType": "date" }),
+            AvroType::TimeMicros => json!({ "type": "long", "logicalType": "time-micros" }),
+            AvroType::TimestampMicros => {
+                json!({ "type": "long", "logicalType": "timestamp-micros" })
+            }
+            AvroType::StringArray => json!({ "type": "array", "items": "string" }),
+        }
+    }
+}
+
+/// Avro record schema of a table derived from TableMapEvent.
+/// <a href="https://avro.apache.org/docs/current/specification/">See more</a>
+#[derive(Clone, Debug)]
+pub struct AvroSchema {
+    /// Gets record name. Table name with characters not allowed in Avro names replaced by '_'.
+    pub name: String,
+
+    /// Gets record namespace. Database name with characters not allowed in Avro names replaced by '_'.
+    pub namespace: String,
+
+    /// Gets schema JSON.
+    pub json: String,
+
+    /// Gets CRC-64-AVRO fingerprint of the schema Parsing Canonical Form.
+    /// The fingerprint changes when the table definition changes.
+    pub fingerprint: u64,
+
+    columns: Vec<(ColumnInfo, AvroType)>,
+}
+
+impl AvroSchema {
+    /// Generates schema from column types and optional metadata of the TableMapEvent.
+    /// Column names are available only with `binlog_row_metadata=FULL`, otherwise fields are named _1, _2, etc.
+    pub fn new(table: &TableMapEvent) -> Result<Self, Error> {
+        let mut columns = Vec::new();
+        let mut fields = Vec::new();
+        for column in table.columns()? {
+            let avro_type = AvroType::from_column(&column)?;
+            fields.push(match column.nullable {
+                true => json!({
+                    "name": to_avro_name(&column.name),
+                    "type": ["null", avro_type.to_json()],
+                    "default": null,
+                }),
+                false => json!({ "name": to_avro_name(&column.name), "type": avro_type.to_json() }),
+            });
+            columns.push((column, avro_type));
+        }
+
+        let name = to_avro_name(&table.table_name);
+        let namespace = to_avro_name(&table.database_name);
+        let schema = json!({
+            "type": "record",
+            "name": name,
+            "namespace": namespace,
+            "fields": fields,
+        });
+        let json = schema.to_string();
+        let fingerprint = fingerprint(canonical_form(&schema, "").as_bytes());
+
+        Ok(Self {
+            name,
+            namespace,
+            json,
+            fingerprint,
+            columns,
+        })
+    }
+
+    /// Encodes row image into Avro binary.
+    pub fn encode(&self, row: &TypedRow) -> Result<Vec<u8>, Error> {
+        if row.columns.len() != self.columns.len() {
+            return Err(Error::String(format!(
+                "Row has {} columns while schema {}.{} has {} fields",
+                row.columns.len(),
+                self.namespace,
+                self.name,
+                self.columns.len()
+            )));
+        }
+
+        let mut buffer = Vec::new();
+        for ((column, avro_type), value) in self.columns.iter().zip(row.columns.iter()) {
+            // Zero dates are encoded as null for nullable columns and epoch otherwise.
+            let value = match value.value.as_ref() {
+                Some(ColumnValue::Value(MySqlValue::Date(x))) if x.year == 0 && column.nullable => {
+                    None
+                }
+                Some(ColumnValue::Value(MySqlValue::DateTime(x)))
+                    if x.year == 0 && column.nullable =>
+                {
+                    None
+                }
+                value => value,
+            };
+            match (value, column.nullable) {
+                (Some(value), true) => {
+                    write_long(&mut buffer, 1);
+                    write_value(&mut buffer, column, avro_type, value)?;
+                }
+                (Some(value), false) => write_value(&mut buffer, column, avro_type, value)?,
+                (None, true) => write_long(&mut buffer, 0),
+                (None, false) => {
+                    return Err(Error::String(format!(
+                        "Value of NOT NULL column {} is missing. Please use binlog_row_image=FULL",
+                        column.name
+                    )))
+                }
+            }
+        }
+        Ok(buffer)
+    }
+}
+
+/// Row change with Avro encoded row images.
+#[derive(Clone, Debug)]
+pub struct AvroRowChange {
+    /// Gets schema of the row images.
+    pub schema: Arc<AvroSchema>,
+
+    /// Gets encoded row before the change. None for inserted rows.
+    pub before: Option<Vec<u8>>,
+
+    /// Gets encoded row after the change. None for deleted rows.
+    pub after: Option<Vec<u8>>,
+}
+
+/// Encodes rows of row events into Avro binary.
+/// Caches schemas of the tables and generates a new schema when the table definition changes.
+/// All events of the stream must be passed in order.
+pub struct AvroEncoder {
+    decoder: RowDecoder,
+    schemas: HashMap<u64, Arc<AvroSchema>>,
+}
+
+impl AvroEncoder {
+    pub fn new() -> Self {
+        Self {
+            decoder: RowDecoder::new(),
+            schemas: HashMap::new(),
+        }
+    }
+
+    /// Gets schema of the table with the id.
+    pub fn schema(&self, table_id: u64) -> Option<&Arc<AvroSchema>> {
+        self.schemas.get(&table_id)
+    }
+
+    /// Returns encoded changed rows of a row event. Returns empty list for other events.
+    pub fn encode(&mut self, event: &BinlogEvent) -> Result<Vec<AvroRowChange>, Error> {
+        let table_id = match event {
+            BinlogEvent::TableMapEvent(x) => {
+                let changed = match self.schemas.get(&x.table_id) {
+                    Some(schema) => {
+                        schema.name != to_avro_name(&x.table_name)
+                            || schema.namespace != to_avro_name(&x.database_name)
+                            || !schema
+                                .columns
+                                .iter()
+                                .map(|(c, _)| c)
+                                .eq(x.columns()?.iter())
+                    }
+                    None => true,
+                };
+                if changed {
+                    self.schemas
+                        .insert(x.table_id, Arc::new(AvroSchema::new(x)?));
+                }
+                None
+            }
+            BinlogEvent::WriteRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::UpdateRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::DeleteRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::MySqlPartialUpdateRowsEvent(x) => Some(x.table_id),
+            _ => None,
+        };
+
+        let changes = self.decoder.decode(event)?;
+        let schema = match table_id.and_then(|x| self.schemas.get(&x)) {
+            Some(schema) => schema,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::with_capacity(changes.len());
+        for change in changes {
+            let (before, after) = match change {
+                RowChange::Insert(x) => (None, Some(schema.encode(&x)?)),
+                RowChange::Update { before, after } => {
+                    (Some(schema.encode(&before)?), Some(schema.encode(&after)?))
+                }
+                RowChange::Delete(x) => (Some(schema.encode(&x)?), None),
+            };
+            result.push(AvroRowChange {
+                schema: schema.clone(),
+                before,
+                after,
+            });
+        }
+        Ok(result)
+    }
+}
+
+impl Default for AvroEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn write_value(
+    buffer: &mut Vec<u8>,
+    column: &ColumnInfo,
+    avro_type: &AvroType,
+    value: &ColumnValue,
+) -> Result<(), Error> {
+    match (avro_type, value) {
+        (AvroType::Int | AvroType::Long, ColumnValue::Signed(x)) => write_long(buffer, *x),
+        (AvroType::Int | AvroType::Long, ColumnValue::Unsigned(x)) => write_long(buffer, *x as i64),
+        (AvroType::Decimal { .. }, ColumnValue::Unsigned(x)) => {
+            write_bytes(buffer, &to_unscaled_bytes(&x.to_string(), false))
+        }
+        (AvroType::String, ColumnValue::Enum(x)) => write_bytes(buffer, x.as_bytes()),
+        (AvroType::StringArray, ColumnValue::Set(x)) => {
+            if !x.is_empty() {
+                write_long(buffer, x.len() as i64);
+                x.iter().for_each(|x| write_bytes(buffer, x.as_bytes()));
+            }
+            write_long(buffer, 0);
+        }
+        (_, ColumnValue::Value(value)) => write_mysql_value(buffer, column, avro_type, value)?,
+        (_, value) => return Err(invalid_value(column, avro_type, value)),
+    }
+    Ok(())
+}
+
+fn write_mysql_value(
+    buffer: &mut Vec<u8>,
+    column: &ColumnInfo,
+    avro_type: &AvroType,
+    value: &MySqlValue,
+) -> Result<(), Error> {
+    match (avro_type, value) {
+        (AvroType::Int | AvroType::Long, MySqlValue::Year(x)) => write_long(buffer, *x as i64),
+        (AvroType::Int | AvroType::Long, MySqlValue::Enum(x)) => write_long(buffer, *x as i64),
+        (AvroType::Long, MySqlValue::Set(x)) => write_long(buffer, *x as i64),
+        (AvroType::Long, MySqlValue::Bit(x)) => {
+            // Bits are ordered from the least significant.
+            let value = x
+                .iter()
+                .take(64)
+                .enumerate()
+                .fold(0u64, |value, (i, bit)| value | (*bit as u64) << i);
+            write_long(buffer, value as i64);
+        }
+        (AvroType::Float, MySqlValue::Float(x)) => buffer.extend_from_slice(&x.to_le_bytes()),
+        (AvroType::Double, MySqlValue::Double(x)) => buffer.extend_from_slice(&x.to_le_bytes()),
+        (AvroType::Decimal { scale, .. }, MySqlValue::Decimal(x)) => {
+            let (negative, digits) = to_unscaled_digits(x, *scale as usize);
+            write_bytes(buffer, &to_unscaled_bytes(&digits, negative));
+        }
+        (AvroType::String, MySqlValue::Decimal(x)) => write_bytes(buffer, x.as_bytes()),
+        (AvroType::String, MySqlValue::String(x)) => write_bytes(buffer, x.as_bytes()),
+        (AvroType::String, MySqlValue::Blob(x)) => {
+            write_bytes(buffer, String::from_utf8_lossy(x).as_bytes())
+        }
+        (AvroType::String, MySqlValue::Json(x)) => write_bytes(buffer, x.to_string().as_bytes()),
+        (AvroType::Bytes, MySqlValue::String(x)) => write_bytes(buffer, x.as_bytes()),
+        (AvroType::Bytes, MySqlValue::Blob(x)) => write_bytes(buffer, x),
+        (AvroType::Bytes, MySqlValue::Geometry(x)) => write_bytes(buffer, &x.to_wkb()),
+        (AvroType::Date, MySqlValue::Date(x)) if x.year == 0 => write_long(buffer, 0),
+        (AvroType::Date, MySqlValue::Date(x)) => {
+            let days = days_from_civil(x.year as i64, x.month as i64, x.day as i64);
+            write_long(buffer, days);
+        }
+        (AvroType::TimeMicros, MySqlValue::Time(x)) => {
+            let seconds = x.hour.unsigned_abs() as i64 * 3600 + x.minute as i64 * 60;
+            let micros = ((seconds + x.second as i64) * 1000 + x.millis as i64) * 1000;
+            write_long(buffer, if x.hour < 0 { -micros } else { micros });
+        }
+        (AvroType::TimestampMicros, MySqlValue::DateTime(x)) if x.year == 0 => {
+            write_long(buffer, 0)
+        }
+        // DATETIME values are encoded as UTC.
+        (AvroType::TimestampMicros, MySqlValue::DateTime(x)) => {
+            let seconds = seconds_from_civil(
+                x.year as i64,
+                x.month as i64,
+                x.day as i64,
+                x.hour as i64,
+                x.minute as i64,
+                x.second as i64,
+            );
+            write_long(buffer, (seconds * 1000 + x.millis as i64) * 1000);
+        }
+        (AvroType::TimestampMicros, MySqlValue::Timestamp(x)) => {
+            write_long(buffer, *x as i64 * 1000)
+        }
+        // RowDecoder applies diffs to the before image, so they are left only if it is not logged.
+        (_, MySqlValue::JsonDiff(_)) => {
+            return Err(Error::String(format!(
+                "Partial update of JSON column {} can't be encoded without the before image. Please use binlog_row_image=FULL",
+                column.name
+            )))
+        }
+        (_, value) => {
+            let value = ColumnValue::Value(value.clone());
+            return Err(invalid_value(column, avro_type, &value));
+        }
+    }
+    Ok(())
+}
+
+fn invalid_value(column: &ColumnInfo, avro_type: &AvroType, value: &ColumnValue) -> Error {
+    Error::String(format!(
+        "Value {:?} of column {} can't be encoded as Avro type {:?}",
+        value, column.name, avro_type
+    ))
+}
+
+/// Writes zig-zag encoded variable-length integer.
+fn write_long(buffer: &mut Vec<u8>, value: i64) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, value: &[u8]) {
+    write_long(buffer, value.len() as i64);
+    buffer.extend_from_slice(value);
+}
+
+/// Gets sign and digits of the decimal string scaled to integer.
+fn to_unscaled_digits(value: &str, scale: usize) -> (bool, String) {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, value),
+    };
+    let (integral, fractional) = value.split_once('.').unwrap_or((value, ""));
+    let mut digits = integral.to_string();
+    digits.extend(fractional.chars().chain(std::iter::repeat('0')).take(scale));
+    (negative, digits)
+}
+
+/// Converts decimal digits to big-endian two's complement bytes.
+fn to_unscaled_bytes(digits: &str, negative: bool) -> Vec<u8> {
+    let mut bytes = vec![0u8];
+    for digit in digits.bytes().filter(|x| x.is_ascii_digit()) {
+        let mut carry = (digit - b'0') as u32;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            bytes.insert(0, carry as u8);
+        }
+    }
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    if negative {
+        bytes.iter_mut().for_each(|x| *x = !*x);
+        for byte in bytes.iter_mut().rev() {
+            let (value, overflow) = byte.overflowing_add(1);
+            *byte = value;
+            if !overflow {
+                break;
+            }
+        }
+    }
+    // Removes sign extension bytes
+    while bytes.len() > 1
+        && ((bytes[0] == 0 && bytes[1] & 0x80 == 0) || (bytes[0] == 0xFF && bytes[1] & 0x80 != 0))
+    {
+        bytes.remove(0);
+    }
+    bytes
+}
+
+/// Replaces characters not allowed in Avro names with underscores.
+fn to_avro_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|x| match x.is_ascii_alphanumeric() {
+            true => x,
+            false => '_',
+        })
+        .collect();
+    if !result.starts_with(|x: char| x.is_ascii_alphabetic() || x == '_') {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Gets Parsing Canonical Form of the schema: attributes other than names and types are stripped
+/// and names are replaced by full names.
+/// <a href="https://avro.apache.org/docs/current/specification/#parsing-canonical-form-for-schemas">See more</a>
+fn canonical_form(schema: &Value, namespace: &str) -> String {
+    let object = match schema {
+        Value::Object(x) => x,
+        Value::Array(x) => {
+            let types: Vec<String> = x.iter().map(|x| canonical_form(x, namespace)).collect();
+            return format!("[{}]", types.join(","));
+        }
+        x => return x.to_string(),
+    };
+    let avro_type = match object.get("type") {
+        Some(Value::String(x))
+            if ["record", "enum", "array", "map", "fixed"].contains(&x.as_str()) =>
+        {
+            x
+        }
+        // Primitive type with attributes, e.g. logical type.
+        Some(x) => return canonical_form(x, namespace),
+        None => return schema.to_string(),
+    };
+
+    let mut namespace = namespace.to_string();
+    let mut parts = Vec::new();
+    if let Some(Value::String(name)) = object.get("name") {
+        let full_name = match (name.rsplit_once('.'), object.get("namespace")) {
+            (Some((x, _)), _) => {
+                namespace = x.to_string();
+                name.clone()
+            }
+            (None, Some(Value::String(x))) if !x.is_empty() => {
+                namespace = x.clone();
+                format!("{}.{}", x, name)
+            }
+            (None, _) if !namespace.is_empty() => format!("{}.{}", namespace, name),
+            (None, _) => name.clone(),
+        };
+        parts.push(format!("\"name\":{}", Value::String(full_name)));
+    }
+    parts.push(format!("\"type\":{}", Value::String(avro_type.clone())));
+    if let Some(Value::Array(fields)) = object.get("fields") {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"name\":{},\"type\":{}}}",
+                    x["name"],
+                    canonical_form(&x["type"], &namespace)
+                )
+            })
+            .collect();
+        parts.push(format!("\"fields\":[{}]", fields.join(",")));
+    }
+    for key in ["symbols", "items", "values", "size"] {
+        if let Some(x) = object.get(key) {
+            parts.push(format!("\"{}\":{}", key, canonical_form(x, &namespace)));
+        }
+    }
+    format!("{{{}}}", parts.join(","))
+}
+
+/// Gets CRC-64-AVRO (Rabin) fingerprint.
+fn fingerprint(data: &[u8]) -> u64 {
+    let mut result = EMPTY_FINGERPRINT;
+    for byte in data {
+        let mut value = (result ^ *byte as u64) & 0xFF;
+        for _ in 0..8 {
+            value = (value >> 1) ^ (EMPTY_FINGERPRINT & (value & 1).wrapping_neg());
+        }
+        result = (result >> 8) ^ value;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fingerprint, to_unscaled_bytes, to_unscaled_digits, AvroEncoder, AvroSchema};
+    use crate::constants::column_type::ColumnType;
+    use crate::errors::Error;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::row_events::json_diff::{JsonDiff, JsonDiffOperation};
+    use crate::events::row_events::mysql_value::{DateTime, MySqlValue};
+    use crate::events::row_events::partial_update_rows_event::PartialUpdateRowsEvent;
+    use crate::events::row_events::row_data::{RowData, UpdateRowData};
+    use crate::events::row_events::write_rows_event::WriteRowsEvent;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::metadata::table_metadata::TableMetadata;
+
+    fn create_table(column_types: Vec<u8>, column_metadata: Vec<u16>) -> TableMapEvent {
+        let columns = column_types.len();
+        TableMapEvent {
+            table_id: 3,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types,
+            column_metadata,
+            null_bitmap: (0..columns).map(|i| i > 0).collect(),
+            table_metadata: Some(TableMetadata {
+                column_names: Some(vec![
+                    String::from("id"),
+                    String::from("total"),
+                    String::from("created at"),
+                ]),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn encode_rows() {
+        let table = create_table(
+            vec![
+                ColumnType::Long as u8,
+                ColumnType::NewDecimal as u8,
+                ColumnType::DateTime2 as u8,
+            ],
+            vec![0, 10 | 2 << 8, 0],
+        );
+        let insert = BinlogEvent::WriteRowsEvent(WriteRowsEvent {
+            table_id: 3,
+            flags: 0,
+            columns_number: 3,
+            columns_present: vec![true; 3],
+            rows: vec![RowData::new(vec![
+                Some(MySqlValue::Int(-2i32 as u32)),
+                Some(MySqlValue::Decimal(String::from("-1.5"))),
+                None,
+            ])],
+        });
+
+        let mut encoder = AvroEncoder::new();
+        assert!(encoder
+            .encode(&BinlogEvent::TableMapEvent(table))
+            .unwrap()
+            .is_empty());
+        let changes = encoder.encode(&insert).unwrap();
+        assert_eq!(
+            r#"{"fields":[{"name":"id","type":"int"},{"default":null,"name":"total","type":["null",{"logicalType":"decimal","precision":10,"scale":2,"type":"bytes"}]},{"default":null,"name":"created_at","type":["null",{"logicalType":"timestamp-micros","type":"long"}]}],"name":"orders","namespace":"shop","type":"record"}"#,
+            changes[0].schema.json
+        );
+        // -2, union index 1, 2 bytes of -150, null
+        assert_eq!(
+            Some(vec![0x03, 0x02, 0x04, 0xFF, 0x6A, 0x00]),
+            changes[0].after
+        );
+        assert!(changes[0].before.is_none());
+        let fingerprint = changes[0].schema.fingerprint;
+
+        // Same definition keeps the schema, changed column type generates a new one.
+        let table = create_table(
+            vec![
+                ColumnType::Long as u8,
+                ColumnType::NewDecimal as u8,
+                ColumnType::DateTime2 as u8,
+            ],
+            vec![0, 10 | 2 << 8, 0],
+        );
+        encoder.encode(&BinlogEvent::TableMapEvent(table)).unwrap();
+        assert_eq!(fingerprint, encoder.schema(3).unwrap().fingerprint);
+
+        let table = create_table(
+            vec![
+                ColumnType::LongLong as u8,
+                ColumnType::NewDecimal as u8,
+                ColumnType::DateTime2 as u8,
+            ],
+            vec![0, 10 | 2 << 8, 0],
+        );
+        encoder.encode(&BinlogEvent::TableMapEvent(table)).unwrap();
+        assert_ne!(fingerprint, encoder.schema(3).unwrap().fingerprint);
+
+        let insert = BinlogEvent::WriteRowsEvent(WriteRowsEvent {
+            table_id: 3,
+            flags: 0,
+            columns_number: 3,
+            columns_present: vec![true; 3],
+            rows: vec![RowData::new(vec![
+                Some(MySqlValue::BigInt(1)),
+                None,
+                Some(MySqlValue::DateTime(DateTime {
+                    year: 1970,
+                    month: 1,
+                    day: 1,
+                    hour: 0,
+                    minute: 0,
+                    second: 1,
+                    millis: 0,
+                })),
+            ])],
+        });
+        let changes = encoder.encode(&insert).unwrap();
+        // 1, null, union index 1, 1000000 micros
+        assert_eq!(
+            Some(vec![0x02, 0x00, 0x02, 0x80, 0x89, 0x7A]),
+            changes[0].after
+        );
+    }
+
+    #[test]
+    fn encode_decimals() {
+        let (negative, digits) = to_unscaled_digits("123.4", 2);
+        assert_eq!(vec![0x30, 0x34], to_unscaled_bytes(&digits, negative));
+        let (negative, digits) = to_unscaled_digits("-0.01", 2);
+        assert_eq!(vec![0xFF], to_unscaled_bytes(&digits, negative));
+        assert_eq!(vec![0x00, 0x80], to_unscaled_bytes("128", false));
+        assert_eq!(vec![0x80], to_unscaled_bytes("128", true));
+        assert_eq!(
+            vec![0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            to_unscaled_bytes(&u64::MAX.to_string(), false)
+        );
+    }
+
+    #[test]
+    fn fingerprint_canonical_form() {
+        // Test vector of the Avro specification.
+        assert_eq!(8247732601305521295, fingerprint(br#""int""#) as i64);
+
+        let table = create_table(
+            vec![
+                ColumnType::Long as u8,
+                ColumnType::NewDecimal as u8,
+                ColumnType::DateTime2 as u8,
+            ],
+            vec![0, 10 | 2 << 8, 0],
+        );
+        let schema = AvroSchema::new(&table).unwrap();
+        // Namespace, defaults and logical types are not part of the canonical form.
+        let canonical_form = r#"{"name":"shop.orders","type":"record","fields":[{"name":"id","type":"int"},{"name":"total","type":["null","bytes"]},{"name":"created_at","type":["null","long"]}]}"#;
+        assert_eq!(fingerprint(canonical_form.as_bytes()), schema.fingerprint);
+    }
+
+    #[test]
+    fn json_diff_without_before_image_is_error() {
+        let table = create_table(
+            vec![
+                ColumnType::Long as u8,
+                ColumnType::Json as u8,
+                ColumnType::DateTime2 as u8,
+            ],
+            vec![0, 4, 0],
+        );
+        let diff = JsonDiff {
+            operation: JsonDiffOperation::Remove,
+            path: String::from("$.a"),
+            value: None,
+        };
+        // binlog_row_image=MINIMAL logs only the primary key in the before image.
+        let update = BinlogEvent::MySqlPartialUpdateRowsEvent(PartialUpdateRowsEvent {
+            table_id: 3,
+            flags: 0,
+            columns_number: 3,
+            columns_before_update: vec![true, false, false],
+            columns_after_update: vec![true, true, false],
+            rows: vec![UpdateRowData::new(
+                RowData::new(vec![Some(MySqlValue::Int(1)), None, None]),
+                RowData::new(vec![
+                    Some(MySqlValue::Int(1)),
+                    Some(MySqlValue::JsonDiff(vec![diff])),
+                    None,
+                ]),
+            )],
+        });
+
+        let mut encoder = AvroEncoder::new();
+        encoder.encode(&BinlogEvent::TableMapEvent(table)).unwrap();
+        match encoder.encode(&update) {
+            Err(Error::String(x)) => assert!(x.contains("JSON column total")),
+            _ => panic!("JSON diffs must not be encoded"),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use clap::{Parser, ValueEnum};
+
+use mysql_cdc::errors::Error;
+use mysql_cdc::providers::mariadb::gtid::gtid_list::GtidList;
+use mysql_cdc::providers::mysql::gtid::gtid_set::GtidSet;
+use mysql_cdc::ssl_mode::SslMode;
+use mysql_cdc::table_filter::{TableFilter, TablePattern};
+use mysql_cdc::transaction::TransactionGtid;
+
+use crate::time::parse_datetime;
+
+/// Prints events of MySQL/MariaDB binlog files or of a server binlog stream.
+#[derive(Parser, Debug)]
+#[command(name = "mysql-cdc", version)]
+pub struct Args {
+    /// Binlog files to read. With --host the first file is the binlog file to start streaming from.
+    pub files: Vec<String>,
+
+    /// Streams events from the server instead of reading local files.
+    #[arg(short = 'H', long)]
+    pub host: Option<String>,
+
+    #[arg(short = 'P', long, default_value_t = 3306)]
+    pub port: u16,
+
+    #[arg(short, long, default_value = "root")]
+    pub user: String,
+
+    #[arg(short, long, default_value = "")]
+    pub password: String,
+
+    #[arg(long, value_enum, default_value_t = SslModeArg::Disabled)]
+    pub ssl_mode: SslModeArg,
+
+    /// Waits for new events after the last one like a replica (server id is used).
+    #[arg(long)]
+    pub stop_never: bool,
+
+    #[arg(long, default_value_t = 65535)]
+    pub server_id: u32,
+
+    /// Starts streaming from the GTID position: MySQL GtidSet or MariaDB GtidList.
+    #[arg(long)]
+    pub start_gtid: Option<String>,
+
+    /// Skips events before the position of the first file.
+    #[arg(long)]
+    pub start_position: Option<u64>,
+
+    /// Stops at the first event at or after the position of the last file.
+    #[arg(long)]
+    pub stop_position: Option<u64>,
+
+    /// Skips events before the UTC datetime, e.g. "2023-07-15 10:20:30".
+    #[arg(long, value_parser = parse_datetime)]
+    pub start_datetime: Option<u64>,
+
+    /// Stops at the first event at or after the UTC datetime.
+    #[arg(long, value_parser = parse_datetime)]
+    pub stop_datetime: Option<u64>,
+
+    /// Prints only transactions with the GTIDs: MySQL GtidSet or MariaDB GtidList.
+    #[arg(long)]
+    pub include_gtids: Option<String>,
+
+    /// Skips transactions with the GTIDs: MySQL GtidSet or MariaDB GtidList.
+    #[arg(long)]
+    pub exclude_gtids: Option<String>,
+
+    /// Prints only statements and rows of the database. Supports `*` and `?` wildcards.
+    #[arg(short, long)]
+    pub database: Vec<String>,
+
+    /// Prints only rows of the table, e.g. shop.orders or shop.order_*
+    #[arg(short, long)]
+    pub table: Vec<String>,
+
+    /// Skips rows of the table, e.g. shop.tmp_*
+    #[arg(long)]
+    pub exclude_table: Vec<String>,
+
+    /// Verifies CRC32 checksums of the events.
+    #[arg(long)]
+    pub verify_checksum: bool,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SslModeArg {
+    Disabled,
+    IfAvailable,
+    Require,
+    RequireVerifyCa,
+    RequireVerifyFull,
+}
+
+impl From<SslModeArg> for SslMode {
+    fn from(value: SslModeArg) -> Self {
+        match value {
+            SslModeArg::Disabled => SslMode::Disabled,
+            SslModeArg::IfAvailable => SslMode::IfAvailable,
+            SslModeArg::Require => SslMode::Require,
+            SslModeArg::RequireVerifyCa => SslMode::RequireVerifyCa,
+            SslModeArg::RequireVerifyFull => SslMode::RequireVerifyFull,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One line per event followed by changed rows.
+    Text,
+
+    /// One JSON object per event.
+    Json,
+
+    /// Statements and pseudo-SQL of changed rows like `mysqlbinlog -v`.
+    Sql,
+}
+
+/// GTIDs of --include-gtids and --exclude-gtids.
+pub enum GtidFilter {
+    MySql(GtidSet),
+    MariaDb(GtidList),
+}
+
+impl GtidFilter {
+    /// Parses MySQL GtidSet (uuid:interval) or MariaDB GtidList (domain-server-sequence).
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        match value.contains(':') {
+            true => Ok(GtidFilter::MySql(GtidSet::parse(value)?)),
+            false => Ok(GtidFilter::MariaDb(GtidList::parse(value)?)),
+        }
+    }
+
+    pub fn contains(&self, gtid: &TransactionGtid) -> bool {
+        match (self, gtid) {
+            (GtidFilter::MySql(set), TransactionGtid::MySql(gtid)) => set.contains(gtid),
+            (GtidFilter::MariaDb(list), TransactionGtid::MariaDb(gtid)) => list.contains(gtid),
+            _ => false,
+        }
+    }
+}
+
+impl Args {
+    /// Gets filter of --database, --table and --exclude-table options.
+    pub fn table_filter(&self) -> Result<TableFilter, Error> {
+        let mut filter = TableFilter::default();
+        for database in &self.database {
+            filter.include.push(TablePattern::new(database, "*"));
+        }
+        for table in &self.table {
+            filter.include.push(parse_table(table)?);
+        }
+        for table in &self.exclude_table {
+            filter.exclude.push(parse_table(table)?);
+        }
+        Ok(filter)
+    }
+}
+
+fn parse_table(value: &str) -> Result<TablePattern, Error> {
+    match value.split_once('.') {
+        Some((database, table)) => Ok(TablePattern::new(database, table)),
+        None => Err(Error::String(format!(
+            "Table {} must be qualified with database name",
+            value
+        ))),
+    }
+}
@@ -0,0 +1,155 @@
+mod args;
+mod printer;
+mod time;
+
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use clap::Parser;
+use futures_util::pin_mut;
+use futures_util::stream::StreamExt;
+use mysql_cdc::binlog_client::BinlogClient;
+use mysql_cdc::binlog_options::BinlogOptions;
+use mysql_cdc::binlog_position::BinlogPosition;
+use mysql_cdc::binlog_reader::BinlogReader;
+use mysql_cdc::errors::Error;
+use mysql_cdc::reader_options::ReaderOptions;
+use mysql_cdc::replica_options::ReplicaOptions;
+use mysql_cdc::table_filter::TablePattern;
+
+use args::{Args, GtidFilter};
+use printer::Printer;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped to `head` and similar tools is closed early.
+        Err(Error::IoError(x)) if x.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("mysql-cdc: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<(), Error> {
+    if args.files.is_empty() && args.host.is_none() {
+        return Err(Error::String(String::from(
+            "Specify binlog files to read or --host to stream from",
+        )));
+    }
+
+    let mut printer = Printer::new(BufWriter::new(io::stdout().lock()), args.output);
+    printer.start_datetime = args.start_datetime;
+    printer.stop_datetime = args.stop_datetime;
+    printer.include_gtids = args
+        .include_gtids
+        .as_deref()
+        .map(GtidFilter::parse)
+        .transpose()?;
+    printer.exclude_gtids = args
+        .exclude_gtids
+        .as_deref()
+        .map(GtidFilter::parse)
+        .transpose()?;
+    printer.databases = args
+        .database
+        .iter()
+        .map(|x| TablePattern::new(x, "*"))
+        .collect();
+    printer.table_filter = args.table_filter()?;
+
+    match &args.host {
+        Some(_) => replicate(&args, &mut printer).await?,
+        None => read_files(&args, &mut printer)?,
+    }
+    printer.flush()
+}
+
+fn read_files<W: Write>(args: &Args, printer: &mut Printer<W>) -> Result<(), Error> {
+    let first = args.files.first().map(|x| file_name(x));
+    let last = args.files.last().map(|x| file_name(x));
+    printer.start_position = args
+        .start_position
+        .zip(first)
+        .map(|(position, file)| BinlogPosition::new(file, position as u32));
+    printer.stop_position = args
+        .stop_position
+        .zip(last)
+        .map(|(position, file)| BinlogPosition::new(file, position as u32));
+
+    for path in &args.files {
+        let options = ReaderOptions {
+            filename: file_name(path),
+            verify_checksum: args.verify_checksum,
+            ..Default::default()
+        };
+        printer.set_filename(&options.filename);
+        let reader = BinlogReader::with_options(File::open(path)?, options)?;
+        for result in reader {
+            let (header, event) = result?;
+            if !printer.handle(&header, &event)? {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn replicate<W: Write>(args: &Args, printer: &mut Printer<W>) -> Result<(), Error> {
+    let binlog = match (&args.start_gtid, args.files.first()) {
+        (Some(gtid), _) => match GtidFilter::parse(gtid)? {
+            GtidFilter::MySql(x) => BinlogOptions::from_mysql_gtid(x),
+            GtidFilter::MariaDb(x) => BinlogOptions::from_mariadb_gtid(x),
+        },
+        (None, Some(file)) => {
+            let position = args.start_position.unwrap_or(4) as u32;
+            BinlogOptions::from_position(file.clone(), position)
+        }
+        (None, None) => BinlogOptions::from_start(),
+    };
+    if let Some(file) = args.files.first() {
+        printer.stop_position = args
+            .stop_position
+            .map(|x| BinlogPosition::new(file.clone(), x as u32));
+    }
+
+    let options = ReplicaOptions {
+        hostname: args.host.clone().unwrap_or_default(),
+        port: args.port,
+        username: args.user.clone(),
+        password: args.password.clone(),
+        ssl_mode: args.ssl_mode.into(),
+        server_id: args.server_id,
+        blocking: args.stop_never,
+        verify_checksum: args.verify_checksum,
+        binlog,
+        ..Default::default()
+    };
+    let mut client = BinlogClient::new(options);
+    let events = client.replicate().await?;
+    pin_mut!(events);
+    while let Some(result) = events.next().await {
+        let (header, event) = result?;
+        if !printer.handle(&header, &event)? {
+            break;
+        }
+        // Events are printed as soon as they arrive when waiting for new ones.
+        if args.stop_never {
+            printer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Gets binlog file name without directory, as it is logged in RotateEvent.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
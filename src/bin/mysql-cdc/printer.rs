@@ -0,0 +1,542 @@
+use std::io::Write;
+
+use mysql_cdc::binlog_position::BinlogPosition;
+use mysql_cdc::errors::Error;
+use mysql_cdc::events::binlog_event::BinlogEvent;
+use mysql_cdc::events::event_header::EventHeader;
+use mysql_cdc::events::row_events::mysql_value::MySqlValue;
+use mysql_cdc::events::row_events::row_decoder::{RowChange, RowDecoder};
+use mysql_cdc::events::row_events::typed_row::{ColumnValue, TypedRow};
+use mysql_cdc::events::table_map_event::TableMapEvent;
+use mysql_cdc::table_filter::{TableFilter, TablePattern};
+use mysql_cdc::transaction::TransactionGtid;
+
+use crate::args::{GtidFilter, OutputFormat};
+use crate::time::format_datetime;
+
+/// Filters binlog events and prints them in the output format.
+pub struct Printer<W: Write> {
+    /// Gets the output format.
+    pub format: OutputFormat,
+
+    /// Skips events of the file before the position.
+    pub start_position: Option<BinlogPosition>,
+
+    /// Stops at the first event of the file at or after the position.
+    pub stop_position: Option<BinlogPosition>,
+
+    /// Skips events created before the time in seconds from Unix.
+    pub start_datetime: Option<u64>,
+
+    /// Stops at the first event created at or after the time in seconds from Unix.
+    pub stop_datetime: Option<u64>,
+
+    /// Prints only transactions with the GTIDs.
+    pub include_gtids: Option<GtidFilter>,
+
+    /// Skips transactions with the GTIDs.
+    pub exclude_gtids: Option<GtidFilter>,
+
+    /// Prints only statements of the databases. All statements are printed if empty.
+    pub databases: Vec<TablePattern>,
+
+    /// Prints only rows of the tables.
+    pub table_filter: TableFilter,
+
+    output: W,
+    filename: String,
+    decoder: RowDecoder,
+    skip_transaction: bool,
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(output: W, format: OutputFormat) -> Self {
+        Self {
+            format,
+            start_position: None,
+            stop_position: None,
+            start_datetime: None,
+            stop_datetime: None,
+            include_gtids: None,
+            exclude_gtids: None,
+            databases: Vec::new(),
+            table_filter: TableFilter::default(),
+            output,
+            filename: String::new(),
+            decoder: RowDecoder::new(),
+            skip_transaction: false,
+        }
+    }
+
+    /// Sets name of the binlog file the next events are read from.
+    pub fn set_filename(&mut self, filename: &str) {
+        self.filename = filename.to_string();
+    }
+
+    /// Prints the event if it passes the filters.
+    /// Returns false when the stop position or datetime is reached.
+    pub fn handle(&mut self, header: &EventHeader, event: &BinlogEvent) -> Result<bool, Error> {
+        let position = header
+            .next_event_position
+            .saturating_sub(header.event_length);
+        if let Some(stop) = &self.stop_position {
+            if stop.filename == self.filename && position >= stop.position {
+                return Ok(false);
+            }
+        }
+        if let Some(stop) = self.stop_datetime {
+            if header.timestamp as u64 >= stop {
+                return Ok(false);
+            }
+        }
+
+        // Table maps are cached even for skipped events to decode the following rows.
+        let changes = self.decoder.decode(event)?;
+        let skip_event = self.skip_event(header, event, position);
+        if let BinlogEvent::RotateEvent(x) = event {
+            self.filename = x.binlog_filename.clone();
+        }
+        if skip_event {
+            return Ok(true);
+        }
+
+        match self.format {
+            OutputFormat::Text => self.print_text(header, event, position, &changes)?,
+            OutputFormat::Json => self.print_json(header, event, position, &changes)?,
+            OutputFormat::Sql => self.print_sql(header, event, position, &changes)?,
+        }
+        Ok(true)
+    }
+
+    /// Writes buffered output.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn skip_event(&mut self, header: &EventHeader, event: &BinlogEvent, position: u32) -> bool {
+        if let Some(start) = &self.start_position {
+            if start.filename == self.filename && position < start.position {
+                return true;
+            }
+        }
+        if let Some(start) = self.start_datetime {
+            if (header.timestamp as u64) < start {
+                return true;
+            }
+        }
+
+        match event {
+            BinlogEvent::MySqlGtidEvent(x) => {
+                self.skip_transaction = self.skip_gtid(&TransactionGtid::MySql(x.gtid.clone()));
+            }
+            BinlogEvent::MariaDbGtidEvent(x) => {
+                self.skip_transaction = self.skip_gtid(&TransactionGtid::MariaDb(x.gtid.clone()));
+            }
+            BinlogEvent::RotateEvent(_)
+            | BinlogEvent::FormatDescriptionEvent(_)
+            | BinlogEvent::HeartbeatEvent(_)
+            | BinlogEvent::MySqlPrevGtidsEvent(_)
+            | BinlogEvent::MariaDbGtidListEvent(_) => self.skip_transaction = false,
+            _ => {}
+        }
+        if self.skip_transaction {
+            return true;
+        }
+
+        match event {
+            BinlogEvent::QueryEvent(x) if !is_transaction_boundary(&x.sql_statement) => {
+                !self.matches_database(&x.database_name)
+            }
+            _ => match self.row_event_table(event) {
+                Some(table) => !self
+                    .table_filter
+                    .matches(&table.database_name, &table.table_name),
+                None => false,
+            },
+        }
+    }
+
+    fn skip_gtid(&self, gtid: &TransactionGtid) -> bool {
+        let included = match &self.include_gtids {
+            Some(x) => x.contains(gtid),
+            None => true,
+        };
+        let excluded = match &self.exclude_gtids {
+            Some(x) => x.contains(gtid),
+            None => false,
+        };
+        !included || excluded
+    }
+
+    fn matches_database(&self, database: &str) -> bool {
+        self.databases.is_empty() || self.databases.iter().any(|x| x.matches(database, ""))
+    }
+
+    fn row_event_table(&self, event: &BinlogEvent) -> Option<&TableMapEvent> {
+        let table_id = match event {
+            BinlogEvent::TableMapEvent(x) => x.table_id,
+            BinlogEvent::WriteRowsEvent(x) => x.table_id,
+            BinlogEvent::UpdateRowsEvent(x) => x.table_id,
+            BinlogEvent::DeleteRowsEvent(x) => x.table_id,
+            BinlogEvent::MySqlPartialUpdateRowsEvent(x) => x.table_id,
+            _ => return None,
+        };
+        self.decoder.table(table_id)
+    }
+
+    fn print_text(
+        &mut self,
+        header: &EventHeader,
+        event: &BinlogEvent,
+        position: u32,
+        changes: &[RowChange],
+    ) -> Result<(), Error> {
+        let details = match event {
+            BinlogEvent::QueryEvent(x) => format!(" {}", x.sql_statement),
+            BinlogEvent::RowsQueryEvent(x) => format!(" {}", x.query),
+            BinlogEvent::RotateEvent(x) => {
+                format!(" {}:{}", x.binlog_filename, x.binlog_position)
+            }
+            BinlogEvent::XidEvent(x) => format!(" xid={}", x.xid),
+            BinlogEvent::MySqlGtidEvent(x) => format!(" {}", x.gtid),
+            BinlogEvent::MariaDbGtidEvent(x) => format!(" {}", x.gtid),
+            _ => match self.row_event_table(event) {
+                Some(x) => format!(" {}.{}", x.database_name, x.table_name),
+                None => String::new(),
+            },
+        };
+        writeln!(
+            self.output,
+            "{}:{} {} server_id={} {}{}",
+            self.filename,
+            position,
+            format_datetime(header.timestamp as u64),
+            header.server_id,
+            event_name(event),
+            details
+        )?;
+
+        for change in changes {
+            match change {
+                RowChange::Insert(row) => writeln!(self.output, "  after: {}", format_row(row))?,
+                RowChange::Update { before, after } => {
+                    writeln!(self.output, "  before: {}", format_row(before))?;
+                    writeln!(self.output, "  after: {}", format_row(after))?;
+                }
+                RowChange::Delete(row) => writeln!(self.output, "  before: {}", format_row(row))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn print_json(
+        &mut self,
+        header: &EventHeader,
+        event: &BinlogEvent,
+        position: u32,
+        changes: &[RowChange],
+    ) -> Result<(), Error> {
+        let value = serde_json::json!({
+            "file": self.filename,
+            "position": position,
+            "header": header,
+            "event": event,
+            "changes": changes,
+        });
+        writeln!(self.output, "{}", value)?;
+        Ok(())
+    }
+
+    fn print_sql(
+        &mut self,
+        header: &EventHeader,
+        event: &BinlogEvent,
+        position: u32,
+        changes: &[RowChange],
+    ) -> Result<(), Error> {
+        writeln!(self.output, "# at {}", position)?;
+        // mysqlbinlog prints time in short YYMMDD HH:MM:SS form.
+        writeln!(
+            self.output,
+            "#{} server id {}  end_log_pos {} \t{}",
+            format_datetime(header.timestamp as u64)[2..].replace('-', ""),
+            header.server_id,
+            header.next_event_position,
+            event_name(event)
+        )?;
+
+        match event {
+            BinlogEvent::QueryEvent(x) => {
+                if !x.database_name.is_empty() {
+                    writeln!(self.output, "use `{}`/*!*/;", x.database_name)?;
+                }
+                writeln!(self.output, "{}\n/*!*/;", x.sql_statement)?;
+            }
+            BinlogEvent::RowsQueryEvent(x) => writeln!(self.output, "# {}", x.query)?,
+            BinlogEvent::XidEvent(x) => writeln!(self.output, "COMMIT/*!*/; # xid={}", x.xid)?,
+            BinlogEvent::MySqlGtidEvent(x) => {
+                writeln!(self.output, "SET @@SESSION.GTID_NEXT= '{}'/*!*/;", x.gtid)?
+            }
+            BinlogEvent::MariaDbGtidEvent(x) => {
+                writeln!(
+                    self.output,
+                    "/*!100001 SET @@session.gtid_seq_no={}*//*!*/;",
+                    x.gtid.sequence
+                )?;
+                writeln!(self.output, "# GTID {}", x.gtid)?;
+            }
+            BinlogEvent::RotateEvent(x) => writeln!(
+                self.output,
+                "# Rotate to {}  pos: {}",
+                x.binlog_filename, x.binlog_position
+            )?,
+            _ => {}
+        }
+
+        let table = match self.row_event_table(event) {
+            Some(x) => format!("`{}`.`{}`", x.database_name, x.table_name),
+            None => return Ok(()),
+        };
+        let mut lines = Vec::new();
+        for change in changes {
+            match change {
+                RowChange::Insert(row) => {
+                    lines.push(format!("### INSERT INTO {}", table));
+                    lines.push(String::from("### SET"));
+                    push_sql_columns(&mut lines, row);
+                }
+                RowChange::Update { before, after } => {
+                    lines.push(format!("### UPDATE {}", table));
+                    lines.push(String::from("### WHERE"));
+                    push_sql_columns(&mut lines, before);
+                    lines.push(String::from("### SET"));
+                    push_sql_columns(&mut lines, after);
+                }
+                RowChange::Delete(row) => {
+                    lines.push(format!("### DELETE FROM {}", table));
+                    lines.push(String::from("### WHERE"));
+                    push_sql_columns(&mut lines, row);
+                }
+            }
+        }
+        for line in lines {
+            writeln!(self.output, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_transaction_boundary(sql: &str) -> bool {
+    sql.eq_ignore_ascii_case("BEGIN") || sql.eq_ignore_ascii_case("COMMIT")
+}
+
+fn event_name(event: &BinlogEvent) -> &'static str {
+    match event {
+        BinlogEvent::UnknownEvent => "UnknownEvent",
+        BinlogEvent::DeleteRowsEvent(_) => "DeleteRowsEvent",
+        BinlogEvent::UpdateRowsEvent(_) => "UpdateRowsEvent",
+        BinlogEvent::WriteRowsEvent(_) => "WriteRowsEvent",
+        BinlogEvent::SkippedRowsEvent(_) => "SkippedRowsEvent",
+        BinlogEvent::XidEvent(_) => "XidEvent",
+        BinlogEvent::IntVarEvent(_) => "IntVarEvent",
+        BinlogEvent::UserVarEvent(_) => "UserVarEvent",
+        BinlogEvent::QueryEvent(_) => "QueryEvent",
+        BinlogEvent::TableMapEvent(_) => "TableMapEvent",
+        BinlogEvent::RotateEvent(_) => "RotateEvent",
+        BinlogEvent::RowsQueryEvent(_) => "RowsQueryEvent",
+        BinlogEvent::HeartbeatEvent(_) => "HeartbeatEvent",
+        BinlogEvent::FormatDescriptionEvent(_) => "FormatDescriptionEvent",
+        BinlogEvent::MySqlGtidEvent(_) => "GtidEvent",
+        BinlogEvent::MySqlPrevGtidsEvent(_) => "PreviousGtidsEvent",
+        BinlogEvent::MySqlPartialUpdateRowsEvent(_) => "PartialUpdateRowsEvent",
+        BinlogEvent::MariaDbGtidEvent(_) => "GtidEvent",
+        BinlogEvent::MariaDbGtidListEvent(_) => "GtidListEvent",
+    }
+}
+
+fn format_row(row: &TypedRow) -> String {
+    let columns: Vec<String> = row
+        .columns
+        .iter()
+        .map(|x| format!("{}={}", x.name, format_value(x.value.as_ref())))
+        .collect();
+    columns.join(", ")
+}
+
+fn push_sql_columns(lines: &mut Vec<String>, row: &TypedRow) {
+    for column in &row.columns {
+        lines.push(format!(
+            "###   {}={}",
+            column.name,
+            format_value(column.value.as_ref())
+        ));
+    }
+}
+
+/// Formats the value as SQL literal.
+fn format_value(value: Option<&ColumnValue>) -> String {
+    let value = match value {
+        Some(ColumnValue::Signed(x)) => return x.to_string(),
+        Some(ColumnValue::Unsigned(x)) => return x.to_string(),
+        Some(ColumnValue::Enum(x)) => return quote(x),
+        Some(ColumnValue::Set(x)) => return quote(&x.join(",")),
+        Some(ColumnValue::Value(x)) => x,
+        None => return String::from("NULL"),
+    };
+    match value {
+        MySqlValue::TinyInt(x) => x.to_string(),
+        MySqlValue::SmallInt(x) => x.to_string(),
+        MySqlValue::MediumInt(x) => x.to_string(),
+        MySqlValue::Int(x) => x.to_string(),
+        MySqlValue::BigInt(x) => x.to_string(),
+        MySqlValue::Float(x) => x.to_string(),
+        MySqlValue::Double(x) => x.to_string(),
+        MySqlValue::Decimal(x) => x.clone(),
+        MySqlValue::String(x) => quote(x),
+        // Bits are stored starting from the least significant one.
+        MySqlValue::Bit(x) => {
+            let bits: String = x.iter().rev().map(|b| if *b { '1' } else { '0' }).collect();
+            format!("b'{}'", bits)
+        }
+        MySqlValue::Enum(x) => x.to_string(),
+        MySqlValue::Set(x) => x.to_string(),
+        MySqlValue::Blob(x) if x.is_empty() => String::from("''"),
+        MySqlValue::Blob(x) => {
+            let hex: String = x.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("0x{}", hex)
+        }
+        MySqlValue::Year(x) => x.to_string(),
+        MySqlValue::Date(x) => format!("'{:04}-{:02}-{:02}'", x.year, x.month, x.day),
+        MySqlValue::Time(x) => {
+            let sign = if x.hour < 0 { "-" } else { "" };
+            format!(
+                "'{}{:02}:{:02}:{:02}{}'",
+                sign,
+                x.hour.unsigned_abs(),
+                x.minute,
+                x.second,
+                format_fraction(x.millis)
+            )
+        }
+        MySqlValue::DateTime(x) => format!(
+            "'{:04}-{:02}-{:02} {:02}:{:02}:{:02}{}'",
+            x.year,
+            x.month,
+            x.day,
+            x.hour,
+            x.minute,
+            x.second,
+            format_fraction(x.millis)
+        ),
+        MySqlValue::Timestamp(x) => format!(
+            "'{}{}'",
+            format_datetime(x / 1000),
+            format_fraction((x % 1000) as u32)
+        ),
+        MySqlValue::Json(x) => quote(&x.to_string()),
+        MySqlValue::JsonDiff(x) => {
+            let diffs: Vec<String> = x
+                .iter()
+                .map(|d| match &d.value {
+                    Some(value) => format!("{:?}({}, {})", d.operation, d.path, value),
+                    None => format!("{:?}({})", d.operation, d.path),
+                })
+                .collect();
+            quote(&diffs.join(", "))
+        }
+        MySqlValue::Geometry(x) => format!("ST_GeomFromText('{}', {})", x.to_wkt(), x.srid),
+    }
+}
+
+fn format_fraction(millis: u32) -> String {
+    match millis {
+        0 => String::new(),
+        x => format!(".{:03}", x),
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_cdc::events::binlog_event::BinlogEvent;
+    use mysql_cdc::events::event_header::EventHeader;
+    use mysql_cdc::events::query_event::QueryEvent;
+    use mysql_cdc::events::row_events::mysql_value::{MySqlValue, Time};
+    use mysql_cdc::events::row_events::typed_row::ColumnValue;
+    use mysql_cdc::table_filter::TablePattern;
+
+    use super::{format_value, Printer};
+    use crate::args::OutputFormat;
+    use crate::time::parse_datetime;
+
+    #[test]
+    fn format_sql_values() {
+        let value = |x: MySqlValue| format_value(Some(&ColumnValue::Value(x)));
+
+        assert_eq!("NULL", format_value(None));
+        assert_eq!("-5", format_value(Some(&ColumnValue::Signed(-5))));
+        assert_eq!("'it\\'s'", value(MySqlValue::String(String::from("it's"))));
+        assert_eq!("0x0AFF", value(MySqlValue::Blob(vec![0x0a, 0xff])));
+        assert_eq!("b'110'", value(MySqlValue::Bit(vec![false, true, true])));
+        assert_eq!(
+            "'-01:02:03.500'",
+            value(MySqlValue::Time(Time {
+                hour: -1,
+                minute: 2,
+                second: 3,
+                millis: 500
+            }))
+        );
+        assert_eq!(
+            "'2023-07-15 10:20:30'",
+            value(MySqlValue::Timestamp(1689416430000))
+        );
+        assert_eq!(Ok(1689416430), parse_datetime("2023-07-15 10:20:30"));
+    }
+
+    #[test]
+    fn filter_and_print_queries() {
+        let mut printer = Printer::new(Vec::new(), OutputFormat::Sql);
+        printer.set_filename("mysql-bin.000001");
+        printer.databases.push(TablePattern::new("shop", "*"));
+        printer.stop_datetime = parse_datetime("2023-07-15 10:21:00").ok();
+
+        let mut header = EventHeader {
+            timestamp: 1689416430,
+            event_type: 2,
+            server_id: 1,
+            event_length: 100,
+            next_event_position: 350,
+            event_flags: 0,
+        };
+        let query = |database: &str| {
+            BinlogEvent::QueryEvent(QueryEvent {
+                thread_id: 5,
+                duration: 0,
+                error_code: 0,
+                status_variables: Vec::new(),
+                status: Default::default(),
+                database_name: database.to_string(),
+                sql_statement: String::from("DROP TABLE t"),
+                schema_change: None,
+            })
+        };
+        assert!(printer.handle(&header, &query("shop")).unwrap());
+        assert!(printer.handle(&header, &query("crm")).unwrap());
+        header.timestamp += 60;
+        assert!(!printer.handle(&header, &query("shop")).unwrap());
+
+        assert_eq!(
+            "# at 250\n\
+             #230715 10:20:30 server id 1  end_log_pos 350 \tQueryEvent\n\
+             use `shop`/*!*/;\n\
+             DROP TABLE t\n\
+             /*!*/;\n",
+            String::from_utf8(printer.output).unwrap()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use mysql_cdc::calendar::{civil_from_days, seconds_from_civil};
+
+/// Parses UTC datetime `YYYY-MM-DD HH:MM:SS` to seconds from Unix epoch.
+pub fn parse_datetime(value: &str) -> Result<u64, String> {
+    let error = || format!("Invalid datetime {}, expected YYYY-MM-DD HH:MM:SS", value);
+    let (date, time) = value
+        .trim()
+        .split_once([' ', 'T'])
+        .unwrap_or((value.trim(), "00:00:00"));
+
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|x| x.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| error())?;
+    let time: Vec<i64> = time
+        .split(':')
+        .map(|x| x.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| error())?;
+    if date.len() != 3 || time.len() != 3 {
+        return Err(error());
+    }
+
+    let seconds = seconds_from_civil(date[0], date[1], date[2], time[0], time[1], time[2]);
+    u64::try_from(seconds).map_err(|_| error())
+}
+
+/// Formats seconds from Unix epoch as UTC `YYYY-MM-DD HH:MM:SS`.
+pub fn format_datetime(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let time = seconds % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,406 @@
+use crate::binlog_client::{BinlogClient, RawEvent};
+use crate::binlog_options::BinlogOptions;
+use crate::binlog_position::compare_filenames;
+use crate::binlog_reader::MAGIC_NUMBER;
+use crate::constants::{EVENT_HEADER_SIZE, FIRST_EVENT_POSITION};
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use futures_util::pin_mut;
+use futures_util::StreamExt;
+use std::cmp::Ordering;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Flag of events generated for the replication stream that are not stored in the binlog.
+const LOG_EVENT_ARTIFICIAL_F: u16 = 0x20;
+
+/// Mirrors server binlog files to a local directory like `mysqlbinlog --raw --stop-never`.
+/// Files are named after the server binlog files and contain the events byte for byte.
+/// The directory must contain only files written by the backup.
+pub struct BinlogBackup {
+    directory: PathBuf,
+    file: Option<BufWriter<File>>,
+    filename: String,
+    position: u32,
+}
+
+impl BinlogBackup {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+            file: None,
+            filename: String::new(),
+            position: 0,
+        }
+    }
+
+    /// Gets name of the binlog file events are written to.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Gets position after the last written event.
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Gets position after the last complete event in the newest local file.
+    /// Incomplete event left by a crash is truncated. Returns None if the directory has no files.
+    pub fn resume_position(&self) -> Result<Option<BinlogOptions>, Error> {
+        let mut newest: Option<String> = None;
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_file()
+                && newest
+                    .as_ref()
+                    .map_or(true, |x| compare_filenames(&name, x) == Ordering::Greater)
+            {
+                newest = Some(name);
+            }
+        }
+        let filename = match newest {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        let path = self.directory.join(&filename);
+        let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+        let length = file.metadata()?.len();
+        let position = find_end_of_events(&mut file, length)?;
+        if position < length {
+            file.set_len(position)?;
+            file.sync_all()?;
+        }
+
+        let position = position.max(FIRST_EVENT_POSITION as u64) as u32;
+        Ok(Some(BinlogOptions::from_position(filename, position)))
+    }
+
+    /// Replicates events from the position of the local files and writes them until the stream ends.
+    /// Written events are committed to the client, so reconnect resumes after them.
+    /// Enable blocking mode in ReplicaOptions to wait for new events.
+    pub async fn run(&mut self, client: &mut BinlogClient) -> Result<(), Error> {
+        if let Some(binlog) = self.resume_position()? {
+            client.options.binlog = binlog;
+        }
+
+        let events = client.replicate_raw().await?;
+        pin_mut!(events);
+        while let Some(result) = events.next().await {
+            let raw = result?;
+            self.write(&raw)?;
+            client.commit(&raw.header, &raw.event)?;
+        }
+        self.sync()
+    }
+
+    /// Writes the event to the file of the server binlog it belongs to.
+    /// Heartbeats and other events generated for the replication stream are skipped.
+    /// Events already written to the file, e.g. replayed after reconnect, are skipped.
+    /// The file is synced to disk at the end of transactions and files.
+    pub fn write(&mut self, raw: &RawEvent) -> Result<(), Error> {
+        let artificial = is_artificial(&raw.header);
+        match &raw.event {
+            BinlogEvent::HeartbeatEvent(_) => return Ok(()),
+            // Names the file at the beginning of the stream.
+            BinlogEvent::RotateEvent(x) if artificial => {
+                if self.filename != x.binlog_filename {
+                    self.open(&x.binlog_filename)?;
+                }
+                return Ok(());
+            }
+            // Sent again when replication starts in the middle of the file.
+            BinlogEvent::FormatDescriptionEvent(_)
+                if self.position > FIRST_EVENT_POSITION as u32 =>
+            {
+                return Ok(())
+            }
+            _ if artificial => return Ok(()),
+            _ => {}
+        }
+
+        let file = match &mut self.file {
+            Some(x) => x,
+            None => {
+                return Err(Error::String(
+                    "Event is received before RotateEvent naming the binlog file".to_string(),
+                ))
+            }
+        };
+        // Reconnect restarts from the committed position that may precede TableMapEvent.
+        if raw.header.next_event_position <= self.position {
+            return Ok(());
+        }
+        if raw.header.next_event_position != self.position.wrapping_add(raw.header.event_length) {
+            return Err(Error::String(format!(
+                "Event ending at {} does not follow position {} of {}",
+                raw.header.next_event_position, self.position, self.filename
+            )));
+        }
+        file.write_all(&raw.data)?;
+        self.position = raw.header.next_event_position;
+
+        match &raw.event {
+            BinlogEvent::RotateEvent(x) => {
+                self.sync()?;
+                self.open(&x.binlog_filename)?;
+            }
+            BinlogEvent::XidEvent(_) => self.sync()?,
+            BinlogEvent::QueryEvent(x) if x.sql_statement != "BEGIN" => self.sync()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Flushes written events and syncs the file to disk.
+    pub fn sync(&mut self) -> Result<(), Error> {
+        if let Some(file) = &mut self.file {
+            file.flush()?;
+            file.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Opens the file for appending. New or empty file is started with the magic number.
+    fn open(&mut self, filename: &str) -> Result<(), Error> {
+        if Path::new(filename).file_name() != Some(filename.as_ref()) {
+            return Err(Error::String(format!(
+                "Invalid binlog file name {}",
+                filename
+            )));
+        }
+        self.sync()?;
+
+        let path = self.directory.join(filename);
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut length = file.metadata()?.len();
+        if length < FIRST_EVENT_POSITION as u64 {
+            file.set_len(0)?;
+            file.write_all(&MAGIC_NUMBER)?;
+            length = FIRST_EVENT_POSITION as u64;
+        }
+
+        self.file = Some(BufWriter::new(file));
+        self.filename = filename.to_string();
+        self.position = length as u32;
+        Ok(())
+    }
+}
+
+fn is_artificial(header: &EventHeader) -> bool {
+    header.event_flags & LOG_EVENT_ARTIFICIAL_F != 0 || header.next_event_position == 0
+}
+
+/// Gets position after the last complete event of the file.
+fn find_end_of_events(file: &mut File, length: u64) -> Result<u64, Error> {
+    let mut magic_number = [0; FIRST_EVENT_POSITION];
+    if length < FIRST_EVENT_POSITION as u64 {
+        return Ok(0);
+    }
+    file.read_exact(&mut magic_number)?;
+    if magic_number != MAGIC_NUMBER {
+        return Err(Error::String("Invalid binary log file header".to_string()));
+    }
+
+    let mut position = FIRST_EVENT_POSITION as u64;
+    let mut header = [0; EVENT_HEADER_SIZE];
+    while position + EVENT_HEADER_SIZE as u64 <= length {
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut header)?;
+        let event_length = EventHeader::parse(&header)?.event_length as u64;
+        if event_length < EVENT_HEADER_SIZE as u64 || position + event_length > length {
+            break;
+        }
+        position += event_length;
+    }
+    Ok(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    use super::BinlogBackup;
+    use crate::binlog_client::{BinlogClient, RawEvent};
+    use crate::binlog_options::BinlogOptions;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::event_parser::EventParser;
+    use crate::events::rotate_event::RotateEvent;
+    use crate::fake_server::{
+        serve_replication_setup, write_eof, write_event, write_xid_event, FakeServer,
+    };
+    use crate::packet_channel::PacketChannel;
+    use crate::reconnect_policy::ReconnectPolicy;
+    use crate::replica_options::ReplicaOptions;
+    use crate::starting_strategy::StartingStrategy;
+    use crate::test_binlogs::{create_binlog, create_directory};
+
+    /// Writes two transactions followed by RotateEvent.
+    fn create_rotated_binlog() -> Vec<u8> {
+        let mut writer = create_binlog(&[1, 2]);
+        writer.rotate("mysql-bin.000002", Vec::new()).unwrap()
+    }
+
+    fn split_events(binlog: &[u8]) -> Vec<RawEvent> {
+        let mut parser = EventParser::new();
+        let mut events = Vec::new();
+        let mut position = 4;
+        while position < binlog.len() {
+            let header = EventHeader::parse(&binlog[position..]).unwrap();
+            let end = position + header.event_length as usize;
+            let event = parser
+                .parse_event(&header, &binlog[position + 19..end])
+                .unwrap();
+            let data = binlog[position..end].to_vec();
+            events.push(RawEvent {
+                header,
+                event,
+                data,
+            });
+            position = end;
+        }
+        events
+    }
+
+    fn fake_rotate(filename: &str) -> RawEvent {
+        let header = EventHeader {
+            timestamp: 0,
+            event_type: 4,
+            server_id: 1,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0x20,
+        };
+        let event = BinlogEvent::RotateEvent(RotateEvent {
+            binlog_filename: filename.to_string(),
+            binlog_position: 4,
+        });
+        RawEvent {
+            header,
+            event,
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn mirror_server_files() {
+        let directory = create_directory("backup_mirror");
+        let binlog = create_rotated_binlog();
+
+        let events = split_events(&binlog);
+        let mut backup = BinlogBackup::new(&directory);
+        backup.write(&fake_rotate("mysql-bin.000001")).unwrap();
+        backup.write(&events[0]).unwrap();
+        backup.write(&events[1]).unwrap();
+        // Replayed event is not written twice.
+        backup.write(&events[1]).unwrap();
+        for event in &events[2..] {
+            backup.write(event).unwrap();
+        }
+        backup.write(&fake_rotate("mysql-bin.000002")).unwrap();
+        backup.sync().unwrap();
+
+        let first = fs::read(directory.join("mysql-bin.000001")).unwrap();
+        let second = fs::read(directory.join("mysql-bin.000002")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(binlog, first);
+        assert_eq!(vec![0xfe, 0x62, 0x69, 0x6e], second);
+        assert_eq!("mysql-bin.000002", backup.filename());
+        assert_eq!(4, backup.position());
+    }
+
+    #[test]
+    fn resume_truncates_incomplete_event() {
+        let directory = create_directory("backup_resume");
+        let binlog = create_rotated_binlog();
+        let mut incomplete = binlog.clone();
+        incomplete.extend_from_slice(&binlog[4..15]);
+        fs::write(directory.join("mysql-bin.000001"), &binlog).unwrap();
+        fs::write(directory.join("mysql-bin.000002"), &incomplete).unwrap();
+
+        let backup = BinlogBackup::new(&directory);
+        let binlog_options = backup.resume_position().unwrap().unwrap();
+        let length = fs::metadata(directory.join("mysql-bin.000002"))
+            .unwrap()
+            .len();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            StartingStrategy::FromPosition,
+            binlog_options.starting_strategy
+        );
+        assert_eq!("mysql-bin.000002", binlog_options.filename);
+        assert_eq!(binlog.len() as u32, binlog_options.position);
+        assert_eq!(binlog.len() as u64, length);
+    }
+
+    async fn write_fake_rotate(channel: &mut PacketChannel, filename: &str) {
+        let mut rotate = fake_rotate(filename);
+        let payload = match &rotate.event {
+            BinlogEvent::RotateEvent(x) => x.serialize().unwrap(),
+            _ => unreachable!(),
+        };
+        rotate.header.event_length = 19 + payload.len() as u32;
+        write_event(channel, &rotate.header, &payload).await;
+    }
+
+    #[tokio::test]
+    async fn reconnect_resumes_after_written_events() {
+        let directory = create_directory("backup_reconnect");
+        let server = FakeServer::bind().await;
+        let options = ReplicaOptions {
+            binlog: BinlogOptions::from_position(String::from("mysql-bin.000001"), 4),
+            reconnect: Some(ReconnectPolicy {
+                max_attempts: 3,
+                backoff: Duration::from_millis(10),
+                ..Default::default()
+            }),
+            ..server.options()
+        };
+        let handle = tokio::spawn(async move {
+            let mut dump_positions = Vec::new();
+
+            let mut channel = server.accept().await;
+            let command = serve_replication_setup(&mut channel).await;
+            dump_positions.push((&command[1..5]).read_u32::<LittleEndian>().unwrap());
+            write_fake_rotate(&mut channel, "mysql-bin.000001").await;
+            write_xid_event(&mut channel, 1, 31).await;
+            write_xid_event(&mut channel, 2, 58).await;
+            drop(channel);
+
+            let mut channel = server.accept().await;
+            let command = serve_replication_setup(&mut channel).await;
+            dump_positions.push((&command[1..5]).read_u32::<LittleEndian>().unwrap());
+            write_fake_rotate(&mut channel, "mysql-bin.000001").await;
+            write_xid_event(&mut channel, 3, 85).await;
+            write_eof(&mut channel, 2).await;
+            dump_positions
+        });
+
+        let mut client = BinlogClient::new(options);
+        let mut backup = BinlogBackup::new(&directory);
+        // The stream ends with an error on end of file.
+        assert!(backup.run(&mut client).await.is_err());
+        backup.sync().unwrap();
+
+        let binlog = fs::read(directory.join("mysql-bin.000001")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(vec![4, 58], handle.await.unwrap());
+        assert_eq!(85, binlog.len());
+        let xids: Vec<u64> = split_events(&binlog)
+            .into_iter()
+            .map(|x| match x.event {
+                BinlogEvent::XidEvent(e) => e.xid,
+                _ => panic!("Unexpected event"),
+            })
+            .collect();
+        assert_eq!(vec![1, 2, 3], xids);
+    }
+}
@@ -1,9 +1,15 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use async_stream::stream;
 use futures_core::Stream;
+use futures_util::StreamExt;
+use tokio::io;
 
-use crate::constants::checksum_type::ChecksumType;
+use crate::binlog_options::BinlogOptions;
+use crate::checkpoint::{Checkpoint, CheckpointStore};
 use crate::constants::database_provider::DatabaseProvider;
-use crate::constants::EVENT_HEADER_SIZE;
+use crate::constants::{EVENT_HEADER_SIZE, TIMEOUT_DELTA, TIMEOUT_MESSAGE};
 use crate::errors::Error;
 use crate::events::binlog_event::BinlogEvent;
 use crate::events::event_header::EventHeader;
@@ -17,8 +23,21 @@ use crate::replica_options::ReplicaOptions;
 use crate::responses::end_of_file_packet::EndOfFilePacket;
 use crate::responses::error_packet::ErrorPacket;
 use crate::responses::response_type;
-use crate::ssl_mode::SslMode;
 use crate::starting_strategy::StartingStrategy;
+use crate::transaction::{Transaction, TransactionGtid};
+
+/// Binlog event with the bytes received from the server.
+#[derive(Clone, Debug)]
+pub struct RawEvent {
+    /// Gets the event header.
+    pub header: EventHeader,
+
+    /// Gets the parsed event.
+    pub event: BinlogEvent,
+
+    /// Gets the event bytes including the header and checksum.
+    pub data: Vec<u8>,
+}
 
 /// MySql replication client streaming binlog events in real-time.
 pub struct BinlogClient {
@@ -26,26 +45,93 @@ pub struct BinlogClient {
     transaction: bool,
     maria_gtid: Option<MariaGtid>,
     mysql_gtid: Option<MySqlGtid>,
+
+    /// Committed replication position shared with the event stream to resume from on reconnect.
+    committed: Arc<Mutex<BinlogOptions>>,
+
+    checkpoint_store: Option<Box<dyn CheckpointStore>>,
+    checkpoint_interval: Duration,
+    last_checkpoint: Option<Instant>,
 }
 
 impl BinlogClient {
     pub fn new(options: ReplicaOptions) -> Self {
-        if options.ssl_mode != SslMode::Disabled {
-            unimplemented!("Ssl encryption is not supported in this version");
-        }
-
+        let committed = Arc::new(Mutex::new(options.binlog.clone()));
         Self {
             options,
             transaction: false,
             maria_gtid: None,
             mysql_gtid: None,
+            committed,
+            checkpoint_store: None,
+            checkpoint_interval: Duration::ZERO,
+            last_checkpoint: None,
         }
     }
 
+    /// Resumes replication from the checkpoint saved in the store, if any,
+    /// and saves the committed position to the store on commit.
+    /// The position is saved at most once per interval; zero interval saves on every commit.
+    pub fn set_checkpoint_store(
+        &mut self,
+        mut store: Box<dyn CheckpointStore>,
+        interval: Duration,
+    ) -> Result<(), Error> {
+        if let Some(checkpoint) = store.load()? {
+            self.options.binlog = checkpoint.binlog;
+            *self.committed.lock().unwrap() = self.options.binlog.clone();
+        }
+        self.checkpoint_store = Some(store);
+        self.checkpoint_interval = interval;
+        self.last_checkpoint = None;
+        Ok(())
+    }
+
+    /// Saves the committed position to the checkpoint store regardless of the interval.
+    /// Call before shutdown so that no committed events are replayed on restart.
+    pub fn save_checkpoint(&mut self) -> Result<(), Error> {
+        if let Some(store) = &mut self.checkpoint_store {
+            store.save(&Checkpoint::new(self.options.binlog.clone()))?;
+            self.last_checkpoint = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    fn save_checkpoint_on_commit(&mut self) -> Result<(), Error> {
+        let due = match self.last_checkpoint {
+            Some(x) => x.elapsed() >= self.checkpoint_interval,
+            None => true,
+        };
+        if due {
+            self.save_checkpoint()?;
+        }
+        Ok(())
+    }
+
     /// Replicates binlog events from the server
     pub async fn replicate(
         &mut self,
     ) -> Result<impl Stream<Item = Result<(EventHeader, BinlogEvent), Error>>, Error> {
+        let events = self.replicate_raw().await?;
+        Ok(events.map(|x| x.map(|x| (x.header, x.event))))
+    }
+
+    /// Replicates binlog events from the server keeping bytes of the events.
+    pub async fn replicate_raw(
+        &mut self,
+    ) -> Result<impl Stream<Item = Result<RawEvent, Error>>, Error> {
+        let (channel, parser) = self.start_replication().await?;
+        *self.committed.lock().unwrap() = self.options.binlog.clone();
+
+        // The stream reconnects with its own client using the committed position.
+        let session = BinlogClient {
+            committed: self.committed.clone(),
+            ..BinlogClient::new(self.options.clone())
+        };
+        Ok(Self::binlog_events_stream(session, channel, parser))
+    }
+
+    async fn start_replication(&mut self) -> Result<(PacketChannel, EventParser), Error> {
         let (mut channel, provider) = self.connect().await?;
 
         // Reset on reconnect
@@ -72,22 +158,76 @@ impl BinlogClient {
             }
         }
 
-        Ok(Self::binlog_events_stream(channel, checksum))
+        let mut parser = EventParser::new();
+        parser.checksum_type = checksum;
+        parser.verify_checksum = self.options.verify_checksum;
+        parser.binlog_filename = self.options.binlog.filename.clone();
+        parser.table_filter = self.options.table_filter.clone();
+        Ok((channel, parser))
+    }
+
+    /// Restarts replication from the committed position according to the reconnect policy.
+    async fn reconnect(&mut self) -> Result<(PacketChannel, EventParser), Error> {
+        let policy = match &self.options.reconnect {
+            Some(policy) => policy.clone(),
+            None => return Err(Error::String("Reconnect is disabled".to_string())),
+        };
+
+        let mut backoff = policy.backoff;
+        let mut last_error = Error::String("No reconnect attempts were made".to_string());
+        for _ in 0..policy.max_attempts {
+            tokio::time::sleep(backoff).await;
+            self.options.binlog = self.committed.lock().unwrap().clone();
+
+            match self.start_replication().await {
+                Ok(replication) => return Ok(replication),
+                Err(e) => last_error = e,
+            }
+            backoff = policy.max_backoff.min(backoff * 2);
+        }
+        Err(last_error)
+    }
+
+    /// Reads next packet failing if nothing arrives within the heartbeat interval.
+    async fn read_event_packet(&self, channel: &mut PacketChannel) -> Result<Vec<u8>, io::Error> {
+        // Zero interval disables master heartbeats.
+        if self.options.heartbeat_interval.is_zero() {
+            return Ok(channel.read_packet().await?.0);
+        }
+
+        let timeout = self.options.heartbeat_interval + TIMEOUT_DELTA;
+        match tokio::time::timeout(timeout, channel.read_packet()).await {
+            Ok(result) => Ok(result?.0),
+            Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, TIMEOUT_MESSAGE)),
+        }
     }
 
     fn binlog_events_stream(
+        mut session: BinlogClient,
         mut channel: PacketChannel,
-        checksum: ChecksumType,
-    ) -> impl Stream<Item = Result<(EventHeader, BinlogEvent), Error>> {
+        mut parser: EventParser,
+    ) -> impl Stream<Item = Result<RawEvent, Error>> {
         stream! {
-            let mut parser = EventParser::new();
-            parser.checksum_type = checksum;
             loop {
-                let (packet, _) = match channel.read_packet().await {
+                let mut packet = match session.read_event_packet(&mut channel).await {
                     Ok(x) => x,
-                    Err(e) => {
+                    Err(e) if session.options.reconnect.is_none() => {
                         yield Err(Error::IoError(e));
                         break;
+                    }
+                    Err(e) => match session.reconnect().await {
+                        Ok((new_channel, new_parser)) => {
+                            channel = new_channel;
+                            parser = new_parser;
+                            continue;
+                        }
+                        Err(reconnect_error) => {
+                            yield Err(Error::String(format!(
+                                "Reconnect failed after read error {}. {:?}",
+                                e, reconnect_error
+                            )));
+                            break;
+                        }
                     },
                 };
                 match packet[0] {
@@ -95,7 +235,8 @@ impl BinlogClient {
                         let header = EventHeader::parse(&packet[1..])?;
                         let event_slice = &packet[1 + EVENT_HEADER_SIZE..];
                         let event = parser.parse_event(&header, event_slice)?;
-                        yield Ok((header, event));
+                        packet.remove(0);
+                        yield Ok(RawEvent { header, event, data: packet });
                     },
                     response_type::ERROR => {
                         let error = ErrorPacket::parse(&packet[1..])?;
@@ -114,10 +255,43 @@ impl BinlogClient {
         }
     }
 
-    /// Updates current replication position
-    pub fn commit(&mut self, header: &EventHeader, event: &BinlogEvent) {
+    /// Updates current replication position and saves it to the checkpoint store if it's set.
+    pub fn commit(&mut self, header: &EventHeader, event: &BinlogEvent) -> Result<(), Error> {
         self.update_gtid_position(event);
         self.update_binlog_position(header, event);
+        self.save_checkpoint_on_commit()
+    }
+
+    /// Updates current replication position to the end of the transaction.
+    /// Use with TransactionAssembler instead of committing separate events.
+    pub fn commit_transaction(&mut self, transaction: &Transaction) -> Result<(), Error> {
+        match &transaction.gtid {
+            Some(TransactionGtid::MySql(x)) => {
+                self.mysql_gtid = Some(x.clone());
+                self.maria_gtid = None;
+            }
+            Some(TransactionGtid::MariaDb(x)) => {
+                self.maria_gtid = Some(x.clone());
+                self.mysql_gtid = None;
+            }
+            None => {}
+        }
+        if transaction.gtid.is_some()
+            && self.options.binlog.starting_strategy == StartingStrategy::FromGtid
+        {
+            self.commit_gtid();
+        }
+
+        let end = &transaction.end;
+        self.options.binlog.filename = end.filename.clone();
+        self.options.binlog.position = end.position;
+
+        {
+            let mut committed = self.committed.lock().unwrap();
+            committed.filename = end.filename.clone();
+            committed.position = end.position;
+        }
+        self.save_checkpoint_on_commit()
     }
 
     fn update_gtid_position(&mut self, event: &BinlogEvent) {
@@ -161,10 +335,15 @@ impl BinlogClient {
             BinlogEvent::RotateEvent(x) => {
                 self.options.binlog.filename = x.binlog_filename.clone();
                 self.options.binlog.position = x.binlog_position as u32;
+
+                let mut committed = self.committed.lock().unwrap();
+                committed.filename = x.binlog_filename.clone();
+                committed.position = x.binlog_position as u32;
             }
             _ => {
                 if header.next_event_position > 0 {
                     self.options.binlog.position = header.next_event_position;
+                    self.committed.lock().unwrap().position = header.next_event_position;
                 }
             }
         }
@@ -173,15 +352,146 @@ impl BinlogClient {
     fn commit_gtid(&mut self) {
         self.transaction = false;
 
+        let mut committed = self.committed.lock().unwrap();
         if let Some(gtid) = &self.maria_gtid {
             if let Some(list) = &mut self.options.binlog.gtid_list {
                 list.add_gtid(gtid.clone());
             }
+            if let Some(list) = &mut committed.gtid_list {
+                list.add_gtid(gtid.clone());
+            }
         }
         if let Some(gtid) = &self.mysql_gtid {
             if let Some(set) = &mut self.options.binlog.gtid_set {
                 set.add_gtid(gtid.clone()).unwrap();
             }
+            if let Some(set) = &mut committed.gtid_set {
+                set.add_gtid(gtid.clone()).unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use byteorder::{LittleEndian, ReadBytesExt};
+    use futures_util::StreamExt;
+
+    use crate::binlog_client::BinlogClient;
+    use crate::binlog_options::BinlogOptions;
+    use crate::errors::Error;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::fake_server::{serve_replication_setup, write_xid_event, FakeServer};
+    use crate::reconnect_policy::ReconnectPolicy;
+    use crate::replica_options::ReplicaOptions;
+
+    fn dump_position(command: &[u8]) -> u32 {
+        (&command[1..5]).read_u32::<LittleEndian>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn missed_heartbeat_fails_stream() {
+        let server = FakeServer::bind().await;
+        let options = ReplicaOptions {
+            heartbeat_interval: Duration::from_millis(100),
+            ..server.options()
+        };
+        let handle = tokio::spawn(async move {
+            let mut channel = server.accept().await;
+            serve_replication_setup(&mut channel).await;
+            // Keep the connection open without sending anything.
+            let _ = channel.read_packet().await;
+        });
+
+        let mut client = BinlogClient::new(options);
+        let mut stream = Box::pin(client.replicate().await.unwrap());
+
+        match stream.next().await {
+            Some(Err(Error::IoError(e))) => assert_eq!(std::io::ErrorKind::TimedOut, e.kind()),
+            result => panic!("Unexpected result {:?}", result.map(|x| x.map(|_| ()))),
+        }
+        drop(stream);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnect_resumes_from_committed_position() {
+        let server = FakeServer::bind().await;
+        let options = ReplicaOptions {
+            binlog: BinlogOptions::from_position(String::from("mysql-bin.000001"), 4),
+            reconnect: Some(ReconnectPolicy {
+                max_attempts: 3,
+                backoff: Duration::from_millis(10),
+                ..Default::default()
+            }),
+            ..server.options()
+        };
+        let handle = tokio::spawn(async move {
+            let mut dump_positions = Vec::new();
+
+            let mut channel = server.accept().await;
+            dump_positions.push(dump_position(&serve_replication_setup(&mut channel).await));
+            write_xid_event(&mut channel, 1, 100).await;
+            write_xid_event(&mut channel, 2, 200).await;
+            drop(channel);
+
+            let mut channel = server.accept().await;
+            dump_positions.push(dump_position(&serve_replication_setup(&mut channel).await));
+            write_xid_event(&mut channel, 2, 200).await;
+            dump_positions
+        });
+
+        let mut client = BinlogClient::new(options);
+        let mut stream = Box::pin(client.replicate().await.unwrap());
+
+        let mut xids = Vec::new();
+        while xids.len() < 3 {
+            let (header, event) = stream.next().await.unwrap().unwrap();
+            if let BinlogEvent::XidEvent(x) = &event {
+                xids.push(x.xid);
+                // Second event is not committed and must be replayed.
+                if x.xid == 1 {
+                    client.commit(&header, &event).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(vec![1, 2, 2], xids);
+        assert_eq!(vec![4, 100], handle.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn failed_reconnect_reports_reconnect_error() {
+        let server = FakeServer::bind().await;
+        let options = ReplicaOptions {
+            reconnect: Some(ReconnectPolicy {
+                max_attempts: 1,
+                backoff: Duration::from_millis(10),
+                ..Default::default()
+            }),
+            ..server.options()
+        };
+        let handle = tokio::spawn(async move {
+            let mut channel = server.accept().await;
+            serve_replication_setup(&mut channel).await;
+            // Stop listening before the connection is closed so reconnect is refused.
+            drop(server);
+            drop(channel);
+        });
+
+        let mut client = BinlogClient::new(options);
+        let mut stream = Box::pin(client.replicate().await.unwrap());
+
+        match stream.next().await {
+            Some(Err(Error::String(e))) => {
+                assert!(e.starts_with("Reconnect failed after read error"));
+                assert!(e.contains("ConnectionRefused"));
+            }
+            result => panic!("Unexpected result {:?}", result.map(|x| x.map(|_| ()))),
         }
+        assert!(stream.next().await.is_none());
+        handle.await.unwrap();
     }
 }
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::binlog_reader::verify_magic_number;
+use crate::checkpoint::replace_file;
+use crate::constants::{EVENT_HEADER_SIZE, FIRST_EVENT_POSITION};
+use crate::errors::Error;
+use crate::events::event_header::EventHeader;
+use crate::events::event_type::EventType;
+use crate::providers::mariadb::events::gtid_event::GtidEvent as MariaDbGtidEvent;
+use crate::providers::mariadb::gtid::gtid::Gtid as MariaDbGtid;
+use crate::providers::mysql::events::gtid_event::GtidEvent as MySqlGtidEvent;
+use crate::providers::mysql::gtid::gtid::Gtid as MySqlGtid;
+use crate::transaction::TransactionGtid;
+
+/// Position of a transaction in a binlog file.
+#[derive(Clone, Debug)]
+pub struct IndexEntry {
+    /// Gets GTID of the transaction.
+    pub gtid: TransactionGtid,
+
+    /// Gets position of the GTID event starting the transaction.
+    pub position: u32,
+}
+
+/// Positions of MySqlGtidEvent and MariaDbGtidEvent events in a binlog file.
+/// Saved as a sidecar file with `length=N` line followed by `position gtid` lines.
+#[derive(Clone, Debug, Default)]
+pub struct BinlogIndex {
+    /// Gets length of the indexed binlog file. Index of a file with different length is stale.
+    pub file_length: u64,
+
+    /// Gets transactions in the order they are logged.
+    pub entries: Vec<IndexEntry>,
+}
+
+impl BinlogIndex {
+    /// Builds index of the binlog stream reading only GTID events and headers of other events.
+    pub fn build<R: Read + Seek>(mut stream: R) -> Result<Self, Error> {
+        let file_length = stream.seek(SeekFrom::End(0))?;
+        stream.seek(SeekFrom::Start(0))?;
+
+        let mut header = [0; FIRST_EVENT_POSITION];
+        stream.read_exact(&mut header)?;
+        verify_magic_number(&header)?;
+
+        let mut entries = Vec::new();
+        let mut position = FIRST_EVENT_POSITION as u64;
+        let mut header_buffer = [0; EVENT_HEADER_SIZE];
+        while position + EVENT_HEADER_SIZE as u64 <= file_length {
+            stream.read_exact(&mut header_buffer)?;
+            let header = EventHeader::parse(&header_buffer)?;
+            let payload_length =
+                (header.event_length as u64).saturating_sub(EVENT_HEADER_SIZE as u64);
+            if position + EVENT_HEADER_SIZE as u64 + payload_length > file_length {
+                break;
+            }
+
+            let gtid = match EventType::from_code(header.event_type) {
+                EventType::MySqlGtidEvent => {
+                    let payload = read_payload(&mut stream, payload_length)?;
+                    let event = MySqlGtidEvent::parse(&mut Cursor::new(payload.as_slice()))?;
+                    Some(TransactionGtid::MySql(event.gtid))
+                }
+                EventType::MariaDbGtidEvent => {
+                    let payload = read_payload(&mut stream, payload_length)?;
+                    let mut cursor = Cursor::new(payload.as_slice());
+                    let event = MariaDbGtidEvent::parse(&mut cursor, &header)?;
+                    Some(TransactionGtid::MariaDb(event.gtid))
+                }
+                _ => {
+                    stream.seek(SeekFrom::Current(payload_length as i64))?;
+                    None
+                }
+            };
+            if let Some(gtid) = gtid {
+                entries.push(IndexEntry {
+                    gtid,
+                    position: position as u32,
+                });
+            }
+            position += EVENT_HEADER_SIZE as u64 + payload_length;
+        }
+
+        Ok(Self {
+            file_length,
+            entries,
+        })
+    }
+
+    /// Loads the sidecar index of the binlog file.
+    /// The index is built and saved if the sidecar file is missing or stale.
+    pub fn open<P: AsRef<Path>>(binlog_path: P) -> Result<Self, Error> {
+        let binlog_path = binlog_path.as_ref();
+        let sidecar_path = Self::sidecar_path(binlog_path);
+        let file_length = fs::metadata(binlog_path)?.len();
+
+        if sidecar_path.exists() {
+            let index = Self::parse(&fs::read_to_string(&sidecar_path)?)?;
+            if index.file_length == file_length {
+                return Ok(index);
+            }
+        }
+
+        let index = Self::build(File::open(binlog_path)?)?;
+        index.save(&sidecar_path)?;
+        Ok(index)
+    }
+
+    /// Gets path of the sidecar index file, e.g. mysql-bin.000001.idx
+    pub fn sidecar_path<P: AsRef<Path>>(binlog_path: P) -> PathBuf {
+        let mut path = binlog_path.as_ref().as_os_str().to_owned();
+        path.push(".idx");
+        PathBuf::from(path)
+    }
+
+    /// Parses index serialized with Display.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let mut lines = value.lines().filter(|x| !x.is_empty());
+        let file_length = match lines.next().and_then(|x| x.strip_prefix("length=")) {
+            Some(x) => x.parse()?,
+            None => {
+                return Err(Error::String(
+                    "Binlog index must start with length".to_string(),
+                ))
+            }
+        };
+
+        let mut entries = Vec::new();
+        for line in lines {
+            let (position, gtid) = match line.split_once(' ') {
+                Some(x) => x,
+                None => {
+                    return Err(Error::String(format!(
+                        "Invalid binlog index line: {}",
+                        line
+                    )))
+                }
+            };
+            let gtid = match gtid.contains(':') {
+                true => TransactionGtid::MySql(MySqlGtid::parse(gtid)?),
+                false => TransactionGtid::MariaDb(MariaDbGtid::parse(gtid)?),
+            };
+            entries.push(IndexEntry {
+                gtid,
+                position: position.parse()?,
+            });
+        }
+        Ok(Self {
+            file_length,
+            entries,
+        })
+    }
+
+    /// Saves the index replacing the file atomically.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        replace_file(path.as_ref(), self.to_string().as_bytes())
+    }
+
+    /// Gets position of the transaction with the GTID.
+    pub fn find(&self, gtid: &TransactionGtid) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|x| match (&x.gtid, gtid) {
+                (TransactionGtid::MySql(x), TransactionGtid::MySql(y)) => {
+                    x.source_id.data == y.source_id.data && x.transaction_id == y.transaction_id
+                }
+                (TransactionGtid::MariaDb(x), TransactionGtid::MariaDb(y)) => {
+                    x.domain_id == y.domain_id
+                        && x.server_id == y.server_id
+                        && x.sequence == y.sequence
+                }
+                _ => false,
+            })
+            .map(|x| x.position)
+    }
+}
+
+fn read_payload<R: Read>(stream: &mut R, length: u64) -> Result<Vec<u8>, Error> {
+    let mut payload = vec![0; length as usize];
+    stream.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+impl fmt::Display for BinlogIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "length={}", self.file_length)?;
+        for entry in &self.entries {
+            writeln!(f, "{} {}", entry.position, entry.gtid)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::BinlogIndex;
+    use crate::binlog_reader::BinlogReader;
+    use crate::binlog_writer::BinlogWriter;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::xid_event::XidEvent;
+    use crate::providers::mysql::gtid::gtid::Gtid;
+    use crate::test_binlogs::{create_header, write_xid};
+    use crate::transaction::TransactionGtid;
+
+    const SERVER_UUID: &str = "9b1ad0d4-19f4-11ee-9d6b-0242ac120002";
+
+    /// Writes transactions with GTIDs 1-3 and an incomplete event.
+    fn create_binlog() -> Vec<u8> {
+        let mut writer = BinlogWriter::new(Vec::new()).unwrap();
+        let gtid = Gtid::parse(&format!("{}:1", SERVER_UUID)).unwrap();
+        for transaction_id in 1..=3u64 {
+            let mut payload = vec![1];
+            payload.extend_from_slice(&gtid.source_id.data);
+            payload.extend_from_slice(&transaction_id.to_le_bytes());
+            writer.write_payload(&create_header(33), &payload).unwrap();
+            write_xid(&mut writer, transaction_id);
+        }
+        let mut binlog = writer.into_inner();
+        binlog.extend_from_slice(&[0; 10]);
+        binlog
+    }
+
+    #[test]
+    fn seek_to_indexed_gtid() {
+        let binlog = create_binlog();
+        let index = BinlogIndex::build(Cursor::new(&binlog)).unwrap();
+        assert_eq!(3, index.entries.len());
+        assert_eq!(binlog.len() as u64, index.file_length);
+
+        let index = BinlogIndex::parse(&index.to_string()).unwrap();
+        let gtid = TransactionGtid::MySql(Gtid::parse(&format!("{}:2", SERVER_UUID)).unwrap());
+        let missing = TransactionGtid::MySql(Gtid::parse(&format!("{}:4", SERVER_UUID)).unwrap());
+        assert!(index.find(&missing).is_none());
+
+        let mut reader = BinlogReader::new(Cursor::new(&binlog)).unwrap();
+        reader.seek_gtid(&index, &gtid).unwrap();
+        let (header, event) = reader.read_event().unwrap();
+        assert_eq!(
+            index.find(&gtid).unwrap(),
+            header.next_event_position - header.event_length
+        );
+        assert!(matches!(event, BinlogEvent::MySqlGtidEvent(x) if x.gtid.transaction_id == 2));
+        assert!(matches!(
+            reader.read_event().unwrap().1,
+            BinlogEvent::XidEvent(XidEvent { xid: 2 })
+        ));
+        assert!(reader.seek_gtid(&index, &missing).is_err());
+    }
+
+    #[test]
+    fn open_saves_sidecar_file() {
+        let path = std::env::temp_dir().join(format!("index_{}.000001", std::process::id()));
+        let sidecar_path = BinlogIndex::sidecar_path(&path);
+        std::fs::write(&path, create_binlog()).unwrap();
+
+        let index = BinlogIndex::open(&path).unwrap();
+        let sidecar = std::fs::read_to_string(&sidecar_path).unwrap();
+        std::fs::write(&path, &create_binlog()[..260]).unwrap();
+        let rebuilt = BinlogIndex::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&sidecar_path).unwrap();
+
+        assert_eq!(index.to_string(), sidecar);
+        assert!(sidecar.ends_with(&format!(
+            "{} {}:3\n",
+            index.entries[2].position, SERVER_UUID
+        )));
+        assert_eq!(260, rebuilt.file_length);
+        assert_eq!(2, rebuilt.entries.len());
+    }
+}
@@ -4,7 +4,8 @@ use crate::providers::mysql::gtid::gtid_set::GtidSet;
 use crate::starting_strategy::StartingStrategy;
 
 /// Replication options used when client connects to the server.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BinlogOptions {
     /// Binary log file name.
     /// The value is automatically changed on the RotateEvent.
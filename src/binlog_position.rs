@@ -0,0 +1,91 @@
+use std::cmp::Ordering;
+
+/// Coordinates of an event in the binary log.
+/// Positions are ordered by file name and then by position in the file.
+/// File names are compared by the numeric extension, so mysql-bin.1000000 follows mysql-bin.999999.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BinlogPosition {
+    /// Gets binary log file name.
+    pub filename: String,
+
+    /// Gets position in the binary log file.
+    pub position: u32,
+}
+
+impl BinlogPosition {
+    pub fn new(filename: String, position: u32) -> Self {
+        Self { filename, position }
+    }
+}
+
+impl Ord for BinlogPosition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_filenames(&self.filename, &other.filename)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for BinlogPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares binary log file names by the base name and then by the numeric extension.
+/// Names without numeric extension are compared as strings.
+pub fn compare_filenames(a: &str, b: &str) -> Ordering {
+    match (split_filename(a), split_filename(b)) {
+        (Some((a_base, a_number)), Some((b_base, b_number))) => a_base
+            .cmp(b_base)
+            .then(a_number.cmp(&b_number))
+            .then_with(|| a.cmp(b)),
+        _ => a.cmp(b),
+    }
+}
+
+fn split_filename(filename: &str) -> Option<(&str, u64)> {
+    let (base, extension) = filename.rsplit_once('.')?;
+    if extension.is_empty() || !extension.bytes().all(|x| x.is_ascii_digit()) {
+        return None;
+    }
+    Some((base, extension.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare_filenames, BinlogPosition};
+
+    #[test]
+    fn compare_by_numeric_extension() {
+        assert_eq!(
+            Ordering::Less,
+            compare_filenames("mysql-bin.999999", "mysql-bin.1000000")
+        );
+        assert_eq!(
+            Ordering::Greater,
+            compare_filenames("mysql-bin.000010", "mysql-bin.000009")
+        );
+
+        let mut positions = [
+            BinlogPosition::new(String::from("mysql-bin.1000000"), 4),
+            BinlogPosition::new(String::from("mysql-bin.999999"), 500),
+            BinlogPosition::new(String::from("mysql-bin.999999"), 120),
+        ];
+        positions.sort();
+        let positions: Vec<_> = positions
+            .iter()
+            .map(|x| (x.filename.as_str(), x.position))
+            .collect();
+        assert_eq!(
+            vec![
+                ("mysql-bin.999999", 120),
+                ("mysql-bin.999999", 500),
+                ("mysql-bin.1000000", 4)
+            ],
+            positions
+        );
+    }
+}
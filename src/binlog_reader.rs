@@ -1,41 +1,59 @@
+use crate::binlog_index::BinlogIndex;
 use crate::constants;
 use crate::errors::Error;
 use crate::events::binlog_event::BinlogEvent;
 use crate::events::event_header::EventHeader;
 use crate::events::event_parser::EventParser;
+use crate::reader_options::ReaderOptions;
+use crate::transaction::{Transaction, TransactionAssembler, TransactionGtid};
 use constants::EVENT_HEADER_SIZE;
-use std::fs::File;
-use std::io::{ErrorKind, Read};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
 
-const MAGIC_NUMBER: [u8; constants::FIRST_EVENT_POSITION] = [0xfe, 0x62, 0x69, 0x6e];
+pub(crate) const MAGIC_NUMBER: [u8; constants::FIRST_EVENT_POSITION] = [0xfe, 0x62, 0x69, 0x6e];
 
-/// Reads binlog events from a stream.
-pub struct BinlogReader {
-    stream: File,
+/// Reads binlog events from a stream, e.g. File, memory buffer or decompressor.
+pub struct BinlogReader<R: Read> {
+    stream: R,
     parser: EventParser,
     payload_buffer: Vec<u8>,
 }
 
-impl BinlogReader {
-    pub fn new(mut stream: File) -> Result<Self, Error> {
+impl<R: Read> BinlogReader<R> {
+    pub fn new(stream: R) -> Result<Self, Error> {
+        Self::with_options(stream, ReaderOptions::default())
+    }
+
+    pub fn with_options(mut stream: R, options: ReaderOptions) -> Result<Self, Error> {
         let mut header = [0; constants::FIRST_EVENT_POSITION];
         stream.read_exact(&mut header)?;
+        verify_magic_number(&header)?;
+        Ok(Self::with_parser(stream, create_parser(options)))
+    }
 
-        if header != MAGIC_NUMBER {
-            return Err(Error::String("Invalid binary log file header".to_string()));
-        }
-
-        Ok(Self {
+    /// Creates reader of the stream positioned after the magic number.
+    /// The parser keeps its state, e.g. table map of the previous file.
+    pub(crate) fn with_parser(stream: R, parser: EventParser) -> Self {
+        Self {
             stream,
-            parser: EventParser::new(),
+            parser,
             payload_buffer: vec![0; constants::PAYLOAD_BUFFER_SIZE],
-        })
+        }
+    }
+
+    pub(crate) fn into_parser(self) -> EventParser {
+        self.parser
     }
 
     pub fn read_events(self) -> Self {
         self
     }
 
+    /// Reads events grouped into transactions.
+    pub fn read_transactions(self) -> impl Iterator<Item = Result<Transaction, Error>> {
+        let assembler = TransactionAssembler::new(self.parser.binlog_filename.clone());
+        assembler.iter(self)
+    }
+
     pub fn read_event(&mut self) -> Result<(EventHeader, BinlogEvent), Error> {
         // Parse header
         let mut header_buffer = [0; EVENT_HEADER_SIZE];
@@ -59,18 +77,99 @@ impl BinlogReader {
     }
 }
 
-impl Iterator for BinlogReader {
+impl<R: Read + Seek> BinlogReader<R> {
+    /// Moves to the event at the position.
+    /// FormatDescriptionEvent is read first to get checksum settings of the file.
+    pub fn seek(&mut self, position: u64) -> Result<(), Error> {
+        self.stream
+            .seek(SeekFrom::Start(constants::FIRST_EVENT_POSITION as u64))?;
+        match self.read_event()? {
+            (_, BinlogEvent::FormatDescriptionEvent(_)) => {}
+            _ => {
+                return Err(Error::String(
+                    "Binlog file must start with FormatDescriptionEvent".to_string(),
+                ))
+            }
+        }
+        self.stream.seek(SeekFrom::Start(position))?;
+        Ok(())
+    }
+
+    /// Gets byte offset of the next event in the stream.
+    pub fn stream_position(&mut self) -> Result<u64, Error> {
+        Ok(self.stream.stream_position()?)
+    }
+
+    /// Moves to the GtidEvent of the transaction using the index of the file.
+    pub fn seek_gtid(&mut self, index: &BinlogIndex, gtid: &TransactionGtid) -> Result<(), Error> {
+        match index.find(gtid) {
+            Some(position) => self.seek(position as u64),
+            None => Err(Error::String(format!(
+                "Gtid {} is not found in the index",
+                gtid
+            ))),
+        }
+    }
+}
+
+impl<R: Read> Iterator for BinlogReader<R> {
     type Item = Result<(EventHeader, BinlogEvent), Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let result = self.read_event();
-        if let Err(error) = &result {
-            if let Error::IoError(io_error) = error {
-                if let ErrorKind::UnexpectedEof = io_error.kind() {
-                    return None;
-                }
-            }
+        match self.read_event() {
+            Err(error) if is_end_of_stream(&error) => None,
+            result => Some(result),
         }
-        Some(result)
+    }
+}
+
+pub(crate) fn verify_magic_number(header: &[u8]) -> Result<(), Error> {
+    if header != MAGIC_NUMBER {
+        return Err(Error::String("Invalid binary log file header".to_string()));
+    }
+    Ok(())
+}
+
+pub(crate) fn create_parser(options: ReaderOptions) -> EventParser {
+    let mut parser = EventParser::new();
+    parser.verify_checksum = options.verify_checksum;
+    parser.binlog_filename = options.filename;
+    parser.table_filter = options.table_filter;
+    parser
+}
+
+/// Returns true for the end of stream reached between or inside events.
+pub(crate) fn is_end_of_stream(error: &Error) -> bool {
+    matches!(error, Error::IoError(x) if x.kind() == ErrorKind::UnexpectedEof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinlogReader;
+    use crate::binlog_writer::BinlogWriter;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::xid_event::XidEvent;
+
+    #[test]
+    fn read_memory_buffer() {
+        let mut writer = BinlogWriter::new(Vec::new()).unwrap();
+        let header = EventHeader {
+            timestamp: 1689416430,
+            event_type: 16,
+            server_id: 1,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0,
+        };
+        let xid = BinlogEvent::XidEvent(XidEvent { xid: 42 });
+        writer.write_event(&header, &xid).unwrap();
+        let binlog = writer.into_inner();
+
+        let reader = BinlogReader::new(binlog.as_slice()).unwrap();
+        let events: Vec<_> = reader.map(|x| x.unwrap()).collect();
+        assert_eq!(2, events.len());
+        assert_eq!(binlog.len() as u32, events[1].0.next_event_position);
+        assert!(BinlogReader::new(&binlog[1..]).is_err());
     }
 }
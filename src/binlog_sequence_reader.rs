@@ -0,0 +1,411 @@
+use crate::binlog_position::{compare_filenames, BinlogPosition};
+use crate::binlog_reader::{create_parser, is_end_of_stream, verify_magic_number, BinlogReader};
+use crate::constants::FIRST_EVENT_POSITION;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::event_parser::EventParser;
+use crate::events::rotate_event::RotateEvent;
+use crate::reader_options::ReaderOptions;
+use crate::table_filter::matches_wildcard;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Event read by BinlogSequenceReader with its coordinates.
+#[derive(Clone, Debug)]
+pub struct SequenceEvent {
+    /// Gets binlog file name and position of the event.
+    /// Position is the byte offset in the read file, also for relay logs.
+    pub position: BinlogPosition,
+
+    /// Gets header of the event.
+    pub header: EventHeader,
+
+    /// Gets the event.
+    pub event: BinlogEvent,
+}
+
+/// Reads a sequence of binlog or relay log files as one stream of events.
+/// RotateEvent at the end of a file selects the next file and position.
+/// Files without RotateEvent, e.g. left by a crash, are followed by the next file of the sequence.
+/// TableMapEvent state is kept across files, so row events are decoded after file boundaries.
+pub struct BinlogSequenceReader {
+    paths: Vec<PathBuf>,
+    next: usize,
+    reader: Option<BinlogReader<BufReader<File>>>,
+    parser: Option<EventParser>,
+    filename: String,
+    offset: u64,
+    rotate: Option<RotateEvent>,
+}
+
+impl BinlogSequenceReader {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self::with_options(paths, ReaderOptions::default())
+    }
+
+    /// Creates reader of the files in the given order.
+    pub fn with_options(paths: Vec<PathBuf>, options: ReaderOptions) -> Self {
+        Self {
+            paths,
+            next: 0,
+            reader: None,
+            parser: Some(create_parser(options)),
+            filename: String::new(),
+            offset: 0,
+            rotate: None,
+        }
+    }
+
+    /// Creates reader of the files listed in an index file, e.g. mysql-bin.index or relay-log.index.
+    /// Relative paths are resolved against the directory of the index file.
+    pub fn from_index_file<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let paths = fs::read_to_string(path)?
+            .lines()
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .map(|x| directory.join(x))
+            .collect();
+        Ok(Self::with_options(paths, options))
+    }
+
+    /// Creates reader of the directory files whose names match the pattern, e.g. `mysql-bin.*`.
+    /// The pattern supports `*` and `?` wildcards. Files are ordered by the numeric extension.
+    /// Files that do not start with the binlog magic number, e.g. the index file, are skipped.
+    pub fn from_directory<P: AsRef<Path>>(
+        directory: P,
+        pattern: &str,
+        options: ReaderOptions,
+    ) -> Result<Self, Error> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_file()
+                && matches_wildcard(pattern, &name)
+                && is_binlog_file(&entry.path())?
+            {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort_by(|a, b| compare_filenames(&file_name(a), &file_name(b)));
+        Ok(Self::with_options(paths, options))
+    }
+
+    /// Gets paths of the files in the sequence.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Reads the next event. Returns None after the last file of the sequence.
+    pub fn read_event(&mut self) -> Result<Option<SequenceEvent>, Error> {
+        loop {
+            let reader = match &mut self.reader {
+                Some(x) => x,
+                None => {
+                    if !self.open_next()? {
+                        return Ok(None);
+                    }
+                    continue;
+                }
+            };
+
+            let (header, event) = match reader.read_event() {
+                Ok(x) => x,
+                Err(error) if is_end_of_stream(&error) => {
+                    self.close();
+                    continue;
+                }
+                Err(error) => {
+                    // The event may be consumed even if it cannot be parsed.
+                    self.offset = reader.stream_position()?;
+                    return Err(error);
+                }
+            };
+            if let BinlogEvent::RotateEvent(x) = &event {
+                self.rotate = Some(x.clone());
+            }
+
+            // Positions of relay log headers belong to the source binlog, so bytes read are counted.
+            let position = self.offset;
+            self.offset += header.event_length as u64;
+            return Ok(Some(SequenceEvent {
+                position: BinlogPosition::new(self.filename.clone(), position as u32),
+                header,
+                event,
+            }));
+        }
+    }
+
+    /// Opens the file named by the last RotateEvent or the next file of the sequence.
+    /// Returns false if there are no more files.
+    fn open_next(&mut self) -> Result<bool, Error> {
+        let mut index = self.next;
+        let mut position = FIRST_EVENT_POSITION as u64;
+        if let Some(rotate) = &self.rotate {
+            let found = self.paths[index..]
+                .iter()
+                .position(|x| file_name(x) == rotate.binlog_filename);
+            if let Some(offset) = found {
+                index += offset;
+                position = rotate.binlog_position;
+            }
+        }
+
+        let path = match self.paths.get(index) {
+            Some(x) => x.clone(),
+            None => return Ok(false),
+        };
+
+        let mut stream = BufReader::new(File::open(&path)?);
+        let mut header = [0; FIRST_EVENT_POSITION];
+        stream.read_exact(&mut header)?;
+        verify_magic_number(&header)?;
+
+        // The sequence is advanced only after the file is opened, so reading can be retried.
+        self.next = index + 1;
+        self.rotate = None;
+
+        // The parser is kept in self.parser if the file cannot be opened.
+        let mut parser = self.parser.take().expect("parser is kept between files");
+        self.filename = file_name(&path);
+        parser.binlog_filename = self.filename.clone();
+
+        let mut reader = BinlogReader::with_parser(stream, parser);
+        let result = match position > FIRST_EVENT_POSITION as u64 {
+            true => reader.seek(position),
+            false => Ok(()),
+        };
+        self.offset = position;
+        self.reader = Some(reader);
+        result.map(|_| true)
+    }
+
+    fn close(&mut self) {
+        if let Some(reader) = self.reader.take() {
+            self.parser = Some(reader.into_parser());
+        }
+    }
+}
+
+impl Iterator for BinlogSequenceReader {
+    type Item = Result<SequenceEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_event().transpose()
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn is_binlog_file(path: &Path) -> Result<bool, Error> {
+    let mut header = [0; FIRST_EVENT_POSITION];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(verify_magic_number(&header).is_ok()),
+        Err(_) => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::path::Path;
+
+    use super::BinlogSequenceReader;
+    use crate::binlog_writer::BinlogWriter;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::reader_options::ReaderOptions;
+    use crate::test_binlogs::{create_directory, create_header, write_xid};
+    use crate::writer_options::WriterOptions;
+
+    /// Writes mysql-bin.000001 rotated to mysql-bin.000002 and mysql-bin.000003 without RotateEvent.
+    fn create_binlogs(directory: &Path) {
+        let file = File::create(directory.join("mysql-bin.000001")).unwrap();
+        let mut writer = BinlogWriter::new(file).unwrap();
+        write_xid(&mut writer, 1);
+        let next = File::create(directory.join("mysql-bin.000002")).unwrap();
+        writer.rotate("mysql-bin.000002", next).unwrap();
+        write_xid(&mut writer, 2);
+        writer.flush().unwrap();
+
+        let file = File::create(directory.join("mysql-bin.000003")).unwrap();
+        let mut writer = BinlogWriter::new(file).unwrap();
+        write_xid(&mut writer, 3);
+        writer.flush().unwrap();
+
+        let index = "./mysql-bin.000001\n./mysql-bin.000002\n./mysql-bin.000003\n";
+        fs::write(directory.join("mysql-bin.index"), index).unwrap();
+    }
+
+    fn read_xids(reader: BinlogSequenceReader) -> Vec<(String, u32, u64)> {
+        reader
+            .map(|x| x.unwrap())
+            .filter_map(|x| match x.event {
+                BinlogEvent::XidEvent(e) => Some((x.position.filename, x.position.position, e.xid)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn read_files_of_index() {
+        let directory = create_directory("sequence_index");
+        create_binlogs(&directory);
+
+        let options = ReaderOptions {
+            verify_checksum: true,
+            ..Default::default()
+        };
+        let reader =
+            BinlogSequenceReader::from_index_file(directory.join("mysql-bin.index"), options)
+                .unwrap();
+        let events: Vec<_> = reader.map(|x| x.unwrap()).collect();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(7, events.len());
+        assert!(
+            matches!(&events[2].event, BinlogEvent::RotateEvent(x) if x.binlog_filename == "mysql-bin.000002")
+        );
+        assert!(matches!(
+            events[3].event,
+            BinlogEvent::FormatDescriptionEvent(_)
+        ));
+        assert_eq!("mysql-bin.000002", events[3].position.filename);
+        assert_eq!(4, events[3].position.position);
+        assert_eq!("mysql-bin.000003", events[6].position.filename);
+        for event in &events {
+            assert_eq!(
+                event.position.position + event.header.event_length,
+                event.header.next_event_position
+            );
+        }
+    }
+
+    #[test]
+    fn read_files_of_directory() {
+        let directory = create_directory("sequence_directory");
+        create_binlogs(&directory);
+
+        let reader =
+            BinlogSequenceReader::from_directory(&directory, "mysql-bin.*", Default::default())
+                .unwrap();
+        assert_eq!(3, reader.paths().len());
+        let xids = read_xids(reader);
+        fs::remove_dir_all(&directory).unwrap();
+
+        let filenames: Vec<_> = xids.iter().map(|x| (x.0.as_str(), x.2)).collect();
+        assert_eq!(
+            vec![
+                ("mysql-bin.000001", 1),
+                ("mysql-bin.000002", 2),
+                ("mysql-bin.000003", 3)
+            ],
+            filenames
+        );
+        assert_eq!(123, xids[1].1);
+    }
+
+    #[test]
+    fn decode_rows_after_rotate() {
+        let directory = create_directory("sequence_table_map");
+        // Table shop.orders with a single INT column
+        let table_map = [
+            &[1, 0, 0, 0, 0, 0, 0, 0, 4][..],
+            b"shop\0",
+            &[6],
+            b"orders\0",
+            &[1, 3, 0, 0],
+        ]
+        .concat();
+        // WRITE_ROWS_EVENT v2 inserting 42
+        let write_rows = [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 1, 0, 42, 0, 0, 0];
+
+        let file = File::create(directory.join("mysql-bin.000001")).unwrap();
+        let mut writer = BinlogWriter::new(file).unwrap();
+        writer
+            .write_payload(&create_header(19), &table_map)
+            .unwrap();
+        let next = File::create(directory.join("mysql-bin.000002")).unwrap();
+        writer.rotate("mysql-bin.000002", next).unwrap();
+        writer
+            .write_payload(&create_header(30), &write_rows)
+            .unwrap();
+        writer.flush().unwrap();
+
+        let reader =
+            BinlogSequenceReader::from_directory(&directory, "mysql-bin.*", Default::default())
+                .unwrap();
+        let events: Vec<_> = reader.map(|x| x.unwrap()).collect();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let rows = events
+            .iter()
+            .find_map(|x| match &x.event {
+                BinlogEvent::WriteRowsEvent(e) => Some((x.position.filename.as_str(), e)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!("mysql-bin.000002", rows.0);
+        assert_eq!(vec![Some(MySqlValue::Int(42))], rows.1.rows[0].cells);
+    }
+
+    #[test]
+    fn positions_of_relay_log_events() {
+        let directory = create_directory("sequence_relay");
+        let path = directory.join("relay-bin.000001");
+        let options = WriterOptions {
+            checksum: false,
+            ..Default::default()
+        };
+        let file = File::create(&path).unwrap();
+        let mut writer = BinlogWriter::with_options(file, options).unwrap();
+        write_xid(&mut writer, 1);
+        write_xid(&mut writer, 2);
+        writer.flush().unwrap();
+
+        // Headers of relay log events keep positions of the source binlog.
+        let mut bytes = fs::read(&path).unwrap();
+        let mut offset = 4;
+        while offset < bytes.len() {
+            let length = u32::from_le_bytes(bytes[offset + 9..offset + 13].try_into().unwrap());
+            let source_position = 10000 + offset as u32;
+            bytes[offset + 13..offset + 17].copy_from_slice(&source_position.to_le_bytes());
+            offset += length as usize;
+        }
+        fs::write(&path, bytes).unwrap();
+
+        let xids = read_xids(BinlogSequenceReader::new(vec![path]));
+        fs::remove_dir_all(&directory).unwrap();
+
+        let relay_log = String::from("relay-bin.000001");
+        assert_eq!(
+            vec![(relay_log.clone(), 123, 1), (relay_log, 123 + 27, 2)],
+            xids
+        );
+    }
+
+    #[test]
+    fn retry_file_that_failed_to_open() {
+        let directory = create_directory("sequence_retry");
+        let path = directory.join("mysql-bin.000001");
+        let mut reader = BinlogSequenceReader::new(vec![path.clone()]);
+        assert!(reader.read_event().is_err());
+
+        let mut writer = BinlogWriter::new(File::create(&path).unwrap()).unwrap();
+        write_xid(&mut writer, 1);
+        writer.flush().unwrap();
+        let xids = read_xids(reader);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(vec![(String::from("mysql-bin.000001"), 123, 1)], xids);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::binlog_reader::MAGIC_NUMBER;
+use crate::constants;
+use crate::constants::checksum_type::ChecksumType;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::event_type::EventType;
+use crate::events::format_description_event::FormatDescriptionEvent;
+use crate::events::rotate_event::RotateEvent;
+use crate::events::table_map_event::TableMapEvent;
+use crate::writer_options::WriterOptions;
+use constants::EVENT_HEADER_SIZE;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BINLOG_VERSION: u16 = 4;
+const CHECKSUM_SIZE: usize = 4;
+
+/// Writes binlog events to a stream in MySQL 5.7 format readable by BinlogReader and mysqlbinlog.
+/// The stream starts with the magic number and FormatDescriptionEvent.
+/// Event lengths, positions and checksums are recomputed for the written file.
+pub struct BinlogWriter<W: Write> {
+    stream: W,
+    options: WriterOptions,
+    position: u32,
+    table_map: HashMap<u64, TableMapEvent>,
+}
+
+impl<W: Write> BinlogWriter<W> {
+    pub fn new(stream: W) -> Result<Self, Error> {
+        Self::with_options(stream, WriterOptions::default())
+    }
+
+    pub fn with_options(stream: W, options: WriterOptions) -> Result<Self, Error> {
+        let mut writer = Self {
+            stream,
+            options,
+            position: 0,
+            table_map: HashMap::new(),
+        };
+        writer.write_file_header()?;
+        Ok(writer)
+    }
+
+    /// Gets position of the next event in the current file.
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Serializes and writes the event.
+    /// Supports QueryEvent, XidEvent, RotateEvent, TableMapEvent, row events and GTID events.
+    /// Row events are written in version 2 format using the last TableMapEvent written
+    /// with write_event for the table. Event type of the header is set from the written format.
+    /// Server id of MariaDB GTID is written in the header.
+    /// Other events must be written with write_payload from the bytes read from the server or a file.
+    pub fn write_event(&mut self, header: &EventHeader, event: &BinlogEvent) -> Result<(), Error> {
+        let (event_type, payload) = match event {
+            BinlogEvent::QueryEvent(x) => (EventType::QueryEvent, x.serialize()?),
+            BinlogEvent::XidEvent(x) => (EventType::XidEvent, x.serialize()?),
+            BinlogEvent::RotateEvent(x) => (EventType::RotateEvent, x.serialize()?),
+            BinlogEvent::TableMapEvent(x) => {
+                let payload = x.serialize()?;
+                self.table_map.insert(x.table_id, x.clone());
+                (EventType::TableMapEvent, payload)
+            }
+            BinlogEvent::WriteRowsEvent(x) => (
+                EventType::MySqlWriteRowsEventV2,
+                x.serialize(&self.table_map)?,
+            ),
+            BinlogEvent::UpdateRowsEvent(x) => (
+                EventType::MySqlUpdateRowsEventV2,
+                x.serialize(&self.table_map)?,
+            ),
+            BinlogEvent::DeleteRowsEvent(x) => (
+                EventType::MySqlDeleteRowsEventV2,
+                x.serialize(&self.table_map)?,
+            ),
+            BinlogEvent::MySqlGtidEvent(x) => (EventType::MySqlGtidEvent, x.serialize()?),
+            BinlogEvent::MariaDbGtidEvent(x) => (EventType::MariaDbGtidEvent, x.serialize()?),
+            _ => {
+                return Err(Error::String(format!(
+                    "Serialization of event type {} is not supported. Please write the event bytes with write_payload",
+                    header.event_type
+                )))
+            }
+        };
+        let mut header = header.clone();
+        header.event_type = event_type as u8;
+        if let BinlogEvent::MariaDbGtidEvent(x) = event {
+            header.server_id = x.gtid.server_id;
+        }
+        self.write(header, &payload, self.options.checksum)
+    }
+
+    /// Writes the event payload without checksum.
+    /// Timestamp, type, server id and flags are taken from the header.
+    pub fn write_payload(&mut self, header: &EventHeader, payload: &[u8]) -> Result<(), Error> {
+        self.write(header.clone(), payload, self.options.checksum)
+    }
+
+    /// Writes RotateEvent pointing to the next file and starts the file in the new stream.
+    /// Returns the stream of the finished file.
+    pub fn rotate(&mut self, filename: &str, stream: W) -> Result<W, Error> {
+        let event = RotateEvent {
+            binlog_filename: filename.to_string(),
+            binlog_position: constants::FIRST_EVENT_POSITION as u64,
+        };
+        let header = self.create_header(EventType::RotateEvent as u8);
+        self.write(header, &event.serialize()?, self.options.checksum)?;
+        self.stream.flush()?;
+
+        let previous = std::mem::replace(&mut self.stream, stream);
+        self.write_file_header()?;
+        Ok(previous)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    /// Gets the stream of the current file.
+    pub fn into_inner(self) -> W {
+        self.stream
+    }
+
+    fn write_file_header(&mut self) -> Result<(), Error> {
+        self.stream.write_all(&MAGIC_NUMBER)?;
+        self.position = constants::FIRST_EVENT_POSITION as u32;
+
+        let checksum_type = match self.options.checksum {
+            true => ChecksumType::Crc32,
+            false => ChecksumType::None,
+        };
+        let event = FormatDescriptionEvent {
+            binlog_version: BINLOG_VERSION,
+            server_version: self.options.server_version.clone(),
+            checksum_type,
+        };
+        let header = self.create_header(EventType::FormatDescriptionEvent as u8);
+
+        // FormatDescriptionEvent has checksum even if checksums of the file are disabled.
+        self.write(header, &event.serialize()?, true)
+    }
+
+    fn write(
+        &mut self,
+        mut header: EventHeader,
+        payload: &[u8],
+        checksum: bool,
+    ) -> Result<(), Error> {
+        let checksum_size = if checksum { CHECKSUM_SIZE } else { 0 };
+        let event_length = EVENT_HEADER_SIZE + payload.len() + checksum_size;
+        let next_event_position = self.position as usize + event_length;
+        if next_event_position > u32::MAX as usize {
+            return Err(Error::String(
+                "Binlog file position exceeds 4GB limit".to_string(),
+            ));
+        }
+
+        header.event_length = event_length as u32;
+        header.next_event_position = next_event_position as u32;
+        let header = header.serialize()?;
+        self.stream.write_all(&header)?;
+        self.stream.write_all(payload)?;
+
+        if checksum {
+            let mut hasher = crc32fast::Hasher::new();
+            hasher.update(&header);
+            hasher.update(payload);
+            self.stream.write_all(&hasher.finalize().to_le_bytes())?;
+        }
+        self.position = next_event_position as u32;
+        Ok(())
+    }
+
+    fn create_header(&self, event_type: u8) -> EventHeader {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs() as u32)
+            .unwrap_or_default();
+        EventHeader {
+            timestamp,
+            event_type,
+            server_id: self.options.server_id,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::BinlogWriter;
+    use crate::binlog_reader::BinlogReader;
+    use crate::constants::column_type::ColumnType;
+    use crate::errors::Error;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::query_event::QueryEvent;
+    use crate::events::row_events::delete_rows_event::DeleteRowsEvent;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::events::row_events::row_data::{RowData, UpdateRowData};
+    use crate::events::row_events::update_rows_event::UpdateRowsEvent;
+    use crate::events::row_events::write_rows_event::WriteRowsEvent;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::events::xid_event::XidEvent;
+    use crate::metadata::default_charset::DefaultCharset;
+    use crate::metadata::table_metadata::TableMetadata;
+    use crate::providers::mariadb::events::gtid_event::GtidEvent as MariaDbGtidEvent;
+    use crate::providers::mariadb::gtid::gtid::Gtid as MariaDbGtid;
+    use crate::providers::mysql::events::gtid_event::GtidEvent as MySqlGtidEvent;
+    use crate::providers::mysql::gtid::gtid::Gtid as MySqlGtid;
+    use crate::providers::mysql::gtid::uuid::Uuid;
+    use crate::reader_options::ReaderOptions;
+    use crate::writer_options::WriterOptions;
+
+    fn create_header(event_type: u8) -> EventHeader {
+        EventHeader {
+            timestamp: 1689416430,
+            event_type,
+            server_id: 7,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 8,
+        }
+    }
+
+    fn create_query(sql: &str) -> BinlogEvent {
+        BinlogEvent::QueryEvent(QueryEvent {
+            thread_id: 5,
+            duration: 0,
+            error_code: 0,
+            status_variables: Vec::new(),
+            status: Default::default(),
+            database_name: String::from("shop"),
+            sql_statement: sql.to_string(),
+            schema_change: None,
+        })
+    }
+
+    fn read_file(path: &std::path::Path) -> Vec<(EventHeader, BinlogEvent)> {
+        let options = ReaderOptions {
+            verify_checksum: true,
+            ..Default::default()
+        };
+        let reader = BinlogReader::with_options(File::open(path).unwrap(), options).unwrap();
+        let events = reader.map(|x| x.unwrap()).collect();
+        std::fs::remove_file(path).unwrap();
+        events
+    }
+
+    #[test]
+    fn write_readable_files() {
+        let path = std::env::temp_dir().join(format!("writer_{}.000001", std::process::id()));
+        let next_path = std::env::temp_dir().join(format!("writer_{}.000002", std::process::id()));
+
+        let mut writer = BinlogWriter::new(File::create(&path).unwrap()).unwrap();
+        writer
+            .write_event(&create_header(2), &create_query("BEGIN"))
+            .unwrap();
+        let xid = BinlogEvent::XidEvent(XidEvent { xid: 42 });
+        writer.write_event(&create_header(16), &xid).unwrap();
+        writer
+            .rotate("writer.000002", File::create(&next_path).unwrap())
+            .unwrap();
+        writer
+            .write_event(&create_header(2), &create_query("DROP TABLE t"))
+            .unwrap();
+        writer.flush().unwrap();
+
+        let events = read_file(&path);
+        assert_eq!(4, events.len());
+        assert!(matches!(
+            events[0].1,
+            BinlogEvent::FormatDescriptionEvent(_)
+        ));
+        assert_eq!(123, events[0].0.next_event_position);
+        assert!(matches!(&events[1].1, BinlogEvent::QueryEvent(x) if x.sql_statement == "BEGIN"));
+        assert_eq!(8, events[1].0.event_flags);
+        assert!(matches!(
+            events[2].1,
+            BinlogEvent::XidEvent(XidEvent { xid: 42 })
+        ));
+        assert!(
+            matches!(&events[3].1, BinlogEvent::RotateEvent(x) if x.binlog_filename == "writer.000002")
+        );
+        for pair in events.windows(2) {
+            let (previous, next) = (&pair[0].0, &pair[1].0);
+            assert_eq!(
+                previous.next_event_position + next.event_length,
+                next.next_event_position
+            );
+        }
+
+        let events = read_file(&next_path);
+        assert_eq!(2, events.len());
+        assert!(
+            matches!(&events[1].1, BinlogEvent::QueryEvent(x) if x.sql_statement == "DROP TABLE t")
+        );
+        assert_eq!(writer.position(), events[1].0.next_event_position);
+    }
+
+    #[test]
+    fn write_without_checksums() {
+        let path = std::env::temp_dir().join(format!("writer_{}.nocrc", std::process::id()));
+        let options = WriterOptions {
+            checksum: false,
+            ..Default::default()
+        };
+        let file = File::create(&path).unwrap();
+        let mut writer = BinlogWriter::with_options(file, options).unwrap();
+        let xid = BinlogEvent::XidEvent(XidEvent { xid: 42 });
+        writer.write_event(&create_header(16), &xid).unwrap();
+        writer.flush().unwrap();
+
+        let events = read_file(&path);
+        assert_eq!(19 + 8, events[1].0.event_length);
+        assert!(matches!(
+            events[1].1,
+            BinlogEvent::XidEvent(XidEvent { xid: 42 })
+        ));
+    }
+
+    #[test]
+    fn write_row_events() {
+        let path = std::env::temp_dir().join(format!("writer_{}.rows", std::process::id()));
+        let table = TableMapEvent {
+            table_id: 3,
+            database_name: String::from("shop"),
+            table_name: String::from("items"),
+            column_types: vec![ColumnType::Long as u8, ColumnType::VarChar as u8],
+            column_metadata: vec![0, 400],
+            null_bitmap: vec![false, true],
+            table_metadata: Some(TableMetadata {
+                column_names: Some(vec![String::from("id"), String::from("name")]),
+                default_charset: Some(DefaultCharset::new(8, Vec::new())),
+                ..Default::default()
+            }),
+        };
+        let row = |id: u32, name: Option<&str>| {
+            RowData::new(vec![
+                Some(MySqlValue::Int(id)),
+                name.map(|x| MySqlValue::String(x.to_string())),
+            ])
+        };
+        let insert = WriteRowsEvent {
+            table_id: 3,
+            flags: 1,
+            columns_number: 2,
+            columns_present: vec![true, true],
+            rows: vec![row(1, Some("café")), row(2, None)],
+        };
+        let update = UpdateRowsEvent {
+            table_id: 3,
+            flags: 1,
+            columns_number: 2,
+            columns_before_update: vec![true, false],
+            columns_after_update: vec![true, true],
+            rows: vec![UpdateRowData::new(
+                RowData::new(vec![Some(MySqlValue::Int(2)), None]),
+                row(2, Some("tea")),
+            )],
+        };
+        let delete = DeleteRowsEvent {
+            table_id: 3,
+            flags: 1,
+            columns_number: 2,
+            columns_present: vec![true, true],
+            rows: vec![row(1, Some("café"))],
+        };
+
+        let mut writer = BinlogWriter::new(File::create(&path).unwrap()).unwrap();
+        let insert_event = BinlogEvent::WriteRowsEvent(insert.clone());
+        match writer.write_event(&create_header(30), &insert_event) {
+            Err(Error::String(x)) => assert!(x.contains("TableMapEvent")),
+            _ => panic!("Rows must not be written without the table"),
+        }
+        let table_event = BinlogEvent::TableMapEvent(table.clone());
+        writer
+            .write_event(&create_header(19), &table_event)
+            .unwrap();
+        // Version 1 event type is replaced by the written version 2 format
+        writer
+            .write_event(&create_header(23), &insert_event)
+            .unwrap();
+        let update_event = BinlogEvent::UpdateRowsEvent(update.clone());
+        writer
+            .write_event(&create_header(31), &update_event)
+            .unwrap();
+        let delete_event = BinlogEvent::DeleteRowsEvent(delete.clone());
+        writer
+            .write_event(&create_header(32), &delete_event)
+            .unwrap();
+        writer.flush().unwrap();
+
+        let events = read_file(&path);
+        assert_eq!(5, events.len());
+        assert!(matches!(&events[1].1, BinlogEvent::TableMapEvent(x) if *x == table));
+        assert_eq!(30, events[2].0.event_type);
+        match &events[2].1 {
+            BinlogEvent::WriteRowsEvent(x) => assert_eq!(insert.rows, x.rows),
+            _ => panic!("WriteRowsEvent expected"),
+        }
+        match &events[3].1 {
+            BinlogEvent::UpdateRowsEvent(x) => assert_eq!(update.rows, x.rows),
+            _ => panic!("UpdateRowsEvent expected"),
+        }
+        match &events[4].1 {
+            BinlogEvent::DeleteRowsEvent(x) => assert_eq!(delete.rows, x.rows),
+            _ => panic!("DeleteRowsEvent expected"),
+        }
+    }
+
+    #[test]
+    fn write_gtid_events() {
+        let path = std::env::temp_dir().join(format!("writer_{}.gtid", std::process::id()));
+        let mut writer = BinlogWriter::new(File::create(&path).unwrap()).unwrap();
+        let mysql_gtid = BinlogEvent::MySqlGtidEvent(MySqlGtidEvent {
+            gtid: MySqlGtid::new(Uuid::new([7; 16]), 42),
+            flags: 1,
+        });
+        writer.write_event(&create_header(33), &mysql_gtid).unwrap();
+        let mariadb_gtid = BinlogEvent::MariaDbGtidEvent(MariaDbGtidEvent {
+            gtid: MariaDbGtid::new(1, 9, 43),
+            flags: 1,
+        });
+        writer
+            .write_event(&create_header(162), &mariadb_gtid)
+            .unwrap();
+        writer.flush().unwrap();
+
+        let events = read_file(&path);
+        match &events[1].1 {
+            BinlogEvent::MySqlGtidEvent(x) => assert_eq!(
+                "07070707-0707-0707-0707-070707070707:42",
+                x.gtid.to_string()
+            ),
+            _ => panic!("MySqlGtidEvent expected"),
+        }
+        // Server id of MariaDB GTID is written in the header.
+        assert_eq!(9, events[2].0.server_id);
+        match &events[2].1 {
+            BinlogEvent::MariaDbGtidEvent(x) => assert_eq!("1-9-43", x.gtid.to_string()),
+            _ => panic!("MariaDbGtidEvent expected"),
+        }
+    }
+
+    #[test]
+    fn unsupported_events_are_not_written() {
+        let mut writer = BinlogWriter::new(Vec::new()).unwrap();
+        let position = writer.position();
+        match writer.write_event(&create_header(19), &BinlogEvent::UnknownEvent) {
+            Err(Error::String(x)) => assert!(x.contains("write_payload")),
+            _ => panic!("Event must not be serialized"),
+        }
+        assert_eq!(position, writer.position());
+    }
+}
@@ -0,0 +1,54 @@
+//! Conversions between proleptic Gregorian calendar dates and Unix time.
+//! <a href="https://howardhinnant.github.io/date_algorithms.html">See more</a>
+
+/// Gets number of days since 1970-01-01 of the proleptic Gregorian calendar date.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Gets (year, month, day) of the date with the number of days since 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Gets number of seconds since 1970-01-01 00:00:00 of the UTC date and time.
+pub fn seconds_from_civil(
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+) -> i64 {
+    days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, days_from_civil, seconds_from_civil};
+
+    #[test]
+    fn convert_dates() {
+        assert_eq!(0, days_from_civil(1970, 1, 1));
+        assert_eq!(19553, days_from_civil(2023, 7, 15));
+        assert_eq!(-719528, days_from_civil(0, 1, 1));
+        assert_eq!((2023, 7, 15), civil_from_days(19553));
+        assert_eq!((2000, 2, 29), civil_from_days(days_from_civil(2000, 2, 29)));
+        assert_eq!(1689416430, seconds_from_civil(2023, 7, 15, 10, 20, 30));
+    }
+}
@@ -0,0 +1,283 @@
+use encoding_rs::Encoding;
+
+/// Collation of binary strings: BINARY, VARBINARY and BLOB columns.
+pub const BINARY_COLLATION: u32 = 63;
+
+/// Collation utf8mb4_general_ci supported by MySQL and MariaDB.
+pub const UTF8MB4_GENERAL_CI: u32 = 45;
+
+/// Character set of a string value.
+/// <a href="https://dev.mysql.com/doc/refman/8.0/en/charset-charsets.html">See more</a>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    Binary,
+    Ascii,
+    /// MySQL latin1 is cp1252 rather than ISO-8859-1.
+    Latin1,
+    Latin2,
+    Latin5,
+    Latin7,
+    Utf8,
+    Utf8mb4,
+    Ucs2,
+    Utf16,
+    Utf16le,
+    Utf32,
+    Big5,
+    Gbk,
+    Gb2312,
+    Gb18030,
+    Sjis,
+    Cp932,
+    Ujis,
+    Eucjpms,
+    Euckr,
+    Koi8r,
+    Koi8u,
+    Greek,
+    Hebrew,
+    Tis620,
+    Cp866,
+    Cp1250,
+    Cp1251,
+    Cp1256,
+    Cp1257,
+    Macroman,
+}
+
+impl Charset {
+    /// Gets charset of the collation id. Returns None for unsupported charsets.
+    /// <a href="https://dev.mysql.com/doc/refman/8.0/en/information-schema-collations-table.html">See more</a>
+    pub fn from_collation(collation: u32) -> Option<Self> {
+        let charset = match collation {
+            63 => Charset::Binary,
+            11 | 65 => Charset::Ascii,
+            5 | 8 | 15 | 31 | 47 | 48 | 49 | 94 => Charset::Latin1,
+            2 | 9 | 21 | 27 | 77 => Charset::Latin2,
+            30 | 78 => Charset::Latin5,
+            20 | 41 | 42 | 79 => Charset::Latin7,
+            33 | 76 | 83 | 192..=215 | 223 => Charset::Utf8,
+            45 | 46 | 224..=247 | 255..=323 => Charset::Utf8mb4,
+            35 | 90 | 128..=151 | 159 => Charset::Ucs2,
+            54 | 55 | 101..=124 => Charset::Utf16,
+            56 | 62 => Charset::Utf16le,
+            60 | 61 | 160..=183 => Charset::Utf32,
+            1 | 84 => Charset::Big5,
+            28 | 87 => Charset::Gbk,
+            24 | 86 => Charset::Gb2312,
+            248..=250 => Charset::Gb18030,
+            13 | 88 => Charset::Sjis,
+            95 | 96 => Charset::Cp932,
+            12 | 91 => Charset::Ujis,
+            97 | 98 => Charset::Eucjpms,
+            19 | 85 => Charset::Euckr,
+            7 | 74 => Charset::Koi8r,
+            22 | 75 => Charset::Koi8u,
+            25 | 70 => Charset::Greek,
+            16 | 71 => Charset::Hebrew,
+            18 | 89 => Charset::Tis620,
+            36 | 68 => Charset::Cp866,
+            26 | 34 | 44 | 66 | 99 => Charset::Cp1250,
+            14 | 23 | 50 | 51 | 52 => Charset::Cp1251,
+            57 | 67 => Charset::Cp1256,
+            29 | 58 | 59 => Charset::Cp1257,
+            39 | 53 => Charset::Macroman,
+            _ => return None,
+        };
+        Some(charset)
+    }
+
+    /// Decodes the string. Returns None for binary charset and malformed data.
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        let encoding: &'static Encoding = match self {
+            Charset::Binary => return None,
+            Charset::Ascii if !bytes.is_ascii() => return None,
+            Charset::Ascii | Charset::Utf8 | Charset::Utf8mb4 => encoding_rs::UTF_8,
+            Charset::Latin1 => encoding_rs::WINDOWS_1252,
+            Charset::Latin2 => encoding_rs::ISO_8859_2,
+            Charset::Latin5 => encoding_rs::WINDOWS_1254,
+            Charset::Latin7 => encoding_rs::ISO_8859_13,
+            Charset::Ucs2 | Charset::Utf16 => encoding_rs::UTF_16BE,
+            Charset::Utf16le => encoding_rs::UTF_16LE,
+            Charset::Utf32 => return decode_utf32(bytes),
+            Charset::Big5 => encoding_rs::BIG5,
+            Charset::Gbk | Charset::Gb2312 => encoding_rs::GBK,
+            Charset::Gb18030 => encoding_rs::GB18030,
+            Charset::Sjis | Charset::Cp932 => encoding_rs::SHIFT_JIS,
+            Charset::Ujis | Charset::Eucjpms => encoding_rs::EUC_JP,
+            Charset::Euckr => encoding_rs::EUC_KR,
+            Charset::Koi8r => encoding_rs::KOI8_R,
+            Charset::Koi8u => encoding_rs::KOI8_U,
+            Charset::Greek => encoding_rs::ISO_8859_7,
+            Charset::Hebrew => encoding_rs::ISO_8859_8,
+            Charset::Tis620 => encoding_rs::WINDOWS_874,
+            Charset::Cp866 => encoding_rs::IBM866,
+            Charset::Cp1250 => encoding_rs::WINDOWS_1250,
+            Charset::Cp1251 => encoding_rs::WINDOWS_1251,
+            Charset::Cp1256 => encoding_rs::WINDOWS_1256,
+            Charset::Cp1257 => encoding_rs::WINDOWS_1257,
+            Charset::Macroman => encoding_rs::MACINTOSH,
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|x| x.into_owned())
+    }
+
+    /// Encodes the string. Returns None for binary charset and characters the charset lacks.
+    pub fn encode(&self, value: &str) -> Option<Vec<u8>> {
+        let encoding: &'static Encoding = match self {
+            Charset::Binary => return None,
+            Charset::Ascii if !value.is_ascii() => return None,
+            Charset::Ascii | Charset::Utf8 | Charset::Utf8mb4 => encoding_rs::UTF_8,
+            Charset::Latin1 => encoding_rs::WINDOWS_1252,
+            Charset::Latin2 => encoding_rs::ISO_8859_2,
+            Charset::Latin5 => encoding_rs::WINDOWS_1254,
+            Charset::Latin7 => encoding_rs::ISO_8859_13,
+            // UCS-2 has no surrogate pairs.
+            Charset::Ucs2 if value.chars().any(|x| x as u32 > 0xFFFF) => return None,
+            Charset::Ucs2 | Charset::Utf16 => {
+                return Some(value.encode_utf16().flat_map(u16::to_be_bytes).collect())
+            }
+            Charset::Utf16le => {
+                return Some(value.encode_utf16().flat_map(u16::to_le_bytes).collect())
+            }
+            Charset::Utf32 => {
+                return Some(
+                    value
+                        .chars()
+                        .flat_map(|x| (x as u32).to_be_bytes())
+                        .collect(),
+                )
+            }
+            Charset::Big5 => encoding_rs::BIG5,
+            Charset::Gbk | Charset::Gb2312 => encoding_rs::GBK,
+            Charset::Gb18030 => encoding_rs::GB18030,
+            Charset::Sjis | Charset::Cp932 => encoding_rs::SHIFT_JIS,
+            Charset::Ujis | Charset::Eucjpms => encoding_rs::EUC_JP,
+            Charset::Euckr => encoding_rs::EUC_KR,
+            Charset::Koi8r => encoding_rs::KOI8_R,
+            Charset::Koi8u => encoding_rs::KOI8_U,
+            Charset::Greek => encoding_rs::ISO_8859_7,
+            Charset::Hebrew => encoding_rs::ISO_8859_8,
+            Charset::Tis620 => encoding_rs::WINDOWS_874,
+            Charset::Cp866 => encoding_rs::IBM866,
+            Charset::Cp1250 => encoding_rs::WINDOWS_1250,
+            Charset::Cp1251 => encoding_rs::WINDOWS_1251,
+            Charset::Cp1256 => encoding_rs::WINDOWS_1256,
+            Charset::Cp1257 => encoding_rs::WINDOWS_1257,
+            Charset::Macroman => encoding_rs::MACINTOSH,
+        };
+        match encoding.encode(value) {
+            (_, _, true) => None,
+            (bytes, _, false) => Some(bytes.into_owned()),
+        }
+    }
+}
+
+/// Decodes string of the collation.
+/// UTF-8 is assumed when the collation is unknown or not logged.
+/// Returns the bytes back for binary collation and data that cannot be decoded.
+pub fn decode_string(bytes: Vec<u8>, collation: Option<u32>) -> Result<String, Vec<u8>> {
+    let charset = match collation {
+        Some(x) => Charset::from_collation(x).unwrap_or(Charset::Utf8mb4),
+        None => Charset::Utf8mb4,
+    };
+    match charset {
+        Charset::Binary => Err(bytes),
+        Charset::Utf8 | Charset::Utf8mb4 => String::from_utf8(bytes).map_err(|e| e.into_bytes()),
+        _ => charset.decode(&bytes).ok_or(bytes),
+    }
+}
+
+/// Encodes string in the charset of the collation. See decode_string.
+/// UTF-8 is written when the collation is unknown, not logged or binary.
+/// Returns None when the string has characters the charset lacks.
+pub fn encode_string(value: &str, collation: Option<u32>) -> Option<Vec<u8>> {
+    let charset = match collation {
+        Some(x) => Charset::from_collation(x).unwrap_or(Charset::Utf8mb4),
+        None => Charset::Utf8mb4,
+    };
+    match charset {
+        Charset::Binary | Charset::Utf8 | Charset::Utf8mb4 => Some(value.as_bytes().to_vec()),
+        _ => charset.encode(value),
+    }
+}
+
+fn decode_utf32(bytes: &[u8]) -> Option<String> {
+    let chunks = bytes.chunks_exact(4);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    chunks
+        .map(|x| char::from_u32(u32::from_be_bytes([x[0], x[1], x[2], x[3]])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_string, encode_string, Charset, BINARY_COLLATION};
+
+    #[test]
+    fn decode_legacy_charsets() {
+        // latin1_swedish_ci
+        assert_eq!(
+            Ok("café".to_string()),
+            decode_string(vec![b'c', b'a', b'f', 0xE9], Some(8))
+        );
+        // gbk_chinese_ci
+        assert_eq!(
+            Ok("中文".to_string()),
+            decode_string(vec![0xD6, 0xD0, 0xCE, 0xC4], Some(28))
+        );
+        // sjis_japanese_ci
+        assert_eq!(
+            Ok("日本".to_string()),
+            decode_string(vec![0x93, 0xFA, 0x96, 0x7B], Some(13))
+        );
+        // ucs2_general_ci
+        assert_eq!(
+            Ok("é".to_string()),
+            decode_string(vec![0x00, 0xE9], Some(35))
+        );
+        // utf32_general_ci
+        assert_eq!(
+            Ok("é".to_string()),
+            decode_string(vec![0, 0, 0, 0xE9], Some(60))
+        );
+    }
+
+    #[test]
+    fn keep_bytes_that_cannot_be_decoded() {
+        assert_eq!(
+            Err(vec![0xE9]),
+            decode_string(vec![0xE9], Some(BINARY_COLLATION))
+        );
+        assert_eq!(Err(vec![0xE9]), decode_string(vec![0xE9], None));
+        assert_eq!(Err(vec![0xE9]), decode_string(vec![0xE9], Some(11)));
+        assert_eq!(Some(Charset::Utf8mb4), Charset::from_collation(255));
+        assert_eq!(None, Charset::from_collation(3));
+    }
+
+    #[test]
+    fn encode_legacy_charsets() {
+        for (value, collation) in [
+            ("café", 8),
+            ("中文", 28),
+            ("日本", 13),
+            ("é", 35),
+            ("é😀", 54),
+            ("é😀", 56),
+            ("é😀", 60),
+        ] {
+            let bytes = encode_string(value, Some(collation)).unwrap();
+            assert_eq!(Ok(value.to_string()), decode_string(bytes, Some(collation)));
+        }
+        assert_eq!(Some(vec![0xE9]), encode_string("é", Some(8)));
+        assert_eq!(Some(vec![0xC3, 0xA9]), encode_string("é", None));
+
+        // Characters missing from the charset
+        assert_eq!(None, encode_string("中", Some(8)));
+        assert_eq!(None, encode_string("é", Some(11)));
+        assert_eq!(None, encode_string("😀", Some(35)));
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::binlog_options::BinlogOptions;
+use crate::errors::Error;
+use crate::providers::mariadb::gtid::gtid_list::GtidList;
+use crate::providers::mysql::gtid::gtid_set::GtidSet;
+use crate::starting_strategy::StartingStrategy;
+
+/// Replication position saved by a CheckpointStore.
+/// Serialized as `key=value` lines, see Display and `Checkpoint::parse`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    /// Gets binlog coordinates to resume replication from.
+    pub binlog: BinlogOptions,
+}
+
+impl Checkpoint {
+    pub fn new(binlog: BinlogOptions) -> Self {
+        Self { binlog }
+    }
+
+    /// Parses checkpoint serialized with Display.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let mut binlog = BinlogOptions::from_start();
+        for line in value.lines().filter(|x| !x.is_empty()) {
+            let (key, value) = match line.split_once('=') {
+                Some(x) => x,
+                None => return Err(Error::String(format!("Invalid checkpoint line: {}", line))),
+            };
+            match key {
+                "starting_strategy" => {
+                    binlog.starting_strategy = match value {
+                        "FromStart" => StartingStrategy::FromStart,
+                        "FromEnd" => StartingStrategy::FromEnd,
+                        "FromPosition" => StartingStrategy::FromPosition,
+                        "FromGtid" => StartingStrategy::FromGtid,
+                        _ => {
+                            let message = format!("Unknown starting strategy: {}", value);
+                            return Err(Error::String(message));
+                        }
+                    }
+                }
+                "filename" => binlog.filename = value.to_string(),
+                "position" => binlog.position = value.parse()?,
+                "gtid_set" => binlog.gtid_set = Some(GtidSet::parse(value)?),
+                "gtid_list" => binlog.gtid_list = Some(GtidList::parse(value)?),
+                _ => return Err(Error::String(format!("Unknown checkpoint key: {}", key))),
+            }
+        }
+        Ok(Self { binlog })
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "starting_strategy={:?}", self.binlog.starting_strategy)?;
+        writeln!(f, "filename={}", self.binlog.filename)?;
+        writeln!(f, "position={}", self.binlog.position)?;
+        if let Some(gtid_set) = &self.binlog.gtid_set {
+            writeln!(f, "gtid_set={}", gtid_set)?;
+        }
+        if let Some(gtid_list) = &self.binlog.gtid_list {
+            writeln!(f, "gtid_list={}", gtid_list)?;
+        }
+        Ok(())
+    }
+}
+
+/// Storage of the committed replication position.
+/// Used by BinlogClient to resume replication after restart.
+pub trait CheckpointStore: Send {
+    /// Gets the last saved checkpoint. Returns None if nothing is saved yet.
+    fn load(&mut self) -> Result<Option<Checkpoint>, Error>;
+
+    /// Saves the checkpoint replacing the previous one.
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<(), Error>;
+}
+
+/// Stores checkpoint in a file.
+/// The checkpoint is written to a temporary file that is renamed over the previous one,
+/// so the file always contains a complete checkpoint after a crash.
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&mut self) -> Result<Option<Checkpoint>, Error> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Checkpoint::parse(&fs::read_to_string(&self.path)?)?))
+    }
+
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<(), Error> {
+        replace_file(&self.path, checkpoint.to_string().as_bytes())
+    }
+}
+
+/// Writes the data to a temporary file and renames it over the file.
+/// The directory is synced after the rename, so the new file survives a crash.
+pub(crate) fn replace_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+
+    // Directories can't be opened as files on Windows.
+    #[cfg(not(windows))]
+    {
+        let directory = match path.parent() {
+            Some(x) if !x.as_os_str().is_empty() => x,
+            _ => Path::new("."),
+        };
+        File::open(directory)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Keeps checkpoint in memory. Clones share the same checkpoint.
+#[derive(Clone, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoint: Arc<Mutex<Option<Checkpoint>>>,
+}
+
+impl MemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the last saved checkpoint.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.checkpoint.lock().unwrap().clone()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&mut self) -> Result<Option<Checkpoint>, Error> {
+        Ok(self.checkpoint())
+    }
+
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<(), Error> {
+        *self.checkpoint.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Checkpoint, CheckpointStore, FileCheckpointStore, MemoryCheckpointStore};
+    use crate::binlog_client::BinlogClient;
+    use crate::binlog_options::BinlogOptions;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::xid_event::XidEvent;
+    use crate::providers::mariadb::gtid::gtid_list::GtidList;
+    use crate::providers::mysql::gtid::gtid_set::GtidSet;
+    use crate::starting_strategy::StartingStrategy;
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let gtid_set = "9b1ad0d4-19f4-11ee-9d6b-0242ac120002:1-10:15";
+        let mut binlog = BinlogOptions::from_mysql_gtid(GtidSet::parse(gtid_set).unwrap());
+        binlog.filename = String::from("mysql-bin.000003");
+        binlog.position = 1200;
+
+        let checkpoint = Checkpoint::parse(&Checkpoint::new(binlog).to_string()).unwrap();
+        assert_eq!(
+            StartingStrategy::FromGtid,
+            checkpoint.binlog.starting_strategy
+        );
+        assert_eq!("mysql-bin.000003", checkpoint.binlog.filename);
+        assert_eq!(1200, checkpoint.binlog.position);
+        assert_eq!(gtid_set, checkpoint.binlog.gtid_set.unwrap().to_string());
+
+        let gtid_list = GtidList::parse("0-1-270,1-2-55").unwrap();
+        let binlog = BinlogOptions::from_mariadb_gtid(gtid_list);
+        let checkpoint = Checkpoint::parse(&Checkpoint::new(binlog).to_string()).unwrap();
+        assert_eq!(
+            "0-1-270,1-2-55",
+            checkpoint.binlog.gtid_list.unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn file_store_replaces_checkpoint() {
+        let path = std::env::temp_dir().join(format!("checkpoint_{}", std::process::id()));
+        let mut store = FileCheckpointStore::new(&path);
+        assert!(store.load().unwrap().is_none());
+
+        for position in [4, 1200] {
+            let binlog = BinlogOptions::from_position(String::from("mysql-bin.000001"), position);
+            store.save(&Checkpoint::new(binlog)).unwrap();
+        }
+        let checkpoint = store.load().unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(1200, checkpoint.binlog.position);
+        assert_eq!(
+            StartingStrategy::FromPosition,
+            checkpoint.binlog.starting_strategy
+        );
+    }
+
+    #[test]
+    fn client_resumes_from_store() {
+        let mut store = MemoryCheckpointStore::new();
+        let binlog = BinlogOptions::from_position(String::from("mysql-bin.000002"), 1200);
+        store.save(&Checkpoint::new(binlog)).unwrap();
+
+        let mut client = BinlogClient::new(Default::default());
+        client
+            .set_checkpoint_store(Box::new(store.clone()), Duration::ZERO)
+            .unwrap();
+        assert_eq!("mysql-bin.000002", client.options.binlog.filename);
+        assert_eq!(1200, client.options.binlog.position);
+
+        let header = EventHeader {
+            timestamp: 0,
+            event_type: 16,
+            server_id: 1,
+            event_length: 31,
+            next_event_position: 1231,
+            event_flags: 0,
+        };
+        let event = BinlogEvent::XidEvent(XidEvent { xid: 1 });
+        client.commit(&header, &event).unwrap();
+        assert_eq!(1231, store.checkpoint().unwrap().binlog.position);
+    }
+}
@@ -67,7 +67,30 @@ impl BinlogClient {
         ChecksumType::from_name(&result_set[0].cells[0])
     }
 
-    async fn read_result_set(
+    /// Executes statement that doesn't return a result set.
+    pub(crate) async fn execute(
+        &self,
+        channel: &mut PacketChannel,
+        sql: &str,
+    ) -> Result<(), Error> {
+        let command = QueryCommand::new(sql.to_string());
+        channel.write_packet(&command.serialize()?, 0).await?;
+        let (packet, _) = channel.read_packet().await?;
+        check_error_packet(&packet, &format!("Executing '{}' error.", sql))?;
+        Ok(())
+    }
+
+    pub(crate) async fn query(
+        &self,
+        channel: &mut PacketChannel,
+        sql: &str,
+    ) -> Result<Vec<ResultSetRowPacket>, Error> {
+        let command = QueryCommand::new(sql.to_string());
+        channel.write_packet(&command.serialize()?, 0).await?;
+        self.read_result_set(channel).await
+    }
+
+    pub(crate) async fn read_result_set(
         &self,
         channel: &mut PacketChannel,
     ) -> Result<Vec<ResultSetRowPacket>, Error> {
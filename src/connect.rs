@@ -49,13 +49,16 @@ impl BinlogClient {
                     .write_packet(&ssl_command.serialize()?, seq_num)
                     .await?;
                 seq_num += 1;
-                channel.upgrade_to_ssl();
+                channel.upgrade_to_ssl(&self.options).await?;
                 use_ssl = true;
             }
         }
 
-        let auth_command =
+        let mut auth_command =
             AuthenticateCommand::new(&self.options, handshake, auth_plugin, UTF8_MB4_GENERAL_CI);
+        if use_ssl {
+            auth_command.client_capabilities |= capability_flags::SSL as u32;
+        }
         channel
             .write_packet(&auth_command.serialize()?, seq_num)
             .await?;
@@ -0,0 +1,1098 @@
+use crate::ddl::lexer::{first_word, tokenize, Token, TokenKind};
+use crate::ddl::schema_change::{
+    AlterOperation, ColumnDefinition, ColumnPosition, IndexDefinition, IndexKind, SchemaChange,
+    TableName,
+};
+use crate::errors::Error;
+
+/// Keywords DDL statements start with.
+const DDL_KEYWORDS: &[&str] = &["CREATE", "ALTER", "DROP", "RENAME", "TRUNCATE"];
+
+/// Bit of sql_mode disabling backslash escapes in string literals.
+const MODE_NO_BACKSLASH_ESCAPES: u64 = 1 << 20;
+
+/// Parses CREATE/ALTER/DROP/RENAME/TRUNCATE TABLE, CREATE/DROP INDEX and CREATE/DROP DATABASE
+/// statements. Tables without database name are resolved using the default database.
+/// Returns None for other statements, including ones on temporary tables.
+/// <a href="https://dev.mysql.com/doc/refman/8.0/en/sql-data-definition-statements.html">See more</a>
+pub fn parse_ddl(sql: &str, database: &str) -> Result<Option<SchemaChange>, Error> {
+    parse_ddl_with_sql_mode(sql, database, 0)
+}
+
+/// Parses DDL statement logged with the sql_mode, e.g. from QueryEvent status variables.
+/// String literals are read without backslash escapes if NO_BACKSLASH_ESCAPES is set.
+pub fn parse_ddl_with_sql_mode(
+    sql: &str,
+    database: &str,
+    sql_mode: u64,
+) -> Result<Option<SchemaChange>, Error> {
+    // Statement based DML can be large, so it's not tokenized.
+    let keyword = first_word(sql);
+    if !DDL_KEYWORDS.iter().any(|x| x.eq_ignore_ascii_case(keyword)) {
+        return Ok(None);
+    }
+
+    let no_backslash_escapes = sql_mode & MODE_NO_BACKSLASH_ESCAPES != 0;
+    let mut parser = Parser {
+        sql,
+        tokens: tokenize(sql, no_backslash_escapes)?,
+        pos: 0,
+        database,
+        no_backslash_escapes,
+    };
+    parser.parse_statement()
+}
+
+/// Element of CREATE TABLE definition list.
+enum TableElement {
+    Column(ColumnDefinition, Vec<IndexDefinition>),
+    Index(IndexDefinition),
+    /// CHECK constraint or period definition.
+    Other,
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    database: &'a str,
+    no_backslash_escapes: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_statement(&mut self) -> Result<Option<SchemaChange>, Error> {
+        if self.accept_keyword("CREATE") {
+            self.accept_keywords(&["OR", "REPLACE"]);
+            if self.is_keyword("TEMPORARY") {
+                return Ok(None);
+            }
+            if self.accept_keyword("TABLE") {
+                return self.parse_create_table().map(Some);
+            }
+            if self.accept_keyword("DATABASE") || self.accept_keyword("SCHEMA") {
+                self.accept_keywords(&["IF", "NOT", "EXISTS"]);
+                let database = self.parse_identifier()?;
+                return Ok(Some(SchemaChange::CreateDatabase { database }));
+            }
+            let _ = self.accept_keyword("ONLINE") || self.accept_keyword("OFFLINE");
+            let kind = if self.accept_keyword("UNIQUE") {
+                IndexKind::Unique
+            } else if self.accept_keyword("FULLTEXT") {
+                IndexKind::Fulltext
+            } else if self.accept_keyword("SPATIAL") {
+                IndexKind::Spatial
+            } else {
+                IndexKind::Index
+            };
+            if self.accept_keyword("INDEX") {
+                return self.parse_create_index(kind).map(Some);
+            }
+        } else if self.accept_keyword("ALTER") {
+            let _ = self.accept_keyword("ONLINE") || self.accept_keyword("OFFLINE");
+            self.accept_keyword("IGNORE");
+            if self.accept_keyword("TABLE") {
+                return self.parse_alter_table().map(Some);
+            }
+        } else if self.accept_keyword("DROP") {
+            if self.is_keyword("TEMPORARY") {
+                return Ok(None);
+            }
+            if self.accept_keyword("TABLE") || self.accept_keyword("TABLES") {
+                return self.parse_drop_table().map(Some);
+            }
+            if self.accept_keyword("DATABASE") || self.accept_keyword("SCHEMA") {
+                self.accept_keywords(&["IF", "EXISTS"]);
+                let database = self.parse_identifier()?;
+                return Ok(Some(SchemaChange::DropDatabase { database }));
+            }
+            let _ = self.accept_keyword("ONLINE") || self.accept_keyword("OFFLINE");
+            if self.accept_keyword("INDEX") {
+                return self.parse_drop_index().map(Some);
+            }
+        } else if self.accept_keyword("RENAME") {
+            if self.accept_keyword("TABLE") || self.accept_keyword("TABLES") {
+                return self.parse_rename_table().map(Some);
+            }
+        } else if self.accept_keyword("TRUNCATE") {
+            self.accept_keyword("TABLE");
+            let table = self.parse_table_name()?;
+            return Ok(Some(SchemaChange::TruncateTable { table }));
+        }
+        Ok(None)
+    }
+
+    fn parse_create_table(&mut self) -> Result<SchemaChange, Error> {
+        self.accept_keywords(&["IF", "NOT", "EXISTS"]);
+        let table = self.parse_table_name()?;
+
+        let mut columns = Vec::new();
+        let mut indexes = Vec::new();
+        let mut like = None;
+
+        if self.accept_keyword("LIKE") {
+            like = Some(self.parse_table_name()?);
+        } else if self.is_symbol('(') && self.is_keyword_at(1, "LIKE") {
+            self.pos += 2;
+            like = Some(self.parse_table_name()?);
+            self.expect_symbol(')')?;
+        } else if self.accept_symbol('(') {
+            loop {
+                match self.parse_table_element()? {
+                    TableElement::Column(column, column_indexes) => {
+                        columns.push(column);
+                        indexes.extend(column_indexes);
+                    }
+                    TableElement::Index(index) => indexes.push(index),
+                    TableElement::Other => {}
+                }
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            self.expect_symbol(')')?;
+        }
+
+        // Table options, partitioning and SELECT part are ignored.
+        Ok(SchemaChange::CreateTable {
+            table,
+            columns,
+            indexes,
+            like,
+        })
+    }
+
+    fn parse_create_index(&mut self, kind: IndexKind) -> Result<SchemaChange, Error> {
+        self.accept_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = self.parse_identifier()?;
+        if self.accept_keyword("USING") {
+            self.next();
+        }
+        self.expect_keyword("ON")?;
+        let table = self.parse_table_name()?;
+        let columns = self.parse_key_parts()?;
+
+        let index = IndexDefinition {
+            name: Some(name),
+            kind,
+            columns,
+        };
+        Ok(SchemaChange::AlterTable {
+            table,
+            operations: vec![AlterOperation::AddIndex(index)],
+        })
+    }
+
+    fn parse_drop_index(&mut self) -> Result<SchemaChange, Error> {
+        self.accept_keywords(&["IF", "EXISTS"]);
+        let name = self.parse_identifier()?;
+        self.expect_keyword("ON")?;
+        let table = self.parse_table_name()?;
+
+        let operation = if name.eq_ignore_ascii_case("PRIMARY") {
+            AlterOperation::DropPrimaryKey
+        } else {
+            AlterOperation::DropIndex { name }
+        };
+        Ok(SchemaChange::AlterTable {
+            table,
+            operations: vec![operation],
+        })
+    }
+
+    fn parse_drop_table(&mut self) -> Result<SchemaChange, Error> {
+        self.accept_keywords(&["IF", "EXISTS"]);
+        let mut tables = vec![self.parse_table_name()?];
+        while self.accept_symbol(',') {
+            tables.push(self.parse_table_name()?);
+        }
+        Ok(SchemaChange::DropTable { tables })
+    }
+
+    fn parse_rename_table(&mut self) -> Result<SchemaChange, Error> {
+        let mut renames = Vec::new();
+        loop {
+            let from = self.parse_table_name()?;
+            self.expect_keyword("TO")?;
+            let to = self.parse_table_name()?;
+            renames.push((from, to));
+            if !self.accept_symbol(',') {
+                break;
+            }
+        }
+        Ok(SchemaChange::RenameTable { renames })
+    }
+
+    fn parse_alter_table(&mut self) -> Result<SchemaChange, Error> {
+        let table = self.parse_table_name()?;
+        let mut operations = Vec::new();
+        while self.peek().is_some() {
+            operations.extend(self.parse_alter_operation()?);
+            self.skip_to_separator();
+            // Table options may be separated by spaces.
+            self.accept_symbol(',');
+        }
+        Ok(SchemaChange::AlterTable { table, operations })
+    }
+
+    fn parse_alter_operation(&mut self) -> Result<Vec<AlterOperation>, Error> {
+        let start = self.pos;
+        let operation = if self.accept_keyword("ADD") {
+            let column = self.accept_keyword("COLUMN");
+            if !column && self.is_index_keyword() {
+                return match self.parse_table_element()? {
+                    TableElement::Index(index) => Ok(vec![AlterOperation::AddIndex(index)]),
+                    _ => Ok(vec![AlterOperation::Other(self.text(start, self.pos))]),
+                };
+            }
+            if !column && self.is_keyword("PARTITION") {
+                self.skip_to_separator();
+                return Ok(vec![AlterOperation::Other(self.text(start, self.pos))]);
+            }
+
+            self.accept_keywords(&["IF", "NOT", "EXISTS"]);
+            let mut operations = Vec::new();
+            if self.accept_symbol('(') {
+                loop {
+                    let (column, indexes) = self.parse_column_definition()?;
+                    operations.push(AlterOperation::AddColumn {
+                        column,
+                        position: None,
+                    });
+                    operations.extend(indexes.into_iter().map(AlterOperation::AddIndex));
+                    if !self.accept_symbol(',') {
+                        break;
+                    }
+                }
+                self.expect_symbol(')')?;
+            } else {
+                let (column, indexes) = self.parse_column_definition()?;
+                let position = self.parse_column_position()?;
+                operations.push(AlterOperation::AddColumn { column, position });
+                operations.extend(indexes.into_iter().map(AlterOperation::AddIndex));
+            }
+            return Ok(operations);
+        } else if self.accept_keyword("DROP") {
+            if self.accept_keyword("PRIMARY") {
+                self.expect_keyword("KEY")?;
+                AlterOperation::DropPrimaryKey
+            } else if self.accept_keyword("INDEX") || self.accept_keyword("KEY") {
+                self.accept_keywords(&["IF", "EXISTS"]);
+                let name = self.parse_identifier()?;
+                AlterOperation::DropIndex { name }
+            } else if self.accept_keyword("FOREIGN") {
+                self.expect_keyword("KEY")?;
+                self.accept_keywords(&["IF", "EXISTS"]);
+                let name = self.parse_identifier()?;
+                AlterOperation::DropForeignKey { name }
+            } else if self.is_keyword("CHECK")
+                || self.is_keyword("CONSTRAINT")
+                || self.is_keyword("PARTITION")
+            {
+                self.skip_to_separator();
+                AlterOperation::Other(self.text(start, self.pos))
+            } else {
+                self.accept_keyword("COLUMN");
+                self.accept_keywords(&["IF", "EXISTS"]);
+                let name = self.parse_identifier()?;
+                AlterOperation::DropColumn { name }
+            }
+        } else if self.accept_keyword("MODIFY") {
+            self.accept_keyword("COLUMN");
+            self.accept_keywords(&["IF", "EXISTS"]);
+            let (column, _) = self.parse_column_definition()?;
+            let position = self.parse_column_position()?;
+            AlterOperation::ModifyColumn { column, position }
+        } else if self.accept_keyword("CHANGE") {
+            self.accept_keyword("COLUMN");
+            self.accept_keywords(&["IF", "EXISTS"]);
+            let old_name = self.parse_identifier()?;
+            let (column, _) = self.parse_column_definition()?;
+            let position = self.parse_column_position()?;
+            AlterOperation::ChangeColumn {
+                old_name,
+                column,
+                position,
+            }
+        } else if self.accept_keyword("RENAME") {
+            if self.accept_keyword("COLUMN") {
+                let old_name = self.parse_identifier()?;
+                self.expect_keyword("TO")?;
+                let new_name = self.parse_identifier()?;
+                AlterOperation::RenameColumn { old_name, new_name }
+            } else if self.accept_keyword("INDEX") || self.accept_keyword("KEY") {
+                let old_name = self.parse_identifier()?;
+                self.expect_keyword("TO")?;
+                let new_name = self.parse_identifier()?;
+                AlterOperation::RenameIndex { old_name, new_name }
+            } else {
+                let _ = self.accept_keyword("TO") || self.accept_keyword("AS");
+                let new_table = self.parse_table_name()?;
+                AlterOperation::RenameTable { new_table }
+            }
+        } else if self.accept_keyword("ALTER") {
+            self.accept_keyword("COLUMN");
+            let name = self.parse_identifier()?;
+            if self.accept_keywords(&["SET", "DEFAULT"]) {
+                let default = Some(self.parse_expression()?);
+                AlterOperation::AlterColumnDefault { name, default }
+            } else if self.accept_keywords(&["DROP", "DEFAULT"]) {
+                AlterOperation::AlterColumnDefault {
+                    name,
+                    default: None,
+                }
+            } else {
+                self.skip_to_separator();
+                AlterOperation::Other(self.text(start, self.pos))
+            }
+        } else {
+            self.skip_to_separator();
+            if self.pos == start {
+                return Err(self.error("unexpected token"));
+            }
+            AlterOperation::Other(self.text(start, self.pos))
+        };
+        Ok(vec![operation])
+    }
+
+    fn parse_column_position(&mut self) -> Result<Option<ColumnPosition>, Error> {
+        if self.accept_keyword("FIRST") {
+            return Ok(Some(ColumnPosition::First));
+        }
+        if self.accept_keyword("AFTER") {
+            return Ok(Some(ColumnPosition::After(self.parse_identifier()?)));
+        }
+        Ok(None)
+    }
+
+    fn is_index_keyword(&self) -> bool {
+        [
+            "INDEX",
+            "KEY",
+            "UNIQUE",
+            "PRIMARY",
+            "FULLTEXT",
+            "SPATIAL",
+            "FOREIGN",
+            "CONSTRAINT",
+            "CHECK",
+        ]
+        .iter()
+        .any(|x| self.is_keyword(x))
+    }
+
+    fn parse_table_element(&mut self) -> Result<TableElement, Error> {
+        let mut constraint = None;
+        if self.accept_keyword("CONSTRAINT")
+            && !["PRIMARY", "UNIQUE", "FOREIGN", "CHECK"]
+                .iter()
+                .any(|x| self.is_keyword(x))
+        {
+            constraint = Some(self.parse_identifier()?);
+        }
+
+        let kind = if self.accept_keyword("PRIMARY") {
+            self.expect_keyword("KEY")?;
+            IndexKind::Primary
+        } else if self.accept_keyword("UNIQUE") {
+            let _ = self.accept_keyword("INDEX") || self.accept_keyword("KEY");
+            IndexKind::Unique
+        } else if self.accept_keyword("FOREIGN") {
+            self.expect_keyword("KEY")?;
+            IndexKind::ForeignKey
+        } else if self.accept_keyword("FULLTEXT") {
+            let _ = self.accept_keyword("INDEX") || self.accept_keyword("KEY");
+            IndexKind::Fulltext
+        } else if self.accept_keyword("SPATIAL") {
+            let _ = self.accept_keyword("INDEX") || self.accept_keyword("KEY");
+            IndexKind::Spatial
+        } else if self.accept_keyword("INDEX") || self.accept_keyword("KEY") {
+            IndexKind::Index
+        } else if constraint.is_some()
+            || self.is_keyword("CHECK")
+            || (self.is_keyword("PERIOD") && self.is_keyword_at(1, "FOR"))
+        {
+            self.skip_to_separator();
+            return Ok(TableElement::Other);
+        } else {
+            let (column, indexes) = self.parse_column_definition()?;
+            return Ok(TableElement::Column(column, indexes));
+        };
+
+        self.accept_keywords(&["IF", "NOT", "EXISTS"]);
+        let mut name = constraint;
+        if kind != IndexKind::Primary && !self.is_symbol('(') && !self.is_keyword("USING") {
+            name = Some(self.parse_identifier()?);
+        }
+        if self.accept_keyword("USING") {
+            self.next();
+        }
+        let columns = self.parse_key_parts()?;
+        // Index options and foreign key references
+        self.skip_to_separator();
+
+        if kind == IndexKind::Primary {
+            name = None;
+        }
+        Ok(TableElement::Index(IndexDefinition {
+            name,
+            kind,
+            columns,
+        }))
+    }
+
+    fn parse_key_parts(&mut self) -> Result<Vec<String>, Error> {
+        self.expect_symbol('(')?;
+        let mut columns = Vec::new();
+        loop {
+            if self.is_symbol('(') {
+                // Functional key part
+                let start = self.pos;
+                self.skip_parentheses()?;
+                columns.push(self.text(start, self.pos));
+            } else {
+                columns.push(self.parse_identifier()?);
+                // Prefix length
+                if self.is_symbol('(') {
+                    self.skip_parentheses()?;
+                }
+            }
+            let _ = self.accept_keyword("ASC") || self.accept_keyword("DESC");
+            if !self.accept_symbol(',') {
+                break;
+            }
+        }
+        self.expect_symbol(')')?;
+        Ok(columns)
+    }
+
+    /// Parses column definition and indexes declared as column attributes.
+    fn parse_column_definition(
+        &mut self,
+    ) -> Result<(ColumnDefinition, Vec<IndexDefinition>), Error> {
+        let name = self.parse_identifier()?;
+        let mut column = ColumnDefinition {
+            name: name.clone(),
+            data_type: self.parse_data_type()?,
+            unsigned: false,
+            nullable: true,
+            default: None,
+            auto_increment: false,
+            charset: None,
+            collation: None,
+            comment: None,
+        };
+        let mut indexes = Vec::new();
+        let index = |kind| IndexDefinition {
+            name: None,
+            kind,
+            columns: vec![name.clone()],
+        };
+
+        // Column position of ALTER TABLE follows the attributes.
+        while self.peek().is_some()
+            && !self.is_symbol(',')
+            && !self.is_symbol(')')
+            && !self.is_keyword("FIRST")
+            && !self.is_keyword("AFTER")
+        {
+            if self.accept_keyword("UNSIGNED") {
+                column.unsigned = true;
+            } else if self.accept_keyword("SIGNED") || self.accept_keyword("ZEROFILL") {
+            } else if self.accept_keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if self.accept_keyword("NULL") {
+                column.nullable = true;
+            } else if self.accept_keyword("DEFAULT") {
+                column.default = Some(self.parse_expression()?);
+            } else if self.accept_keyword("AUTO_INCREMENT") {
+                column.auto_increment = true;
+            } else if self.accept_keyword("COMMENT") {
+                column.comment = Some(self.parse_string()?);
+            } else if self.accept_keywords(&["CHARACTER", "SET"]) || self.accept_keyword("CHARSET")
+            {
+                column.charset = Some(self.parse_identifier()?);
+            } else if self.accept_keyword("COLLATE") {
+                column.collation = Some(self.parse_identifier()?);
+            } else if self.accept_keyword("PRIMARY") || self.is_keyword("KEY") {
+                self.accept_keyword("KEY");
+                column.nullable = false;
+                indexes.push(index(IndexKind::Primary));
+            } else if self.accept_keyword("UNIQUE") {
+                self.accept_keyword("KEY");
+                indexes.push(index(IndexKind::Unique));
+            } else if self.accept_keywords(&["ON", "UPDATE"]) {
+                self.parse_expression()?;
+            } else if self.accept_keyword("REFERENCES") {
+                self.skip_to_separator();
+            } else if self.is_symbol('(') {
+                // Generated column expression and CHECK constraint
+                self.skip_parentheses()?;
+            } else {
+                self.next();
+            }
+        }
+        Ok((column, indexes))
+    }
+
+    fn parse_data_type(&mut self) -> Result<String, Error> {
+        let mut data_type = match self.next() {
+            Some(TokenKind::Word(x)) => x.to_lowercase(),
+            _ => return Err(self.error("expected data type")),
+        };
+        if data_type == "national" || data_type == "long" {
+            if let Some(TokenKind::Word(x)) = self.peek() {
+                data_type = format!("{} {}", data_type, x.to_lowercase());
+                self.pos += 1;
+            }
+        }
+        for x in ["PRECISION", "VARYING"] {
+            if self.accept_keyword(x) {
+                data_type = format!("{} {}", data_type, x.to_lowercase());
+            }
+        }
+
+        if self.is_symbol('(') {
+            let start = self.pos;
+            self.skip_parentheses()?;
+            // Arguments without spaces, e.g. decimal(10,2)
+            for token in &self.tokens[start..self.pos] {
+                match &token.kind {
+                    // Labels are written with backslash escapes for ColumnDefinition::type_values.
+                    TokenKind::Str(x) if self.no_backslash_escapes => {
+                        data_type.push('\'');
+                        data_type.push_str(&x.replace('\\', "\\\\").replace('\'', "''"));
+                        data_type.push('\'');
+                    }
+                    _ => data_type.push_str(&self.sql[token.start..token.end]),
+                }
+            }
+        }
+        Ok(data_type)
+    }
+
+    /// Parses a literal, a function call or an expression in parentheses.
+    fn parse_expression(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        if self.is_symbol('(') {
+            self.skip_parentheses()?;
+            return Ok(self.text(start, self.pos));
+        }
+
+        let _ = self.accept_symbol('-') || self.accept_symbol('+');
+        match self.next() {
+            Some(TokenKind::Word(x)) => {
+                // String with charset introducer, e.g. _utf8mb4'abc'
+                if x.starts_with('_') && matches!(self.peek(), Some(TokenKind::Str(_))) {
+                    self.pos += 1;
+                } else if self.is_symbol('(') {
+                    self.skip_parentheses()?;
+                }
+            }
+            Some(_) => {}
+            None => return Err(self.error("expected expression")),
+        }
+        Ok(self.text(start, self.pos))
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some(TokenKind::Str(x)) => Ok(x),
+            _ => Err(self.error("expected string")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some(TokenKind::Word(x))
+            | Some(TokenKind::QuotedIdent(x))
+            | Some(TokenKind::Str(x)) => Ok(x),
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    fn parse_table_name(&mut self) -> Result<TableName, Error> {
+        let name = self.parse_identifier()?;
+        if self.accept_symbol('.') {
+            let table = self.parse_identifier()?;
+            return Ok(TableName::new(&name, &table));
+        }
+        Ok(TableName::new(self.database, &name))
+    }
+
+    /// Skips tokens until a comma or closing parenthesis outside of parentheses.
+    fn skip_to_separator(&mut self) {
+        let mut depth = 0;
+        while let Some(kind) = self.peek() {
+            match kind {
+                TokenKind::Symbol('(') => depth += 1,
+                TokenKind::Symbol(')') if depth == 0 => break,
+                TokenKind::Symbol(')') => depth -= 1,
+                TokenKind::Symbol(',') if depth == 0 => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn skip_parentheses(&mut self) -> Result<(), Error> {
+        self.expect_symbol('(')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(TokenKind::Symbol('(')) => depth += 1,
+                Some(TokenKind::Symbol(')')) => depth -= 1,
+                Some(_) => {}
+                None => return Err(self.error("unbalanced parentheses")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets statement text of the tokens.
+    fn text(&self, from: usize, to: usize) -> String {
+        if from >= to {
+            return String::new();
+        }
+        self.sql[self.tokens[from].start..self.tokens[to - 1].end].to_string()
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|x| &x.kind)
+    }
+
+    fn next(&mut self) -> Option<TokenKind> {
+        let token = self.tokens.get(self.pos).map(|x| x.kind.clone());
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn is_keyword_at(&self, offset: usize, keyword: &str) -> bool {
+        match self.tokens.get(self.pos + offset).map(|x| &x.kind) {
+            Some(TokenKind::Word(x)) => x.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_keyword_at(0, keyword)
+    }
+
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        let accepted = self.is_keyword(keyword);
+        if accepted {
+            self.pos += 1;
+        }
+        accepted
+    }
+
+    /// Accepts the sequence of keywords if all of them follow.
+    fn accept_keywords(&mut self, keywords: &[&str]) -> bool {
+        let accepted = keywords
+            .iter()
+            .enumerate()
+            .all(|(i, x)| self.is_keyword_at(i, x));
+        if accepted {
+            self.pos += keywords.len();
+        }
+        accepted
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        match self.accept_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected {}", keyword))),
+        }
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.peek() == Some(&TokenKind::Symbol(symbol))
+    }
+
+    fn accept_symbol(&mut self, symbol: char) -> bool {
+        let accepted = self.is_symbol(symbol);
+        if accepted {
+            self.pos += 1;
+        }
+        accepted
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), Error> {
+        match self.accept_symbol(symbol) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected '{}'", symbol))),
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let offset = self
+            .tokens
+            .get(self.pos)
+            .map(|x| x.start)
+            .unwrap_or(self.sql.len());
+        Error::String(format!(
+            "Cannot parse DDL statement at {}, {}: {}",
+            offset, message, self.sql
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ddl, parse_ddl_with_sql_mode};
+    use crate::ddl::schema_change::{
+        AlterOperation, ColumnPosition, IndexKind, SchemaChange, TableName,
+    };
+
+    #[test]
+    fn parse_create_table() {
+        let sql = "CREATE TABLE IF NOT EXISTS `orders` (
+            `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+            `status` enum('new','paid') CHARACTER SET latin1 DEFAULT 'new' COMMENT 'Order status',
+            `total` decimal(10, 2) NOT NULL DEFAULT '0.00',
+            `created_at` datetime(3) DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+            PRIMARY KEY (`id`),
+            UNIQUE KEY `uk_status` (`status`, `total` DESC),
+            KEY `idx_created` (`created_at`),
+            CONSTRAINT `chk_total` CHECK ((`total` >= 0))
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 /*!50100 PARTITION BY HASH (id) */";
+
+        let (table, columns, indexes) = match parse_ddl(sql, "shop").unwrap() {
+            Some(SchemaChange::CreateTable {
+                table,
+                columns,
+                indexes,
+                like: None,
+            }) => (table, columns, indexes),
+            x => panic!("Unexpected result {:?}", x),
+        };
+        assert_eq!(TableName::new("shop", "orders"), table);
+        assert_eq!(4, columns.len());
+        assert_eq!("bigint", columns[0].data_type);
+        assert!(columns[0].unsigned && columns[0].auto_increment && !columns[0].nullable);
+        assert_eq!("enum('new','paid')", columns[1].data_type);
+        assert_eq!(Some("latin1".to_string()), columns[1].charset);
+        assert_eq!(Some("'new'".to_string()), columns[1].default);
+        assert_eq!(Some("Order status".to_string()), columns[1].comment);
+        assert_eq!("decimal(10,2)", columns[2].data_type);
+        assert_eq!(Some("CURRENT_TIMESTAMP(3)".to_string()), columns[3].default);
+
+        assert_eq!(3, indexes.len());
+        assert_eq!(IndexKind::Primary, indexes[0].kind);
+        assert_eq!(vec!["id".to_string()], indexes[0].columns);
+        assert_eq!(Some("uk_status".to_string()), indexes[1].name);
+        assert_eq!(vec!["status", "total"], indexes[1].columns);
+        assert_eq!(IndexKind::Index, indexes[2].kind);
+    }
+
+    #[test]
+    fn parse_alter_table() {
+        let sql = "ALTER TABLE shop.orders ADD COLUMN note varchar(100) NULL AFTER total, \
+            DROP COLUMN legacy, MODIFY status varchar(10) NOT NULL, \
+            CHANGE `total` `amount` decimal(12,2) FIRST, RENAME COLUMN a TO b, \
+            ADD INDEX idx_note (note(10)), DROP INDEX uk_status, DROP PRIMARY KEY, \
+            ALTER COLUMN amount SET DEFAULT 0, ENGINE=InnoDB, RENAME TO archive.orders";
+
+        let operations = match parse_ddl(sql, "").unwrap() {
+            Some(SchemaChange::AlterTable { table, operations }) => {
+                assert_eq!(TableName::new("shop", "orders"), table);
+                operations
+            }
+            x => panic!("Unexpected result {:?}", x),
+        };
+        assert_eq!(11, operations.len());
+        assert!(
+            matches!(&operations[0], AlterOperation::AddColumn { column, position }
+            if column.name == "note" && column.data_type == "varchar(100)"
+                && *position == Some(ColumnPosition::After("total".to_string())))
+        );
+        assert!(matches!(&operations[1], AlterOperation::DropColumn { name } if name == "legacy"));
+        assert!(
+            matches!(&operations[2], AlterOperation::ModifyColumn { column, .. }
+            if !column.nullable)
+        );
+        assert!(
+            matches!(&operations[3], AlterOperation::ChangeColumn { old_name, column, position }
+            if old_name == "total" && column.name == "amount"
+                && *position == Some(ColumnPosition::First))
+        );
+        assert!(
+            matches!(&operations[4], AlterOperation::RenameColumn { old_name, new_name }
+            if old_name == "a" && new_name == "b")
+        );
+        assert!(matches!(&operations[5], AlterOperation::AddIndex(x)
+            if x.columns == vec!["note".to_string()]));
+        assert!(
+            matches!(&operations[6], AlterOperation::DropIndex { name } if name == "uk_status")
+        );
+        assert_eq!(AlterOperation::DropPrimaryKey, operations[7]);
+        assert!(
+            matches!(&operations[8], AlterOperation::AlterColumnDefault { default, .. }
+            if *default == Some("0".to_string()))
+        );
+        assert_eq!(
+            AlterOperation::Other("ENGINE=InnoDB".to_string()),
+            operations[9]
+        );
+        assert!(
+            matches!(&operations[10], AlterOperation::RenameTable { new_table }
+            if *new_table == TableName::new("archive", "orders"))
+        );
+    }
+
+    #[test]
+    fn parse_other_statements() {
+        assert_eq!(
+            Some(SchemaChange::DropTable {
+                tables: vec![TableName::new("shop", "a"), TableName::new("crm", "b")]
+            }),
+            parse_ddl(
+                "DROP TABLE IF EXISTS `a`,crm.b /* generated by server */",
+                "shop"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Some(SchemaChange::RenameTable {
+                renames: vec![(TableName::new("shop", "a"), TableName::new("shop", "b"))]
+            }),
+            parse_ddl("rename table a to b", "shop").unwrap()
+        );
+        assert_eq!(
+            Some(SchemaChange::TruncateTable {
+                table: TableName::new("shop", "a")
+            }),
+            parse_ddl("TRUNCATE a", "shop").unwrap()
+        );
+        assert_eq!(
+            Some(SchemaChange::CreateDatabase {
+                database: "crm".to_string()
+            }),
+            parse_ddl(
+                "CREATE DATABASE IF NOT EXISTS crm CHARACTER SET utf8mb4",
+                ""
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            parse_ddl("CREATE UNIQUE INDEX ux ON t (a)", "shop").unwrap(),
+            Some(SchemaChange::AlterTable { operations, .. })
+                if matches!(&operations[0], AlterOperation::AddIndex(x) if x.kind == IndexKind::Unique)
+        ));
+        assert_eq!(None, parse_ddl("BEGIN", "shop").unwrap());
+        assert_eq!(
+            None,
+            parse_ddl("CREATE TEMPORARY TABLE t (a int)", "shop").unwrap()
+        );
+        assert_eq!(
+            None,
+            parse_ddl("CREATE VIEW v AS SELECT 1", "shop").unwrap()
+        );
+        assert!(parse_ddl("ALTER TABLE t ADD COLUMN", "shop").is_err());
+    }
+
+    #[test]
+    fn parse_alter_column_clauses() {
+        let sql = "ALTER TABLE t MODIFY COLUMN IF EXISTS a int unsigned DEFAULT 1 AFTER b, \
+            CHANGE COLUMN `c` `d` text, RENAME COLUMN `e` TO `f`, DROP g, \
+            DROP COLUMN IF EXISTS h, ADD (i int, j int UNIQUE)";
+
+        let operations = match parse_ddl(sql, "shop").unwrap() {
+            Some(SchemaChange::AlterTable { operations, .. }) => operations,
+            x => panic!("Unexpected result {:?}", x),
+        };
+        assert_eq!(8, operations.len());
+        assert!(
+            matches!(&operations[0], AlterOperation::ModifyColumn { column, position }
+            if column.name == "a" && column.unsigned && column.default == Some("1".to_string())
+                && *position == Some(ColumnPosition::After("b".to_string())))
+        );
+        assert!(
+            matches!(&operations[1], AlterOperation::ChangeColumn { old_name, column, position }
+            if old_name == "c" && column.name == "d" && column.data_type == "text"
+                && position.is_none())
+        );
+        assert_eq!(
+            AlterOperation::RenameColumn {
+                old_name: "e".to_string(),
+                new_name: "f".to_string()
+            },
+            operations[2]
+        );
+        assert!(matches!(&operations[3], AlterOperation::DropColumn { name } if name == "g"));
+        assert!(matches!(&operations[4], AlterOperation::DropColumn { name } if name == "h"));
+        assert!(
+            matches!(&operations[5], AlterOperation::AddColumn { column, position: None }
+            if column.name == "i")
+        );
+        assert!(
+            matches!(&operations[6], AlterOperation::AddColumn { column, position: None }
+            if column.name == "j")
+        );
+        assert!(matches!(&operations[7], AlterOperation::AddIndex(x)
+            if x.kind == IndexKind::Unique && x.columns == vec!["j".to_string()]));
+    }
+
+    #[test]
+    fn parse_alter_index_clauses() {
+        let sql = "ALTER TABLE t ADD UNIQUE KEY uk_a (a), ADD PRIMARY KEY (id), \
+            ADD FULLTEXT INDEX ft_b (b), ADD CONSTRAINT fk_c FOREIGN KEY (c) REFERENCES p (id) \
+            ON DELETE CASCADE, DROP KEY idx_d, DROP FOREIGN KEY fk_e, DROP CHECK chk_f, \
+            RENAME INDEX g TO h, ALTER COLUMN i DROP DEFAULT";
+
+        let operations = match parse_ddl(sql, "shop").unwrap() {
+            Some(SchemaChange::AlterTable { operations, .. }) => operations,
+            x => panic!("Unexpected result {:?}", x),
+        };
+        assert_eq!(9, operations.len());
+        let kinds: Vec<_> = operations[..4]
+            .iter()
+            .map(|x| match x {
+                AlterOperation::AddIndex(index) => (index.name.clone(), index.kind),
+                x => panic!("Unexpected operation {:?}", x),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (Some("uk_a".to_string()), IndexKind::Unique),
+                (None, IndexKind::Primary),
+                (Some("ft_b".to_string()), IndexKind::Fulltext),
+                (Some("fk_c".to_string()), IndexKind::ForeignKey),
+            ],
+            kinds
+        );
+        assert!(matches!(&operations[4], AlterOperation::DropIndex { name } if name == "idx_d"));
+        assert!(
+            matches!(&operations[5], AlterOperation::DropForeignKey { name } if name == "fk_e")
+        );
+        assert_eq!(
+            AlterOperation::Other("DROP CHECK chk_f".to_string()),
+            operations[6]
+        );
+        assert!(
+            matches!(&operations[7], AlterOperation::RenameIndex { old_name, new_name }
+            if old_name == "g" && new_name == "h")
+        );
+        assert!(
+            matches!(&operations[8], AlterOperation::AlterColumnDefault { name, default: None }
+            if name == "i")
+        );
+    }
+
+    #[test]
+    fn parse_create_table_like() {
+        for sql in [
+            "CREATE TABLE b LIKE crm.a",
+            "CREATE TABLE IF NOT EXISTS b (LIKE crm.a)",
+        ] {
+            assert_eq!(
+                Some(SchemaChange::CreateTable {
+                    table: TableName::new("shop", "b"),
+                    columns: Vec::new(),
+                    indexes: Vec::new(),
+                    like: Some(TableName::new("crm", "a")),
+                }),
+                parse_ddl(sql, "shop").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_rename_and_drop_database() {
+        assert_eq!(
+            Some(SchemaChange::RenameTable {
+                renames: vec![
+                    (TableName::new("shop", "a"), TableName::new("shop", "a_old")),
+                    (TableName::new("shop", "a_new"), TableName::new("shop", "a")),
+                    (TableName::new("crm", "c"), TableName::new("archive", "c")),
+                ]
+            }),
+            parse_ddl(
+                "RENAME TABLES a TO a_old, `a_new` TO `a`, crm.c TO archive.c",
+                "shop"
+            )
+            .unwrap()
+        );
+        for sql in ["DROP DATABASE IF EXISTS `crm`", "drop schema crm"] {
+            assert_eq!(
+                Some(SchemaChange::DropDatabase {
+                    database: "crm".to_string()
+                }),
+                parse_ddl(sql, "shop").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_version_comments() {
+        assert_eq!(
+            Some(SchemaChange::DropTable {
+                tables: vec![TableName::new("shop", "a")]
+            }),
+            parse_ddl("/*!40000 DROP TABLE IF EXISTS `a` */", "shop").unwrap()
+        );
+
+        let sql = "CREATE TABLE t (a int /*!80023 INVISIBLE */, b int) /*!50100 ENGINE=InnoDB */";
+        assert!(matches!(
+            parse_ddl(sql, "shop").unwrap(),
+            Some(SchemaChange::CreateTable { columns, .. })
+                if columns.iter().map(|x| x.name.as_str()).eq(["a", "b"])
+        ));
+
+        // Regular comments are skipped
+        assert_eq!(
+            None,
+            parse_ddl("/* DROP TABLE a */ SELECT 1", "shop").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_without_backslash_escapes() {
+        let sql = "CREATE TABLE t (path enum('C:\\','D:\\') COMMENT 'C:\\')";
+        assert!(parse_ddl(sql, "shop").is_err());
+
+        let columns = match parse_ddl_with_sql_mode(sql, "shop", 1 << 20).unwrap() {
+            Some(SchemaChange::CreateTable { columns, .. }) => columns,
+            x => panic!("Unexpected {:?}", x),
+        };
+        assert_eq!(Some("C:\\".to_string()), columns[0].comment);
+        assert_eq!(
+            vec!["C:\\".to_string(), "D:\\".to_string()],
+            columns[0].type_values()
+        );
+    }
+
+    #[test]
+    fn skip_statements_without_ddl_keyword() {
+        // Not tokenized, so the unterminated literal is not an error.
+        assert_eq!(
+            None,
+            parse_ddl("INSERT INTO t VALUES ('C:\\')", "shop").unwrap()
+        );
+        assert_eq!(None, parse_ddl("", "shop").unwrap());
+        assert!(parse_ddl(" -- comment\n truncate t", "shop")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn parse_invalid_statements() {
+        for sql in [
+            "CREATE TABLE t (a int",
+            "CREATE TABLE",
+            "CREATE DATABASE",
+            "ALTER TABLE t ADD INDEX idx",
+            "ALTER TABLE t RENAME COLUMN a b",
+            "ALTER TABLE t DROP PRIMARY",
+            "DROP TABLE",
+            "RENAME TABLE a",
+            "RENAME TABLE a TO b,",
+            "TRUNCATE TABLE",
+            "CREATE INDEX idx t (a)",
+            "DROP TABLE 'a",
+        ] {
+            assert!(parse_ddl(sql, "shop").is_err(), "{}", sql);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::errors::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    /// Unquoted identifier or keyword.
+    Word(String),
+
+    /// Identifier quoted with backticks.
+    QuotedIdent(String),
+
+    /// String literal quoted with single or double quotes.
+    Str(String),
+
+    Number(String),
+    Symbol(char),
+}
+
+/// Token with its byte range in the statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits SQL statement into tokens skipping whitespace and comments.
+/// Content of version comments (/*!50100 ... */) is tokenized as MySQL executes it.
+/// Backslash is a literal character in strings if NO_BACKSLASH_ESCAPES sql_mode is set.
+pub fn tokenize(sql: &str, no_backslash_escapes: bool) -> Result<Vec<Token>, Error> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map(|x| x.0).unwrap_or(sql.len());
+    let at = |i: usize| chars.get(i).map(|x| x.1);
+
+    let mut tokens = Vec::new();
+    let mut version_comment = false;
+    let mut i = 0;
+    while let Some(c) = at(i) {
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // Comments
+        if c == '#' || (c == '-' && at(i + 1) == Some('-') && at(i + 2).map_or(true, is_space)) {
+            while at(i).is_some_and(|x| x != '\n') {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && at(i + 1) == Some('*') {
+            if at(i + 2) == Some('!') {
+                i += 3;
+                while at(i).is_some_and(|x| x.is_ascii_digit()) {
+                    i += 1;
+                }
+                version_comment = true;
+                continue;
+            }
+            i += 2;
+            while at(i).is_some() && !(at(i) == Some('*') && at(i + 1) == Some('/')) {
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        if version_comment && c == '*' && at(i + 1) == Some('/') {
+            version_comment = false;
+            i += 2;
+            continue;
+        }
+
+        let kind = match c {
+            '\'' | '"' | '`' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match at(i) {
+                        None => {
+                            return Err(Error::String(format!(
+                                "Unterminated quoted value at {}",
+                                offset(start)
+                            )))
+                        }
+                        // Quote is escaped by doubling
+                        Some(x) if x == c && at(i + 1) == Some(c) => {
+                            value.push(c);
+                            i += 2;
+                        }
+                        Some(x) if x == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some('\\') if c != '`' && !no_backslash_escapes => {
+                            match at(i + 1) {
+                                Some('n') => value.push('\n'),
+                                Some('t') => value.push('\t'),
+                                Some('r') => value.push('\r'),
+                                Some('0') => value.push('\0'),
+                                Some(x) => value.push(x),
+                                None => {}
+                            }
+                            i += 2;
+                        }
+                        Some(x) => {
+                            value.push(x);
+                            i += 1;
+                        }
+                    }
+                }
+                match c {
+                    '`' => TokenKind::QuotedIdent(value),
+                    _ => TokenKind::Str(value),
+                }
+            }
+            c if is_word_char(c) => {
+                while at(i).is_some_and(is_word_char) {
+                    i += 1;
+                }
+                // Decimal part of a number
+                if c.is_ascii_digit() && at(i) == Some('.') {
+                    i += 1;
+                    while at(i).is_some_and(|x| x.is_ascii_digit()) {
+                        i += 1;
+                    }
+                }
+                let word = &sql[offset(start)..offset(i)];
+                if word.chars().all(|x| x.is_ascii_digit() || x == '.') {
+                    TokenKind::Number(word.to_string())
+                } else {
+                    TokenKind::Word(word.to_string())
+                }
+            }
+            c => {
+                i += 1;
+                TokenKind::Symbol(c)
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start: offset(start),
+            end: offset(i),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Gets the first word of the statement skipping whitespace and comments.
+/// The rest of the statement is not tokenized.
+pub fn first_word(sql: &str) -> &str {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start();
+        if let Some(x) = rest.strip_prefix("/*!") {
+            rest = x.trim_start_matches(|c: char| c.is_ascii_digit());
+        } else if let Some(x) = rest.strip_prefix("/*") {
+            rest = x.split_once("*/").map_or("", |(_, x)| x);
+        } else if rest.starts_with('#')
+            || (rest.starts_with("--") && rest[2..].chars().next().map_or(true, is_space))
+        {
+            rest = rest.split_once('\n').map_or("", |(_, x)| x);
+        } else {
+            break;
+        }
+    }
+    let end = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+    &rest[..end]
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn is_space(c: char) -> bool {
+    c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_word, tokenize, TokenKind};
+
+    #[test]
+    fn tokenize_statement() {
+        let sql = "ALTER TABLE `my``table` /* comment */ ADD c DECIMAL(10,2) DEFAULT '1''0' -- x\n/*!50100 FIRST */";
+        let kinds: Vec<TokenKind> = tokenize(sql, false)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.kind)
+            .collect();
+        assert_eq!(
+            vec![
+                TokenKind::Word("ALTER".to_string()),
+                TokenKind::Word("TABLE".to_string()),
+                TokenKind::QuotedIdent("my`table".to_string()),
+                TokenKind::Word("ADD".to_string()),
+                TokenKind::Word("c".to_string()),
+                TokenKind::Word("DECIMAL".to_string()),
+                TokenKind::Symbol('('),
+                TokenKind::Number("10".to_string()),
+                TokenKind::Symbol(','),
+                TokenKind::Number("2".to_string()),
+                TokenKind::Symbol(')'),
+                TokenKind::Word("DEFAULT".to_string()),
+                TokenKind::Str("1'0".to_string()),
+                TokenKind::Word("FIRST".to_string()),
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn tokenize_without_backslash_escapes() {
+        let sql = "'C:\\' 'a\\''b'";
+        assert!(tokenize(sql, false).is_err());
+        let kinds: Vec<TokenKind> = tokenize(sql, true)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.kind)
+            .collect();
+        assert_eq!(
+            vec![
+                TokenKind::Str("C:\\".to_string()),
+                TokenKind::Str("a\\'b".to_string()),
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn first_word_after_comments() {
+        assert_eq!(
+            "ALTER",
+            first_word("  /* x */ -- y\n# z\n/*!50100 ALTER TABLE t */")
+        );
+        assert_eq!("INSERT", first_word("INSERT INTO t VALUES ('C:\\')"));
+        assert_eq!("", first_word("/* unterminated"));
+        assert_eq!("", first_word("--x"));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod ddl_parser;
+pub mod schema_change;
+
+mod lexer;
@@ -0,0 +1,198 @@
+use crate::ddl::lexer::{tokenize, TokenKind};
+
+/// Fully qualified table name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableName {
+    /// Gets database name. Default database of the statement is used if not specified.
+    pub database: String,
+
+    /// Gets table name.
+    pub name: String,
+}
+
+impl TableName {
+    pub fn new(database: &str, name: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Column definition of CREATE TABLE and ALTER TABLE statements.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnDefinition {
+    /// Gets column name.
+    pub name: String,
+
+    /// Gets lowercase data type with its arguments, e.g. varchar(255) or enum('a','b')
+    pub data_type: String,
+
+    /// Gets whether a numeric column is UNSIGNED.
+    pub unsigned: bool,
+
+    /// Gets whether the column accepts NULL values.
+    pub nullable: bool,
+
+    /// Gets default value expression as it's written in the statement.
+    pub default: Option<String>,
+
+    /// Gets whether the column is AUTO_INCREMENT.
+    pub auto_increment: bool,
+
+    /// Gets character set of a string column if specified.
+    pub charset: Option<String>,
+
+    /// Gets collation of a string column if specified.
+    pub collation: Option<String>,
+
+    /// Gets column comment.
+    pub comment: Option<String>,
+}
+
+impl ColumnDefinition {
+    /// Gets data type without arguments, e.g. varchar or enum.
+    pub fn type_name(&self) -> &str {
+        match self.data_type.find('(') {
+            Some(i) => &self.data_type[..i],
+            None => &self.data_type,
+        }
+    }
+
+    /// Gets string arguments of the data type, e.g. labels of ENUM and SET columns.
+    pub fn type_values(&self) -> Vec<String> {
+        let arguments = match self.data_type.find('(') {
+            Some(i) => &self.data_type[i..],
+            None => return Vec::new(),
+        };
+        // Parser writes string arguments with backslash escapes.
+        match tokenize(arguments, false) {
+            Ok(tokens) => tokens
+                .into_iter()
+                .filter_map(|x| match x.kind {
+                    TokenKind::Str(value) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Kind of an index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IndexKind {
+    Primary,
+    Unique,
+    Index,
+    Fulltext,
+    Spatial,
+    ForeignKey,
+}
+
+/// Index or key definition.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexDefinition {
+    /// Gets index name. None for primary keys and unnamed indexes.
+    pub name: Option<String>,
+
+    /// Gets index kind.
+    pub kind: IndexKind,
+
+    /// Gets indexed columns. Functional key parts are returned as expressions.
+    pub columns: Vec<String>,
+}
+
+/// Position of an added or modified column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnPosition {
+    First,
+    After(String),
+}
+
+/// Single operation of ALTER TABLE statement.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlterOperation {
+    AddColumn {
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    DropColumn {
+        name: String,
+    },
+    ModifyColumn {
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    /// CHANGE COLUMN renames the column and redefines it.
+    ChangeColumn {
+        old_name: String,
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    RenameColumn {
+        old_name: String,
+        new_name: String,
+    },
+    /// SET DEFAULT or DROP DEFAULT (None) of ALTER COLUMN.
+    AlterColumnDefault {
+        name: String,
+        default: Option<String>,
+    },
+    AddIndex(IndexDefinition),
+    DropIndex {
+        name: String,
+    },
+    DropPrimaryKey,
+    DropForeignKey {
+        name: String,
+    },
+    RenameIndex {
+        old_name: String,
+        new_name: String,
+    },
+    RenameTable {
+        new_table: TableName,
+    },
+    /// Table options, partitioning and other operations that don't change columns or indexes.
+    Other(String),
+}
+
+/// Schema change parsed from a DDL statement.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SchemaChange {
+    CreateDatabase {
+        database: String,
+    },
+    DropDatabase {
+        database: String,
+    },
+    CreateTable {
+        table: TableName,
+        columns: Vec<ColumnDefinition>,
+        indexes: Vec<IndexDefinition>,
+        /// Source table of CREATE TABLE ... LIKE statement.
+        like: Option<TableName>,
+    },
+    /// Includes CREATE INDEX and DROP INDEX statements.
+    AlterTable {
+        table: TableName,
+        operations: Vec<AlterOperation>,
+    },
+    DropTable {
+        tables: Vec<TableName>,
+    },
+    RenameTable {
+        renames: Vec<(TableName, TableName)>,
+    },
+    TruncateTable {
+        table: TableName,
+    },
+}
@@ -0,0 +1,617 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Map, Value};
+
+use crate::calendar::{civil_from_days, days_from_civil, seconds_from_civil};
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::row_events::mysql_value::MySqlValue;
+use crate::events::row_events::row_decoder::{RowChange, RowDecoder};
+use crate::events::row_events::typed_row::{ColumnValue, TypedRow};
+use crate::events::table_map_event::{ColumnInfo, TableMapEvent, BINARY_COLLATION};
+use crate::extensions::encode_base64;
+
+/// Fields of the `source` block: type, optional, name.
+const SOURCE_FIELDS: [(&str, bool, &str); 15] = [
+    ("string", false, "version"),
+    ("string", false, "connector"),
+    ("string", false, "name"),
+    ("int64", false, "ts_ms"),
+    ("string", true, "snapshot"),
+    ("string", false, "db"),
+    ("string", true, "sequence"),
+    ("string", true, "table"),
+    ("int64", false, "server_id"),
+    ("string", true, "gtid"),
+    ("string", false, "file"),
+    ("int64", false, "pos"),
+    ("int32", false, "row"),
+    ("int64", true, "thread"),
+    ("string", true, "query"),
+];
+
+/// Converts row events into change events of the Debezium MySQL connector.
+/// Values are converted the same way as the connector does with default settings,
+/// except DECIMAL columns that are converted to strings like with `decimal.handling.mode=string`.
+/// <a href="https://debezium.io/documentation/reference/stable/connectors/mysql.html#mysql-events">See more</a>
+pub struct DebeziumConverter {
+    /// Gets logical name of the server used in `source.name` and schema names (`topic.prefix`).
+    pub server_name: String,
+
+    /// Gets whether the `schema` section is included like with `schemas.enable=true` of JsonConverter.
+    pub include_schema: bool,
+
+    filename: String,
+    gtid: Option<String>,
+    thread_id: Option<u32>,
+    query: Option<String>,
+    decoder: RowDecoder,
+}
+
+impl DebeziumConverter {
+    pub fn new(server_name: &str) -> Self {
+        Self {
+            server_name: server_name.to_string(),
+            include_schema: true,
+            filename: String::new(),
+            gtid: None,
+            thread_id: None,
+            query: None,
+            decoder: RowDecoder::new(),
+        }
+    }
+
+    /// Returns a change event for each row of a row event.
+    /// Tracks binlog filename, GTID, thread id and TableMapEvent events,
+    /// so all events of the stream must be passed in order.
+    pub fn convert(
+        &mut self,
+        header: &EventHeader,
+        event: &BinlogEvent,
+    ) -> Result<Vec<Value>, Error> {
+        let table_id = match event {
+            BinlogEvent::RotateEvent(x) => {
+                self.filename = x.binlog_filename.clone();
+                None
+            }
+            BinlogEvent::MySqlGtidEvent(x) => {
+                self.gtid = Some(x.gtid.to_string());
+                None
+            }
+            BinlogEvent::MariaDbGtidEvent(x) => {
+                self.gtid = Some(x.gtid.to_string());
+                None
+            }
+            BinlogEvent::QueryEvent(x) => {
+                self.thread_id = Some(x.thread_id);
+                None
+            }
+            BinlogEvent::RowsQueryEvent(x) => {
+                self.query = Some(x.query.clone());
+                None
+            }
+            BinlogEvent::XidEvent(_) => {
+                self.query = None;
+                None
+            }
+            BinlogEvent::WriteRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::UpdateRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::DeleteRowsEvent(x) => Some(x.table_id),
+            BinlogEvent::MySqlPartialUpdateRowsEvent(x) => Some(x.table_id),
+            _ => None,
+        };
+
+        let changes = self.decoder.decode(event)?;
+        match table_id.and_then(|x| self.decoder.table(x)) {
+            Some(table) => self.create_envelopes(header, table, &changes),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns a change event for each row of a row event with its TableMapEvent.
+    pub fn convert_rows(
+        &self,
+        header: &EventHeader,
+        table: &TableMapEvent,
+        event: &BinlogEvent,
+    ) -> Result<Vec<Value>, Error> {
+        let mut decoder = RowDecoder::new();
+        decoder.decode(&BinlogEvent::TableMapEvent(table.clone()))?;
+        let changes = decoder.decode(event)?;
+        self.create_envelopes(header, table, &changes)
+    }
+
+    /// Gets the schema section of change events of the table.
+    pub fn schema(&self, table: &TableMapEvent) -> Result<Value, Error> {
+        let columns = table.columns()?;
+        self.create_schema(table, &columns)
+    }
+
+    fn create_envelopes(
+        &self,
+        header: &EventHeader,
+        table: &TableMapEvent,
+        changes: &[RowChange],
+    ) -> Result<Vec<Value>, Error> {
+        let columns = table.columns()?;
+        let schema = match self.include_schema {
+            true => Some(self.create_schema(table, &columns)?),
+            false => None,
+        };
+
+        let mut envelopes = Vec::with_capacity(changes.len());
+        for (row, change) in changes.iter().enumerate() {
+            let (op, before, after) = match change {
+                RowChange::Insert(x) => ("c", None, Some(x)),
+                RowChange::Update { before, after } => ("u", Some(before), Some(after)),
+                RowChange::Delete(x) => ("d", Some(x), None),
+            };
+            let before = before.map(|x| row_value(&columns, x)).transpose()?;
+            let after = after.map(|x| row_value(&columns, x)).transpose()?;
+            let payload = json!({
+                "before": before,
+                "after": after,
+                "source": self.create_source(header, table, row),
+                "op": op,
+                "ts_ms": current_millis(),
+            });
+            envelopes.push(match &schema {
+                Some(schema) => json!({ "schema": schema, "payload": payload }),
+                None => payload,
+            });
+        }
+        Ok(envelopes)
+    }
+
+    fn create_source(&self, header: &EventHeader, table: &TableMapEvent, row: usize) -> Value {
+        json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "connector": "mysql",
+            "name": self.server_name,
+            "ts_ms": header.timestamp as u64 * 1000,
+            "snapshot": "false",
+            "db": table.database_name,
+            "sequence": null,
+            "table": table.table_name,
+            "server_id": header.server_id,
+            "gtid": self.gtid,
+            "file": self.filename,
+            "pos": header.next_event_position.saturating_sub(header.event_length),
+            "row": row,
+            "thread": self.thread_id,
+            "query": self.query,
+        })
+    }
+
+    fn create_schema(&self, table: &TableMapEvent, columns: &[ColumnInfo]) -> Result<Value, Error> {
+        let prefix = format!(
+            "{}.{}.{}",
+            self.server_name, table.database_name, table.table_name
+        );
+
+        let mut fields = Vec::with_capacity(columns.len());
+        for column in columns {
+            fields.push(column_schema(column)?);
+        }
+        let value_schema = |field: &str| {
+            json!({
+                "type": "struct",
+                "fields": fields,
+                "optional": true,
+                "name": format!("{}.Value", prefix),
+                "field": field,
+            })
+        };
+        let source_fields: Vec<Value> = SOURCE_FIELDS
+            .iter()
+            .map(|(schema_type, optional, field)| {
+                json!({ "type": schema_type, "optional": optional, "field": field })
+            })
+            .collect();
+
+        Ok(json!({
+            "type": "struct",
+            "fields": [
+                value_schema("before"),
+                value_schema("after"),
+                {
+                    "type": "struct",
+                    "fields": source_fields,
+                    "optional": false,
+                    "name": "io.debezium.connector.mysql.Source",
+                    "field": "source",
+                },
+                { "type": "string", "optional": false, "field": "op" },
+                { "type": "int64", "optional": true, "field": "ts_ms" },
+            ],
+            "optional": false,
+            "name": format!("{}.Envelope", prefix),
+        }))
+    }
+}
+
+fn column_schema(column: &ColumnInfo) -> Result<Value, Error> {
+    let (schema_type, name, parameters) = match ColumnType::from_code(column.column_type)? {
+        ColumnType::Tiny => ("int16", None, None),
+        ColumnType::Short if column.unsigned => ("int32", None, None),
+        ColumnType::Short => ("int16", None, None),
+        ColumnType::Int24 => ("int32", None, None),
+        ColumnType::Long if column.unsigned => ("int64", None, None),
+        ColumnType::Long => ("int32", None, None),
+        ColumnType::LongLong => ("int64", None, None),
+        ColumnType::Float => ("float", None, None),
+        ColumnType::Double => ("double", None, None),
+        ColumnType::Decimal | ColumnType::NewDecimal => ("string", None, None),
+        ColumnType::VarChar | ColumnType::VarString | ColumnType::String => {
+            match column.collation == Some(BINARY_COLLATION) {
+                true => ("bytes", None, None),
+                false => ("string", None, None),
+            }
+        }
+        ColumnType::TinyBlob | ColumnType::MediumBlob | ColumnType::LongBlob | ColumnType::Blob => {
+            match is_text(column) {
+                true => ("string", None, None),
+                false => ("bytes", None, None),
+            }
+        }
+        ColumnType::Bit => match bit_length(column) {
+            1 => ("boolean", None, None),
+            length => (
+                "bytes",
+                Some("io.debezium.data.Bits"),
+                Some(json!({ "length": length.to_string() })),
+            ),
+        },
+        ColumnType::Enum => match &column.labels {
+            Some(labels) => (
+                "string",
+                Some("io.debezium.data.Enum"),
+                Some(json!({ "allowed": labels.join(",") })),
+            ),
+            None => ("int32", None, None),
+        },
+        ColumnType::Set => match &column.labels {
+            Some(labels) => (
+                "string",
+                Some("io.debezium.data.EnumSet"),
+                Some(json!({ "allowed": labels.join(",") })),
+            ),
+            None => ("int64", None, None),
+        },
+        ColumnType::Year => ("int32", Some("io.debezium.time.Year"), None),
+        ColumnType::Date | ColumnType::NewDate => ("int32", Some("io.debezium.time.Date"), None),
+        ColumnType::Time | ColumnType::Time2 => ("int64", Some("io.debezium.time.MicroTime"), None),
+        ColumnType::DateTime | ColumnType::DateTime2 => {
+            ("int64", Some("io.debezium.time.Timestamp"), None)
+        }
+        ColumnType::TimeStamp | ColumnType::TimeStamp2 => {
+            ("string", Some("io.debezium.time.ZonedTimestamp"), None)
+        }
+        ColumnType::Json => ("string", Some("io.debezium.data.Json"), None),
+        ColumnType::Geometry => {
+            return Ok(json!({
+                "type": "struct",
+                "fields": [
+                    { "type": "bytes", "optional": false, "field": "wkb" },
+                    { "type": "int32", "optional": true, "field": "srid" },
+                ],
+                "optional": column.nullable,
+                "name": "io.debezium.data.geometry.Geometry",
+                "version": 1,
+                "field": column.name,
+            }))
+        }
+        ColumnType::Null => ("string", None, None),
+    };
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!(schema_type));
+    schema.insert("optional".to_string(), json!(column.nullable));
+    if let Some(name) = name {
+        schema.insert("name".to_string(), json!(name));
+        schema.insert("version".to_string(), json!(1));
+    }
+    if let Some(parameters) = parameters {
+        schema.insert("parameters".to_string(), parameters);
+    }
+    schema.insert("field".to_string(), json!(column.name));
+    Ok(Value::Object(schema))
+}
+
+fn row_value(columns: &[ColumnInfo], row: &TypedRow) -> Result<Value, Error> {
+    let mut values = Map::new();
+    for (column, typed_column) in columns.iter().zip(row.columns.iter()) {
+        let value = column_value(column, typed_column.value.as_ref())?;
+        values.insert(column.name.clone(), value);
+    }
+    Ok(Value::Object(values))
+}
+
+fn column_value(column: &ColumnInfo, value: Option<&ColumnValue>) -> Result<Value, Error> {
+    let value = match value {
+        Some(ColumnValue::Signed(x)) => return Ok(json!(x)),
+        Some(ColumnValue::Unsigned(x)) => return Ok(json!(x)),
+        Some(ColumnValue::Enum(x)) => return Ok(json!(x)),
+        Some(ColumnValue::Set(x)) => return Ok(json!(x.join(","))),
+        Some(ColumnValue::Value(x)) => x,
+        None => return Ok(Value::Null),
+    };
+
+    // Zero dates are converted to null for optional columns and epoch otherwise.
+    let zero = match column.nullable {
+        true => Value::Null,
+        false => json!(0),
+    };
+    let value = match value {
+        MySqlValue::TinyInt(x) => json!(x),
+        MySqlValue::SmallInt(x) => json!(x),
+        MySqlValue::MediumInt(x) => json!(x),
+        MySqlValue::Int(x) => json!(x),
+        MySqlValue::BigInt(x) => json!(x),
+        MySqlValue::Float(x) => json!(x),
+        MySqlValue::Double(x) => json!(x),
+        MySqlValue::Decimal(x) => json!(x),
+        MySqlValue::String(x) => match column.collation == Some(BINARY_COLLATION) {
+            true => json!(encode_base64(x.as_bytes())),
+            false => json!(x),
+        },
+        MySqlValue::Bit(x) if x.len() == 1 => json!(x[0]),
+        MySqlValue::Bit(x) => {
+            // Bits are returned as little-endian byte array.
+            let mut bytes = vec![0u8; x.len().div_ceil(8)];
+            for (i, bit) in x.iter().enumerate() {
+                if *bit {
+                    bytes[i / 8] |= 1 << (i % 8);
+                }
+            }
+            json!(encode_base64(&bytes))
+        }
+        MySqlValue::Enum(x) => json!(x),
+        MySqlValue::Set(x) => json!(x),
+        MySqlValue::Blob(x) => match is_text(column) {
+            true => json!(String::from_utf8_lossy(x)),
+            false => json!(encode_base64(x)),
+        },
+        MySqlValue::Year(x) => json!(x),
+        MySqlValue::Date(x) if x.year == 0 => zero,
+        MySqlValue::Date(x) => json!(days_from_civil(x.year as i64, x.month as i64, x.day as i64)),
+        MySqlValue::Time(x) => {
+            let micros =
+                ((x.hour.unsigned_abs() as i64 * 3600 + x.minute as i64 * 60 + x.second as i64)
+                    * 1000
+                    + x.millis as i64)
+                    * 1000;
+            json!(if x.hour < 0 { -micros } else { micros })
+        }
+        MySqlValue::DateTime(x) if x.year == 0 => zero,
+        MySqlValue::DateTime(x) => {
+            let seconds = seconds_from_civil(
+                x.year as i64,
+                x.month as i64,
+                x.day as i64,
+                x.hour as i64,
+                x.minute as i64,
+                x.second as i64,
+            );
+            json!(seconds * 1000 + x.millis as i64)
+        }
+        MySqlValue::Timestamp(x) => json!(format_zoned_timestamp(*x)),
+        MySqlValue::Json(x) => json!(x.to_string()),
+        // Diffs are kept only when the before image of the JSON column is not logged.
+        MySqlValue::JsonDiff(_) => {
+            return Err(Error::String(format!(
+                "Partial update of JSON column {} can't be converted without the before image. Please use binlog_row_image=FULL",
+                column.name
+            )))
+        }
+        MySqlValue::Geometry(x) => json!({
+            "wkb": encode_base64(&x.to_wkb()),
+            "srid": x.srid,
+        }),
+    };
+    Ok(value)
+}
+
+/// Checks whether BLOB column is a TEXT column.
+fn is_text(column: &ColumnInfo) -> bool {
+    matches!(column.collation, Some(x) if x != BINARY_COLLATION)
+}
+
+fn bit_length(column: &ColumnInfo) -> u16 {
+    (column.metadata >> 8) * 8 + (column.metadata & 0xFF)
+}
+
+/// Formats milliseconds from Unix epoch as ISO-8601 UTC timestamp, e.g. 2023-07-15T10:20:30.5Z
+fn format_zoned_timestamp(millis: u64) -> String {
+    let seconds = (millis / 1000) as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    let time = seconds.rem_euclid(86400);
+    let mut result = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    );
+    if millis % 1000 != 0 {
+        let fraction = format!("{:03}", millis % 1000);
+        result.push('.');
+        result.push_str(fraction.trim_end_matches('0'));
+    }
+    result.push('Z');
+    result
+}
+
+fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{format_zoned_timestamp, DebeziumConverter};
+    use crate::constants::column_type::ColumnType;
+    use crate::errors::Error;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::rotate_event::RotateEvent;
+    use crate::events::row_events::json_diff::{JsonDiff, JsonDiffOperation};
+    use crate::events::row_events::mysql_value::{Date, MySqlValue};
+    use crate::events::row_events::partial_update_rows_event::PartialUpdateRowsEvent;
+    use crate::events::row_events::row_data::{RowData, UpdateRowData};
+    use crate::events::row_events::update_rows_event::UpdateRowsEvent;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::metadata::table_metadata::TableMetadata;
+    use crate::providers::mysql::events::gtid_event::GtidEvent;
+    use crate::providers::mysql::gtid::gtid::Gtid;
+
+    fn create_header(event_type: u8, next_event_position: u32) -> EventHeader {
+        EventHeader {
+            timestamp: 1689416430,
+            event_type,
+            server_id: 1,
+            event_length: 50,
+            next_event_position,
+            event_flags: 0,
+        }
+    }
+
+    #[test]
+    fn convert_update() {
+        let table = TableMapEvent {
+            table_id: 7,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![
+                ColumnType::Long as u8,
+                ColumnType::VarChar as u8,
+                ColumnType::Date as u8,
+            ],
+            column_metadata: vec![0, 255, 0],
+            null_bitmap: vec![false, true, true],
+            table_metadata: Some(TableMetadata {
+                signedness: Some(vec![true]),
+                column_names: Some(vec![
+                    String::from("id"),
+                    String::from("status"),
+                    String::from("created"),
+                ]),
+                ..Default::default()
+            }),
+        };
+        let row = |status: &str| {
+            RowData::new(vec![
+                Some(MySqlValue::Int(4000000000)),
+                Some(MySqlValue::String(status.to_string())),
+                Some(MySqlValue::Date(Date {
+                    year: 2023,
+                    month: 7,
+                    day: 15,
+                })),
+            ])
+        };
+        let gtid = "9b1ad0d4-19f4-11ee-9d6b-0242ac120002:12";
+        let events = vec![
+            BinlogEvent::RotateEvent(RotateEvent {
+                binlog_filename: String::from("mysql-bin.000003"),
+                binlog_position: 4,
+            }),
+            BinlogEvent::MySqlGtidEvent(GtidEvent {
+                gtid: Gtid::parse(gtid).unwrap(),
+                flags: 0,
+            }),
+            BinlogEvent::TableMapEvent(table),
+            BinlogEvent::UpdateRowsEvent(UpdateRowsEvent {
+                table_id: 7,
+                flags: 0,
+                columns_number: 3,
+                columns_before_update: vec![true; 3],
+                columns_after_update: vec![true; 3],
+                rows: vec![UpdateRowData::new(row("new"), row("paid"))],
+            }),
+        ];
+
+        let mut converter = DebeziumConverter::new("dbserver1");
+        let mut envelopes = Vec::new();
+        for event in &events {
+            envelopes.extend(converter.convert(&create_header(31, 1200), event).unwrap());
+        }
+        assert_eq!(1, envelopes.len());
+
+        let payload = &envelopes[0]["payload"];
+        assert_eq!("u", payload["op"]);
+        let before = json!({ "id": 4000000000u64, "status": "new", "created": 19553 });
+        assert_eq!(before, payload["before"]);
+        assert_eq!("paid", payload["after"]["status"]);
+        assert_eq!("mysql-bin.000003", payload["source"]["file"]);
+        assert_eq!(1150, payload["source"]["pos"]);
+        assert_eq!(gtid, payload["source"]["gtid"]);
+        assert_eq!("orders", payload["source"]["table"]);
+        assert_eq!(1689416430000u64, payload["source"]["ts_ms"]);
+
+        let schema = &envelopes[0]["schema"];
+        assert_eq!("dbserver1.shop.orders.Envelope", schema["name"]);
+        let fields = &schema["fields"][0]["fields"];
+        assert_eq!(
+            json!({ "type": "int64", "optional": false, "field": "id" }),
+            fields[0]
+        );
+        assert_eq!("io.debezium.time.Date", fields[2]["name"]);
+    }
+
+    #[test]
+    fn format_timestamps() {
+        assert_eq!("1970-01-01T00:00:00Z", format_zoned_timestamp(0));
+        assert_eq!(
+            "2023-07-15T10:20:30.5Z",
+            format_zoned_timestamp(1689416430500)
+        );
+    }
+
+    #[test]
+    fn json_diff_without_before_image_is_error() {
+        let table = TableMapEvent {
+            table_id: 7,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![ColumnType::Long as u8, ColumnType::Json as u8],
+            column_metadata: vec![0, 4],
+            null_bitmap: vec![false, true],
+            table_metadata: Some(TableMetadata {
+                column_names: Some(vec![String::from("id"), String::from("details")]),
+                ..Default::default()
+            }),
+        };
+        let diff = JsonDiff {
+            operation: JsonDiffOperation::Remove,
+            path: String::from("$.a"),
+            value: None,
+        };
+        // binlog_row_image=MINIMAL logs only the primary key in the before image.
+        let update = BinlogEvent::MySqlPartialUpdateRowsEvent(PartialUpdateRowsEvent {
+            table_id: 7,
+            flags: 0,
+            columns_number: 2,
+            columns_before_update: vec![true, false],
+            columns_after_update: vec![false, true],
+            rows: vec![UpdateRowData::new(
+                RowData::new(vec![Some(MySqlValue::Int(1)), None]),
+                RowData::new(vec![None, Some(MySqlValue::JsonDiff(vec![diff]))]),
+            )],
+        });
+
+        let converter = DebeziumConverter::new("dbserver1");
+        match converter.convert_rows(&create_header(39, 1200), &table, &update) {
+            Err(Error::String(x)) => assert!(x.contains("JSON column details")),
+            _ => panic!("JSON diffs must not be converted"),
+        }
+    }
+}
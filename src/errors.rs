@@ -16,6 +16,10 @@ pub enum Error {
     FromHexError(#[from] FromHexError),
     #[error("can't parse int, {0}")]
     ParseIntError(#[from] ParseIntError),
+    #[error("ssl error, {0}")]
+    SslError(#[from] rustls::Error),
+    #[error("checksum mismatch in {filename} at position {position}")]
+    ChecksumMismatch { filename: String, position: u32 },
     #[error("{0}")]
     String(String),
 }
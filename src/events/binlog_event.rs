@@ -4,6 +4,8 @@ use crate::events::intvar_event::IntVarEvent;
 use crate::events::query_event::QueryEvent;
 use crate::events::rotate_event::RotateEvent;
 use crate::events::row_events::delete_rows_event::DeleteRowsEvent;
+use crate::events::row_events::partial_update_rows_event::PartialUpdateRowsEvent;
+use crate::events::row_events::skipped_rows_event::SkippedRowsEvent;
 use crate::events::row_events::update_rows_event::UpdateRowsEvent;
 use crate::events::row_events::write_rows_event::WriteRowsEvent;
 use crate::events::rows_query_event::RowsQueryEvent;
@@ -16,12 +18,14 @@ use crate::providers::mysql::events::gtid_event::GtidEvent as MySqlGtidEvent;
 use crate::providers::mysql::events::prev_gtids_event::PreviousGtidsEvent;
 
 /// Represents a binlog event.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinlogEvent {
     UnknownEvent,
     DeleteRowsEvent(DeleteRowsEvent),
     UpdateRowsEvent(UpdateRowsEvent),
     WriteRowsEvent(WriteRowsEvent),
+    SkippedRowsEvent(SkippedRowsEvent),
     XidEvent(XidEvent),
     IntVarEvent(IntVarEvent),
     UserVarEvent(UserVarEvent),
@@ -34,6 +38,7 @@ pub enum BinlogEvent {
     // Provider specific events
     MySqlGtidEvent(MySqlGtidEvent),
     MySqlPrevGtidsEvent(PreviousGtidsEvent),
+    MySqlPartialUpdateRowsEvent(PartialUpdateRowsEvent),
     MariaDbGtidEvent(MariaDbGtidEvent),
     MariaDbGtidListEvent(GtidListEvent),
 }
@@ -1,12 +1,13 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::Cursor;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor};
 
 use crate::errors::Error;
 
 /// Binlog event header version 4. Header size is 19 bytes.
 /// See <a href="https://mariadb.com/kb/en/library/2-binlog-event-header/">MariaDB docs</a>
 /// See <a href="https://dev.mysql.com/doc/internals/en/binlog-version.html">MySQL docs</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventHeader {
     /// Provides creation time in seconds from Unix.
     pub timestamp: u32,
@@ -40,4 +41,18 @@ impl EventHeader {
             event_flags: cursor.read_u16::<LittleEndian>()?,
         })
     }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+
+        cursor.write_u32::<LittleEndian>(self.timestamp)?;
+        cursor.write_u8(self.event_type)?;
+        cursor.write_u32::<LittleEndian>(self.server_id)?;
+        cursor.write_u32::<LittleEndian>(self.event_length)?;
+        cursor.write_u32::<LittleEndian>(self.next_event_position)?;
+        cursor.write_u16::<LittleEndian>(self.event_flags)?;
+
+        Ok(vec)
+    }
 }
@@ -9,6 +9,8 @@ use crate::events::intvar_event::IntVarEvent;
 use crate::events::query_event::QueryEvent;
 use crate::events::rotate_event::RotateEvent;
 use crate::events::row_events::delete_rows_event::DeleteRowsEvent;
+use crate::events::row_events::partial_update_rows_event::PartialUpdateRowsEvent;
+use crate::events::row_events::skipped_rows_event::SkippedRowsEvent;
 use crate::events::row_events::update_rows_event::UpdateRowsEvent;
 use crate::events::row_events::write_rows_event::WriteRowsEvent;
 use crate::events::rows_query_event::RowsQueryEvent;
@@ -19,22 +21,46 @@ use crate::providers::mariadb::events::gtid_event::GtidEvent as MariaDbGtidEvent
 use crate::providers::mariadb::events::gtid_list_event::GtidListEvent;
 use crate::providers::mysql::events::gtid_event::GtidEvent as MySqlGtidEvent;
 use crate::providers::mysql::events::prev_gtids_event::PreviousGtidsEvent;
-use std::collections::HashMap;
+use crate::table_filter::TableFilter;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 
+const CHECKSUM_SIZE: usize = 4;
+
+/// Flag of FormatDescriptionEvent set while the binlog file is open.
+/// It is set after the checksum is computed and excluded from it.
+const LOG_EVENT_BINLOG_IN_USE_F: u16 = 0x1;
+
 pub struct EventParser {
     /// Gets checksum algorithm type used in a binlog file.
     pub checksum_type: ChecksumType,
 
+    /// Defines whether CRC32 checksums of events are verified.
+    pub verify_checksum: bool,
+
+    /// Gets name of the current binlog file. Updated on RotateEvent.
+    pub binlog_filename: String,
+
+    /// Defines tables whose row events are parsed.
+    pub table_filter: TableFilter,
+
     /// Gets TableMapEvent cache required in row events.
     table_map: HashMap<u64, TableMapEvent>,
+
+    /// Gets ids of tables excluded by the filter.
+    skipped_tables: HashSet<u64>,
 }
 
 impl EventParser {
     pub fn new() -> Self {
         Self {
             checksum_type: ChecksumType::None,
+            verify_checksum: false,
+            binlog_filename: String::new(),
+            table_filter: TableFilter::default(),
             table_map: HashMap::new(),
+            skipped_tables: HashSet::new(),
         }
     }
 
@@ -43,13 +69,26 @@ impl EventParser {
         header: &EventHeader,
         slice: &[u8],
     ) -> Result<BinlogEvent, Error> {
-        // Consider verifying checksum
         let mut cursor = match self.checksum_type {
             ChecksumType::None => Cursor::new(slice),
-            ChecksumType::Crc32 => Cursor::new(&slice[0..slice.len() - 4]),
+            ChecksumType::Crc32 => {
+                if self.verify_checksum {
+                    self.verify_event_checksum(header, slice)?;
+                }
+                Cursor::new(&slice[0..slice.len() - CHECKSUM_SIZE])
+            }
         };
 
-        let binlog_event: BinlogEvent = match EventType::from_code(header.event_type) {
+        let event_type = EventType::from_code(header.event_type);
+        if is_rows_event(&event_type) && !self.skipped_tables.is_empty() {
+            // Rows of excluded tables are not decoded.
+            let table_id = cursor.clone().read_u48::<LittleEndian>()?;
+            if self.skipped_tables.contains(&table_id) {
+                return Ok(BinlogEvent::SkippedRowsEvent(SkippedRowsEvent { table_id }));
+            }
+        }
+
+        let binlog_event: BinlogEvent = match event_type {
             EventType::FormatDescriptionEvent => BinlogEvent::FormatDescriptionEvent(
                 FormatDescriptionEvent::parse(&mut cursor, &header)?,
             ),
@@ -88,6 +127,9 @@ impl EventParser {
             EventType::MySqlDeleteRowsEventV2 => BinlogEvent::DeleteRowsEvent(
                 DeleteRowsEvent::parse(&mut cursor, &self.table_map, 2)?,
             ),
+            EventType::MySqlPartialUpdateRowsEvent => BinlogEvent::MySqlPartialUpdateRowsEvent(
+                PartialUpdateRowsEvent::parse(&mut cursor, &self.table_map)?,
+            ),
             EventType::MySqlRowsQueryEvent => {
                 BinlogEvent::RowsQueryEvent(RowsQueryEvent::parse_mysql(&mut cursor)?)
             }
@@ -111,13 +153,221 @@ impl EventParser {
         };
 
         if let BinlogEvent::FormatDescriptionEvent(x) = &binlog_event {
+            // FormatDescriptionEvent is parsed before its checksum type is known.
+            if self.verify_checksum
+                && matches!(self.checksum_type, ChecksumType::None)
+                && matches!(x.checksum_type, ChecksumType::Crc32)
+            {
+                self.verify_event_checksum(header, slice)?;
+            }
             self.checksum_type = x.checksum_type;
         }
 
+        if let BinlogEvent::RotateEvent(x) = &binlog_event {
+            self.binlog_filename = x.binlog_filename.clone();
+        }
+
         if let BinlogEvent::TableMapEvent(x) = &binlog_event {
-            self.table_map.insert(x.table_id, x.clone()); //todo: optimize
+            if self.table_filter.matches(&x.database_name, &x.table_name) {
+                self.skipped_tables.remove(&x.table_id);
+                self.table_map.insert(x.table_id, x.clone()); //todo: optimize
+            } else {
+                self.table_map.remove(&x.table_id);
+                self.skipped_tables.insert(x.table_id);
+            }
         }
 
         Ok(binlog_event)
     }
+
+    fn verify_event_checksum(&self, header: &EventHeader, slice: &[u8]) -> Result<(), Error> {
+        let position = header
+            .next_event_position
+            .saturating_sub(header.event_length);
+        let checksum_mismatch = || Error::ChecksumMismatch {
+            filename: self.binlog_filename.clone(),
+            position,
+        };
+
+        if slice.len() < CHECKSUM_SIZE {
+            return Err(checksum_mismatch());
+        }
+        let (payload, mut checksum) = slice.split_at(slice.len() - CHECKSUM_SIZE);
+        let expected = checksum.read_u32::<LittleEndian>()?;
+
+        let mut header = header.clone();
+        if header.event_type == EventType::FormatDescriptionEvent as u8 {
+            header.event_flags &= !LOG_EVENT_BINLOG_IN_USE_F;
+        }
+
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&header.serialize()?);
+        hasher.update(payload);
+
+        if hasher.finalize() != expected {
+            return Err(checksum_mismatch());
+        }
+        Ok(())
+    }
+}
+
+fn is_rows_event(event_type: &EventType) -> bool {
+    matches!(
+        event_type,
+        EventType::WriteRowsEventV1
+            | EventType::UpdateRowsEventV1
+            | EventType::DeleteRowsEventV1
+            | EventType::MySqlWriteRowsEventV2
+            | EventType::MySqlUpdateRowsEventV2
+            | EventType::MySqlDeleteRowsEventV2
+            | EventType::MySqlPartialUpdateRowsEvent
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::checksum_type::ChecksumType;
+    use crate::errors::Error;
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::event_parser::EventParser;
+    use crate::events::format_description_event::FormatDescriptionEvent;
+    use crate::table_filter::{TableFilter, TablePattern};
+
+    fn xid_event() -> (EventHeader, Vec<u8>) {
+        let header = EventHeader {
+            timestamp: 1_600_000_000,
+            event_type: 16,
+            server_id: 1,
+            event_length: 31,
+            next_event_position: 1031,
+            event_flags: 0,
+        };
+        let mut payload = 42u64.to_le_bytes().to_vec();
+
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&header.serialize().unwrap());
+        hasher.update(&payload);
+        payload.extend_from_slice(&hasher.finalize().to_le_bytes());
+        (header, payload)
+    }
+
+    fn create_parser() -> EventParser {
+        let mut parser = EventParser::new();
+        parser.checksum_type = ChecksumType::Crc32;
+        parser.verify_checksum = true;
+        parser.binlog_filename = String::from("mysql-bin.000001");
+        parser
+    }
+
+    #[test]
+    fn valid_checksum_is_accepted() {
+        let (header, payload) = xid_event();
+        let event = create_parser().parse_event(&header, &payload).unwrap();
+        assert!(matches!(event, BinlogEvent::XidEvent(x) if x.xid == 42));
+    }
+
+    #[test]
+    fn corrupted_event_returns_checksum_mismatch() {
+        let (header, mut payload) = xid_event();
+        payload[0] ^= 0xff;
+
+        match create_parser().parse_event(&header, &payload) {
+            Err(Error::ChecksumMismatch { filename, position }) => {
+                assert_eq!("mysql-bin.000001", filename);
+                assert_eq!(1000, position);
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn checksum_is_ignored_when_verification_disabled() {
+        let (header, mut payload) = xid_event();
+        payload[0] ^= 0xff;
+
+        let mut parser = create_parser();
+        parser.verify_checksum = false;
+        assert!(parser.parse_event(&header, &payload).is_ok());
+    }
+
+    #[test]
+    fn active_binlog_format_description_is_accepted() {
+        let event = FormatDescriptionEvent {
+            binlog_version: 4,
+            server_version: String::from("8.0.33"),
+            checksum_type: ChecksumType::Crc32,
+        };
+        let mut payload = event.serialize().unwrap();
+        let mut header = header(15);
+        header.event_length = (19 + payload.len() + 4) as u32;
+        header.next_event_position = 4 + header.event_length;
+
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&header.serialize().unwrap());
+        hasher.update(&payload);
+        payload.extend_from_slice(&hasher.finalize().to_le_bytes());
+        // The server sets the in-use flag after computing the checksum.
+        header.event_flags = 1;
+
+        let mut parser = create_parser();
+        parser.checksum_type = ChecksumType::None;
+        let event = parser.parse_event(&header, &payload).unwrap();
+        assert!(matches!(event, BinlogEvent::FormatDescriptionEvent(_)));
+
+        // FormatDescriptionEvent of the next file is verified before parsing.
+        assert!(parser.parse_event(&header, &payload).is_ok());
+    }
+
+    fn header(event_type: u8) -> EventHeader {
+        EventHeader {
+            timestamp: 0,
+            event_type,
+            server_id: 1,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0,
+        }
+    }
+
+    fn table_map_event(table_id: u8, table_name: &str) -> Vec<u8> {
+        let mut payload = vec![table_id, 0, 0, 0, 0, 0, 0, 0];
+        payload.extend_from_slice(&[4, b's', b'h', b'o', b'p', 0]);
+        payload.push(table_name.len() as u8);
+        payload.extend_from_slice(table_name.as_bytes());
+        // One INT column without metadata
+        payload.extend_from_slice(&[0, 1, 3, 0, 0]);
+        payload
+    }
+
+    fn write_rows_event(table_id: u8) -> Vec<u8> {
+        // INT column with value 1
+        vec![table_id, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 1, 0, 1, 0, 0, 0]
+    }
+
+    #[test]
+    fn rows_of_excluded_tables_are_skipped() {
+        let mut parser = EventParser::new();
+        parser.table_filter = TableFilter {
+            include: vec![TablePattern::new("shop", "orders")],
+            exclude: Vec::new(),
+        };
+
+        for (table_id, table_name) in [(1, "orders"), (2, "audit")] {
+            let event = parser
+                .parse_event(&header(19), &table_map_event(table_id, table_name))
+                .unwrap();
+            assert!(matches!(event, BinlogEvent::TableMapEvent(_)));
+        }
+
+        let event = parser
+            .parse_event(&header(30), &write_rows_event(1))
+            .unwrap();
+        assert!(matches!(event, BinlogEvent::WriteRowsEvent(x) if x.rows.len() == 1));
+
+        let event = parser
+            .parse_event(&header(30), &write_rows_event(2))
+            .unwrap();
+        assert!(matches!(event, BinlogEvent::SkippedRowsEvent(x) if x.table_id == 2));
+    }
 }
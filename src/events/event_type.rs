@@ -67,6 +67,9 @@ pub enum EventType {
     /// Identifies <see cref="XaPrepareEvent"/> in MySQL from 5.6 to 8.0.
     MySqlXaPrepare = 38,
 
+    /// Identifies <see cref="PartialUpdateRowsEvent"/> in MySQL 8.0.
+    MySqlPartialUpdateRowsEvent = 39,
+
     /// MariaDB specific events
     /// Identifies <see cref="RowsQueryEvent"/> in MariaDB.
     MariaDbAnnotateRowsEvent = 160,
@@ -107,6 +110,7 @@ impl EventType {
             33 => EventType::MySqlGtidEvent,
             35 => EventType::MySqlPreviousGtidsEvent,
             38 => EventType::MySqlXaPrepare,
+            39 => EventType::MySqlPartialUpdateRowsEvent,
             160 => EventType::MariaDbAnnotateRowsEvent,
             161 => EventType::MariaDbBinlogCheckpointEvent,
             162 => EventType::MariaDbGtidEvent,
@@ -2,16 +2,26 @@ use crate::constants::checksum_type::ChecksumType;
 use crate::events::event_header::EventHeader;
 use crate::events::event_type::EventType;
 use crate::{constants, errors::Error};
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
 const EVENT_TYPES_OFFSET: u8 = 2 + 50 + 4 + 1;
+const SERVER_VERSION_LENGTH: usize = 50;
+
+/// Post-header lengths of event types 1-38 written by MySQL 5.7.
+/// Serialized events must use the same post-header layout.
+pub const POST_HEADER_LENGTHS: [u8; 38] = [
+    0x38, 0x0d, 0x00, 0x08, 0x00, 0x12, 0x00, 0x04, 0x04, 0x04, 0x04, 0x12, 0x00, 0x00, 0x5f, 0x00,
+    0x04, 0x1a, 0x08, 0x00, 0x00, 0x00, 0x08, 0x08, 0x08, 0x02, 0x00, 0x00, 0x00, 0x0a, 0x0a, 0x0a,
+    0x2a, 0x2a, 0x00, 0x12, 0x34, 0x00,
+];
 
 /// Written as the first event in binlog file or when replication is started.
 /// See <a href="https://mariadb.com/kb/en/library/format_description_event/">MariaDB docs</a>
 /// See <a href="https://dev.mysql.com/doc/internals/en/format-description-event.html">MySQL docs</a>
 /// See <a href="https://mariadb.com/kb/en/library/5-slave-registration/#events-transmission-after-com_binlog_dump">start events flow</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FormatDescriptionEvent {
     /// Gets binary log format version. This should always be 4.
     pub binlog_version: u16,
@@ -29,7 +39,7 @@ impl FormatDescriptionEvent {
         let binlog_version = cursor.read_u16::<LittleEndian>()?;
 
         // Read server version
-        let mut server_version = [0u8; 50];
+        let mut server_version = [0u8; SERVER_VERSION_LENGTH];
         cursor.read_exact(&mut server_version)?;
         let mut slice: &[u8] = &server_version;
         if let Some(zero_index) = server_version.iter().position(|&b| b == 0) {
@@ -61,4 +71,26 @@ impl FormatDescriptionEvent {
             checksum_type,
         })
     }
+
+    /// Serializes the event with post-header lengths of MySQL 5.7.
+    /// The checksum algorithm is followed by 4 bytes of the event checksum
+    /// which are always present in FormatDescriptionEvent.
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+
+        cursor.write_u16::<LittleEndian>(self.binlog_version)?;
+        let mut server_version = [0u8; SERVER_VERSION_LENGTH];
+        let length = self.server_version.len().min(SERVER_VERSION_LENGTH - 1);
+        server_version[..length].copy_from_slice(&self.server_version.as_bytes()[..length]);
+        cursor.write_all(&server_version)?;
+
+        // Creation timestamp is only set in the first binlog after server startup.
+        cursor.write_u32::<LittleEndian>(0)?;
+        cursor.write_u8(constants::EVENT_HEADER_SIZE as u8)?;
+        cursor.write_all(&POST_HEADER_LENGTHS)?;
+        cursor.write_u8(self.checksum_type as u8)?;
+
+        Ok(vec)
+    }
 }
@@ -4,7 +4,8 @@ use crate::errors::Error;
 
 /// The event is sent from master to the client for keep alive feature.
 /// <a href="https://mariadb.com/kb/en/library/heartbeat_log_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeartbeatEvent {
     /// Gets current master binlog filename
     pub binlog_filename: String,
@@ -5,7 +5,8 @@ use crate::errors::Error;
 
 /// Generated when an auto increment column or LAST_INSERT_ID() function are used.
 /// <a href="https://mariadb.com/kb/en/library/intvar_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntVarEvent {
     /// Gets type.
     /// 0x00 - Invalid value.
@@ -8,6 +8,7 @@ pub mod intvar_event;
 pub mod query_event;
 pub mod rotate_event;
 pub mod rows_query_event;
+pub mod status_variables;
 pub mod table_map_event;
 pub mod uservar_event;
 pub mod xid_event;
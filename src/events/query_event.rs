@@ -1,10 +1,15 @@
+use crate::charset::{decode_string, UTF8MB4_GENERAL_CI};
+use crate::ddl::ddl_parser::parse_ddl_with_sql_mode;
+use crate::ddl::schema_change::SchemaChange;
+use crate::events::status_variables::{StatusVariables, Q_CHARSET_CODE};
 use crate::{errors::Error, extensions::read_string};
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
 /// Represents sql statement in binary log.
 /// <a href="https://mariadb.com/kb/en/library/query_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueryEvent {
     /// Gets id of the thread that issued the statement.
     pub thread_id: u32,
@@ -18,11 +23,18 @@ pub struct QueryEvent {
     /// Gets status variables.
     pub status_variables: Vec<u8>,
 
+    /// Gets parsed status variables.
+    pub status: StatusVariables,
+
     /// Gets the default database name.
     pub database_name: String,
 
     /// Gets the SQL statement.
     pub sql_statement: String,
+
+    /// Gets schema change of a DDL statement.
+    /// None if the statement doesn't change tables or databases or cannot be parsed.
+    pub schema_change: Option<SchemaChange>,
 }
 
 impl QueryEvent {
@@ -44,16 +56,172 @@ impl QueryEvent {
         let database_name = read_string(cursor, database_name_length as usize)?;
         cursor.seek(SeekFrom::Current(1))?;
 
-        let mut sql_statement = String::new();
-        cursor.read_to_string(&mut sql_statement)?;
+        // Statement is written in the client charset.
+        let status = StatusVariables::parse(&status_variables);
+        let collation = status.charset_client.map(|x| x as u32);
+        let mut statement = Vec::new();
+        cursor.read_to_end(&mut statement)?;
+        let sql_statement = match decode_string(statement, collation) {
+            Ok(x) => x,
+            Err(x) => String::from_utf8_lossy(&x).into_owned(),
+        };
+
+        // Unsupported syntax shouldn't fail parsing of the event.
+        let sql_mode = status.sql_mode.unwrap_or(0);
+        let schema_change =
+            parse_ddl_with_sql_mode(&sql_statement, &database_name, sql_mode).unwrap_or(None);
 
         Ok(Self {
             thread_id,
             duration,
             error_code,
             status_variables,
+            status,
             database_name,
             sql_statement,
+            schema_change,
         })
     }
+
+    /// Serializes the event with raw status variables. The statement is written in UTF-8,
+    /// so the logged client charset is replaced with utf8mb4.
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut status_variables = self.status_variables.clone();
+        if let Some(i) = StatusVariables::find_variable(&status_variables, Q_CHARSET_CODE) {
+            let collation = UTF8MB4_GENERAL_CI as u16;
+            status_variables[i..i + 2].copy_from_slice(&collation.to_le_bytes());
+        }
+
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+
+        cursor.write_u32::<LittleEndian>(self.thread_id)?;
+        cursor.write_u32::<LittleEndian>(self.duration)?;
+        cursor.write_u8(self.database_name.len() as u8)?;
+        cursor.write_u16::<LittleEndian>(self.error_code)?;
+        cursor.write_u16::<LittleEndian>(status_variables.len() as u16)?;
+        cursor.write_all(&status_variables)?;
+
+        // DatabaseName is null terminated
+        cursor.write_all(self.database_name.as_bytes())?;
+        cursor.write_u8(0)?;
+        cursor.write_all(self.sql_statement.as_bytes())?;
+
+        Ok(vec)
+    }
+
+    /// Parses the statement as a DDL statement using the default database of the event.
+    /// Unlike the schema_change field returns the error for unsupported syntax.
+    pub fn parse_schema_change(&self) -> Result<Option<SchemaChange>, Error> {
+        let sql_mode = self.status.sql_mode.unwrap_or(0);
+        parse_ddl_with_sql_mode(&self.sql_statement, &self.database_name, sql_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::QueryEvent;
+    use crate::ddl::schema_change::{SchemaChange, TableName};
+
+    fn parse(sql: &str) -> QueryEvent {
+        let event = QueryEvent {
+            thread_id: 5,
+            duration: 0,
+            error_code: 0,
+            status_variables: Vec::new(),
+            status: Default::default(),
+            database_name: String::from("shop"),
+            sql_statement: sql.to_string(),
+            schema_change: None,
+        };
+        let payload = event.serialize().unwrap();
+        QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap()
+    }
+
+    #[test]
+    fn schema_change_is_parsed_with_event() {
+        let event = parse("TRUNCATE orders");
+        assert_eq!(
+            Some(SchemaChange::TruncateTable {
+                table: TableName::new("shop", "orders")
+            }),
+            event.schema_change
+        );
+        assert!(parse("BEGIN").schema_change.is_none());
+
+        // Unsupported syntax doesn't fail the event.
+        let event = parse("ALTER TABLE orders ADD COLUMN (");
+        assert!(event.schema_change.is_none());
+        assert!(event.parse_schema_change().is_err());
+    }
+
+    #[test]
+    fn schema_change_uses_sql_mode() {
+        // sql_mode with NO_BACKSLASH_ESCAPES
+        let status_variables = vec![0x01, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let event = QueryEvent {
+            thread_id: 5,
+            duration: 0,
+            error_code: 0,
+            status_variables,
+            status: Default::default(),
+            database_name: String::from("shop"),
+            sql_statement: String::from("CREATE TABLE t (a int COMMENT 'C:\\')"),
+            schema_change: None,
+        };
+        let payload = event.serialize().unwrap();
+        let event = QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap();
+
+        assert!(event.schema_change.is_some());
+        assert!(event.parse_schema_change().unwrap().is_some());
+        assert!(parse("CREATE TABLE t (a int COMMENT 'C:\\')")
+            .schema_change
+            .is_none());
+    }
+
+    #[test]
+    fn serialize_statement_of_another_charset() {
+        // flags2, then charset with latin1 client, connection and server
+        let status_variables = vec![
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00,
+        ];
+        let mut payload = vec![0; 13];
+        payload[11] = status_variables.len() as u8;
+        payload.extend_from_slice(&status_variables);
+        payload.push(0);
+        payload.extend_from_slice(b"INSERT INTO t VALUES ('caf\xe9')");
+        let event = QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap();
+        assert_eq!("INSERT INTO t VALUES ('café')", event.sql_statement);
+
+        let payload = event.serialize().unwrap();
+        let event = QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap();
+        assert_eq!("INSERT INTO t VALUES ('café')", event.sql_statement);
+        assert_eq!(Some(45), event.status.charset_client);
+        assert_eq!(Some(8), event.status.collation_connection);
+    }
+
+    #[test]
+    fn malformed_status_variables_keep_raw_bytes() {
+        // charset variable is truncated
+        let status_variables = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x21, 0x00];
+        let event = QueryEvent {
+            thread_id: 5,
+            duration: 0,
+            error_code: 0,
+            status_variables: status_variables.clone(),
+            status: Default::default(),
+            database_name: String::from("shop"),
+            sql_statement: String::from("BEGIN"),
+            schema_change: None,
+        };
+        let payload = event.serialize().unwrap();
+        let event = QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap();
+
+        assert_eq!(status_variables, event.status_variables);
+        assert_eq!(Some(0), event.status.flags2);
+        assert_eq!(None, event.status.charset_client);
+        assert_eq!("BEGIN", event.sql_statement);
+    }
 }
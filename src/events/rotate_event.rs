@@ -1,12 +1,13 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Cursor, Read};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Read, Write};
 
 use crate::errors::Error;
 
 /// Last event in a binlog file which points to next binlog file.
 /// Fake version is also returned when replication is started.
 /// <a href="https://mariadb.com/kb/en/library/rotate_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RotateEvent {
     /// Gets next binlog filename
     pub binlog_filename: String,
@@ -28,4 +29,14 @@ impl RotateEvent {
             binlog_filename,
         })
     }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+
+        cursor.write_u64::<LittleEndian>(self.binlog_position)?;
+        cursor.write_all(self.binlog_filename.as_bytes())?;
+
+        Ok(vec)
+    }
 }
@@ -1,16 +1,19 @@
 use crate::errors::Error;
 use crate::events::row_events::mysql_value::{Date, DateTime, Time};
-use crate::extensions::{read_bitmap_big_endian, read_string};
+use crate::extensions::read_bitmap_big_endian;
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 use std::io::{Cursor, Read};
 
-pub fn parse_string(cursor: &mut Cursor<&[u8]>, metadata: u16) -> Result<String, Error> {
+/// Reads raw bytes of a string. Decoding depends on the column charset.
+pub fn parse_string(cursor: &mut Cursor<&[u8]>, metadata: u16) -> Result<Vec<u8>, Error> {
     let length = if metadata < 256 {
         cursor.read_u8()? as usize
     } else {
         cursor.read_u16::<LittleEndian>()? as usize
     };
-    Ok(read_string(cursor, length)?)
+    let mut vec = vec![0; length];
+    cursor.read_exact(&mut vec)?;
+    Ok(vec)
 }
 
 pub fn parse_bit(cursor: &mut Cursor<&[u8]>, metadata: u16) -> Result<Vec<bool>, Error> {
@@ -0,0 +1,200 @@
+use crate::errors::Error;
+use crate::events::row_events::mysql_value::{Date, DateTime, Time};
+use crate::extensions::write_bitmap_big_endian;
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+use std::io::{Cursor, Write};
+
+// Writing column values. See col_parser.
+
+/// Writes unsigned integer of the size in bytes. Fails if the value doesn't fit.
+pub fn write_uint<T: ByteOrder>(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    value: u64,
+    size: usize,
+) -> Result<(), Error> {
+    if size == 0 || size > 8 || (size < 8 && value >> (8 * size) != 0) {
+        return Err(Error::String(format!(
+            "Value {} doesn't fit {} bytes",
+            value, size
+        )));
+    }
+    cursor.write_uint::<T>(value, size)?;
+    Ok(())
+}
+
+/// Writes raw bytes of a string with the length prefix.
+pub fn write_string(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bytes: &[u8],
+    metadata: u16,
+) -> Result<(), Error> {
+    if bytes.len() > metadata as usize {
+        return Err(Error::String(format!(
+            "String of {} bytes doesn't fit column of {} bytes",
+            bytes.len(),
+            metadata
+        )));
+    }
+    if metadata < 256 {
+        write_uint::<LittleEndian>(cursor, bytes.len() as u64, 1)?;
+    } else {
+        write_uint::<LittleEndian>(cursor, bytes.len() as u64, 2)?;
+    }
+    cursor.write_all(bytes)?;
+    Ok(())
+}
+
+pub fn write_bit(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bitmap: &[bool],
+    metadata: u16,
+) -> Result<(), Error> {
+    let length = (metadata >> 8) * 8 + (metadata & 0xFF);
+    if bitmap.len() != length as usize {
+        return Err(Error::String(format!(
+            "BIT({}) column can't store {} bits",
+            length,
+            bitmap.len()
+        )));
+    }
+    let mut bitmap = bitmap.to_vec();
+    bitmap.reverse();
+    write_bitmap_big_endian(cursor, &bitmap)?;
+    Ok(())
+}
+
+pub fn write_blob(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bytes: &[u8],
+    metadata: u16,
+) -> Result<(), Error> {
+    write_uint::<LittleEndian>(cursor, bytes.len() as u64, metadata as usize)?;
+    cursor.write_all(bytes)?;
+    Ok(())
+}
+
+pub fn write_year(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    year: u16,
+    _metadata: u16,
+) -> Result<(), Error> {
+    match year.checked_sub(1900) {
+        Some(x) if x <= u8::MAX as u16 => cursor.write_u8(x as u8)?,
+        _ => return Err(Error::String(format!("Invalid YEAR value {}", year))),
+    }
+    Ok(())
+}
+
+pub fn write_date(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    date: &Date,
+    _metadata: u16,
+) -> Result<(), Error> {
+    let value = (date.year as u64) << 9 | (date.month as u64) << 5 | date.day as u64;
+    write_uint::<LittleEndian>(cursor, value, 3)
+}
+
+pub fn write_time(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    time: &Time,
+    _metadata: u16,
+) -> Result<(), Error> {
+    if time.hour < 0 {
+        return Err(Error::String(
+            "Writing negative TIME values is not supported in this version".to_string(),
+        ));
+    }
+    let value = time.hour as u64 * 10000 + time.minute as u64 * 100 + time.second as u64;
+    write_uint::<LittleEndian>(cursor, value, 3)
+}
+
+pub fn write_time2(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    time: &Time,
+    metadata: u16,
+) -> Result<(), Error> {
+    // Negative values are stored in reverse order. See parse_time2.
+    if time.hour < 0 {
+        return Err(Error::String(
+            "Writing negative TIME values is not supported in this version".to_string(),
+        ));
+    }
+    if time.hour >= 1 << 10 {
+        return Err(Error::String(format!("Invalid TIME hour {}", time.hour)));
+    }
+
+    // 1 bit sign. 1 bit unused. 10 bits hour. 6 bits minute. 6 bits second.
+    let value = 1 << 23 | (time.hour as u64) << 12 | (time.minute as u64) << 6 | time.second as u64;
+    write_uint::<BigEndian>(cursor, value, 3)?;
+    write_fractional_part(cursor, time.millis, metadata)
+}
+
+pub fn write_date_time(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    date_time: &DateTime,
+    _metadata: u16,
+) -> Result<(), Error> {
+    let mut value = date_time.year as u64;
+    for x in [
+        date_time.month,
+        date_time.day,
+        date_time.hour,
+        date_time.minute,
+        date_time.second,
+    ] {
+        value = value * 100 + x as u64;
+    }
+    cursor.write_u64::<LittleEndian>(value)?;
+    Ok(())
+}
+
+pub fn write_date_time2(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    date_time: &DateTime,
+    metadata: u16,
+) -> Result<(), Error> {
+    // 1 bit sign(always true). 17 bits year*13+month. 5 bits day. 5 bits hour. 6 bits minute. 6 bits second.
+    let year_month = date_time.year as u64 * 13 + date_time.month as u64;
+    let value = 1 << 39
+        | year_month << 22
+        | (date_time.day as u64) << 17
+        | (date_time.hour as u64) << 12
+        | (date_time.minute as u64) << 6
+        | date_time.second as u64;
+    write_uint::<BigEndian>(cursor, value, 5)?;
+    write_fractional_part(cursor, date_time.millis, metadata)
+}
+
+pub fn write_timestamp(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    timestamp: u64,
+    _metadata: u16,
+) -> Result<(), Error> {
+    write_uint::<LittleEndian>(cursor, timestamp / 1000, 4)
+}
+
+pub fn write_timestamp2(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    timestamp: u64,
+    metadata: u16,
+) -> Result<(), Error> {
+    write_uint::<BigEndian>(cursor, timestamp / 1000, 4)?;
+    write_fractional_part(cursor, (timestamp % 1000) as u32, metadata)
+}
+
+fn write_fractional_part(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    millis: u32,
+    metadata: u16,
+) -> Result<(), Error> {
+    let length = metadata.div_ceil(2);
+    if length == 0 {
+        return Ok(());
+    }
+    if millis >= 1000 {
+        return Err(Error::String(format!("Invalid milliseconds {}", millis)));
+    }
+
+    let fraction = millis as u64 * 1000 / u64::pow(100, 3 - length as u32);
+    write_uint::<BigEndian>(cursor, fraction, length as usize)
+}
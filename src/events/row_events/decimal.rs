@@ -1,15 +1,25 @@
 use crate::errors::Error;
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use std::io::{Cursor, Read};
 
 /// See <a href="https://dev.mysql.com/doc/internals/en/date-and-time-data-type-representation.html">Docs</a>
 
 const DIGITS_PER_INT: u8 = 9;
 const COMPRESSED_BYTES: [u8; 10] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4];
+const MAX_PRECISION: u16 = 65;
+const MAX_SCALE: u8 = 30;
 
 pub fn parse_decimal(cursor: &mut Cursor<&[u8]>, metadata: u16) -> Result<String, Error> {
     let precision = metadata & 0xFF;
     let scale = (metadata >> 8) as u8;
+    // Precision and scale come from the event bytes.
+    if precision == 0 || precision > MAX_PRECISION || scale > MAX_SCALE || scale as u16 > precision
+    {
+        return Err(Error::String(format!(
+            "Invalid decimal precision {} and scale {}",
+            precision, scale
+        )));
+    }
     let integral = (precision - scale as u16) as u8;
 
     let uncompressed_integral = integral / DIGITS_PER_INT;
@@ -82,12 +92,92 @@ pub fn parse_decimal(cursor: &mut Cursor<&[u8]>, metadata: u16) -> Result<String
     Ok(result)
 }
 
+/// Writes decimal value like '-123.45' in the binary format of parse_decimal.
+pub fn serialize_decimal(value: &str, metadata: u16) -> Result<Vec<u8>, Error> {
+    let precision = metadata & 0xFF;
+    let scale = (metadata >> 8) as u8;
+    if precision == 0 || precision > MAX_PRECISION || scale > MAX_SCALE || scale as u16 > precision
+    {
+        return Err(Error::String(format!(
+            "Invalid decimal precision {} and scale {}",
+            precision, scale
+        )));
+    }
+    let integral = (precision - scale as u16) as u8;
+
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, value),
+    };
+    let (integral_digits, fractional_digits) = digits.split_once('.').unwrap_or((digits, ""));
+    let integral_digits = integral_digits.trim_start_matches('0');
+    if !integral_digits.bytes().all(|x| x.is_ascii_digit())
+        || !fractional_digits.bytes().all(|x| x.is_ascii_digit())
+        || integral_digits.len() > integral as usize
+        || fractional_digits.len() > scale as usize
+    {
+        return Err(Error::String(format!(
+            "Decimal value {} doesn't fit precision {} and scale {}",
+            value, precision, scale
+        )));
+    }
+    // Digits padded with zeros to the column precision and scale.
+    let integral_digits = format!("{:0>width$}", integral_digits, width = integral as usize);
+    let fractional_digits = format!("{:0<width$}", fractional_digits, width = scale as usize);
+
+    let uncompressed_integral = integral / DIGITS_PER_INT;
+    let compressed_integral = integral - (uncompressed_integral * DIGITS_PER_INT);
+    let uncompressed_fractional = scale / DIGITS_PER_INT;
+    let compressed_fractional = scale - (uncompressed_fractional * DIGITS_PER_INT);
+
+    let mut vec = Vec::new();
+    let mut cursor = Cursor::new(&mut vec);
+    let (compressed, uncompressed) = integral_digits.split_at(compressed_integral as usize);
+    let size = COMPRESSED_BYTES[compressed_integral as usize];
+    if size > 0 {
+        cursor.write_uint::<BigEndian>(compressed.parse::<u64>()?, size as usize)?;
+    }
+    for i in 0..uncompressed_integral as usize {
+        let number = &uncompressed[i * 9..(i + 1) * 9];
+        cursor.write_u32::<BigEndian>(number.parse::<u32>()?)?;
+    }
+
+    let (uncompressed, compressed) =
+        fractional_digits.split_at(uncompressed_fractional as usize * 9);
+    for i in 0..uncompressed_fractional as usize {
+        let number = &uncompressed[i * 9..(i + 1) * 9];
+        cursor.write_u32::<BigEndian>(number.parse::<u32>()?)?;
+    }
+    let size = COMPRESSED_BYTES[compressed_fractional as usize];
+    if size > 0 {
+        cursor.write_uint::<BigEndian>(compressed.parse::<u64>()?, size as usize)?;
+    }
+
+    // Zero is always positive
+    if negative && vec.iter().any(|x| *x != 0) {
+        for x in vec.iter_mut() {
+            *x ^= 0xFF;
+        }
+    }
+    vec[0] ^= 0x80;
+    Ok(vec)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::events::row_events::decimal::parse_decimal;
+    use crate::events::row_events::decimal::{parse_decimal, serialize_decimal};
     use byteorder::{LittleEndian, ReadBytesExt};
     use std::io::Cursor;
 
+    #[test]
+    fn invalid_precision_and_scale_are_rejected() {
+        let payload = [0u8; 32];
+        for (precision, scale) in [(0, 0), (4, 5), (66, 0), (65, 31)] {
+            let mut cursor = Cursor::new(&payload[..]);
+            assert!(parse_decimal(&mut cursor, precision | scale << 8).is_err());
+        }
+    }
+
     #[test]
     fn parse_positive_number() {
         // decimal(65,10), column = '1234567890112233445566778899001112223334445556667778889.9900011112'
@@ -174,4 +264,47 @@ mod tests {
         let expected = String::from("34445556667778889");
         assert_eq!(expected, parse_decimal(&mut cursor, metadata).unwrap());
     }
+
+    #[test]
+    fn serialize_numbers() {
+        // Payloads of the tests above
+        let payloads: [(&str, Vec<u8>); 3] = [
+            (
+                "-1234567890112233445566778899001112223334445556667778889.9900011112",
+                vec![
+                    65, 10, 126, 242, 4, 199, 45, 249, 79, 116, 26, 222, 55, 163, 236, 255, 239, 7,
+                    96, 236, 16, 196, 11, 216, 50, 128, 182, 196, 253, 200, 40, 253,
+                ],
+            ),
+            (
+                "0.9900011112",
+                vec![
+                    65, 10, 128, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 0, 59, 2, 55, 215, 2,
+                ],
+            ),
+            (
+                "34445556667778889.123456789006700",
+                vec![
+                    60, 15, 128, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 13, 152, 244, 39, 205, 127,
+                    73, 7, 91, 205, 21, 0, 26, 44,
+                ],
+            ),
+        ];
+        for (value, payload) in payloads {
+            let metadata = payload[0] as u16 | (payload[1] as u16) << 8;
+            assert_eq!(payload[2..], serialize_decimal(value, metadata).unwrap());
+        }
+
+        // decimal(5,2)
+        let metadata = 5 | 2 << 8;
+        for (value, expected) in [("-1.5", "-1.50"), ("-0", "0.00"), ("123", "123.00")] {
+            let bytes = serialize_decimal(value, metadata).unwrap();
+            let mut cursor = Cursor::new(bytes.as_slice());
+            assert_eq!(expected, parse_decimal(&mut cursor, metadata).unwrap());
+        }
+        for value in ["1234", "1.234", "1e5", "abc"] {
+            assert!(serialize_decimal(value, metadata).is_err());
+        }
+    }
 }
@@ -0,0 +1,372 @@
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt};
+use std::fmt;
+use std::io::Cursor;
+
+use crate::errors::Error;
+use crate::events::row_events::json_value::JsonValue;
+
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+
+/// Point of a geometry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Geometry tree decoded from the WKB representation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    Point(Point),
+    LineString(Vec<Point>),
+
+    /// Exterior ring followed by interior rings.
+    Polygon(Vec<Vec<Point>>),
+    MultiPoint(Vec<Point>),
+    MultiLineString(Vec<Vec<Point>>),
+    MultiPolygon(Vec<Vec<Vec<Point>>>),
+    GeometryCollection(Vec<Shape>),
+}
+
+/// Value of a MySQL GEOMETRY column.
+/// <a href="https://dev.mysql.com/doc/refman/8.0/en/gis-data-formats.html#gis-internal-format">See more</a>
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geometry {
+    /// Gets spatial reference system identifier. 0 if not specified.
+    pub srid: u32,
+
+    /// Gets the geometry tree.
+    pub shape: Shape,
+}
+
+impl Geometry {
+    /// Parses internal format: 4 bytes of SRID followed by WKB.
+    pub fn parse(slice: &[u8]) -> Result<Self, Error> {
+        let mut cursor = Cursor::new(slice);
+        let srid = cursor.read_u32::<LittleEndian>()?;
+        let shape = parse_shape(&mut cursor)?;
+        Ok(Self { srid, shape })
+    }
+
+    /// Gets Well-Known Text representation, e.g. POINT(1 2)
+    pub fn to_wkt(&self) -> String {
+        self.shape.to_string()
+    }
+
+    /// Gets GeoJSON representation the same way as ST_AsGeoJSON.
+    pub fn to_geojson(&self) -> JsonValue {
+        self.shape.to_geojson()
+    }
+
+    /// Gets Well-Known Binary representation in little-endian byte order without SRID.
+    pub fn to_wkb(&self) -> Vec<u8> {
+        let mut wkb = Vec::new();
+        write_shape(&mut wkb, &self.shape);
+        wkb
+    }
+}
+
+impl Shape {
+    /// Gets GeoJSON representation of the shape.
+    pub fn to_geojson(&self) -> JsonValue {
+        let (name, key, value) = match self {
+            Shape::Point(x) => ("Point", "coordinates", point_json(x)),
+            Shape::LineString(x) => ("LineString", "coordinates", points_json(x)),
+            Shape::Polygon(x) => ("Polygon", "coordinates", rings_json(x)),
+            Shape::MultiPoint(x) => ("MultiPoint", "coordinates", points_json(x)),
+            Shape::MultiLineString(x) => ("MultiLineString", "coordinates", rings_json(x)),
+            Shape::MultiPolygon(x) => (
+                "MultiPolygon",
+                "coordinates",
+                JsonValue::Array(x.iter().map(|p| rings_json(p)).collect()),
+            ),
+            Shape::GeometryCollection(x) => (
+                "GeometryCollection",
+                "geometries",
+                JsonValue::Array(x.iter().map(|s| s.to_geojson()).collect()),
+            ),
+        };
+        JsonValue::Object(vec![
+            ("type".to_string(), JsonValue::String(name.to_string())),
+            (key.to_string(), value),
+        ])
+    }
+}
+
+/// Renders the shape as Well-Known Text.
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Point(x) => write!(f, "POINT({} {})", x.x, x.y),
+            Shape::LineString(x) => {
+                write!(f, "LINESTRING")?;
+                write_points(f, x)
+            }
+            Shape::Polygon(x) => {
+                write!(f, "POLYGON")?;
+                write_rings(f, x)
+            }
+            Shape::MultiPoint(x) => {
+                let points: Vec<Vec<Point>> = x.iter().map(|p| vec![*p]).collect();
+                write!(f, "MULTIPOINT")?;
+                write_rings(f, &points)
+            }
+            Shape::MultiLineString(x) => {
+                write!(f, "MULTILINESTRING")?;
+                write_rings(f, x)
+            }
+            Shape::MultiPolygon(x) => {
+                write!(f, "MULTIPOLYGON(")?;
+                for (i, polygon) in x.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_rings(f, polygon)?;
+                }
+                write!(f, ")")
+            }
+            Shape::GeometryCollection(x) if x.is_empty() => write!(f, "GEOMETRYCOLLECTION EMPTY"),
+            Shape::GeometryCollection(x) => {
+                write!(f, "GEOMETRYCOLLECTION(")?;
+                for (i, shape) in x.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", shape)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn write_points(f: &mut fmt::Formatter, points: &[Point]) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{} {}", point.x, point.y)?;
+    }
+    write!(f, ")")
+}
+
+fn write_rings(f: &mut fmt::Formatter, rings: &[Vec<Point>]) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, ring) in rings.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write_points(f, ring)?;
+    }
+    write!(f, ")")
+}
+
+fn point_json(point: &Point) -> JsonValue {
+    JsonValue::Array(vec![JsonValue::Double(point.x), JsonValue::Double(point.y)])
+}
+
+fn points_json(points: &[Point]) -> JsonValue {
+    JsonValue::Array(points.iter().map(point_json).collect())
+}
+
+fn rings_json(rings: &[Vec<Point>]) -> JsonValue {
+    JsonValue::Array(rings.iter().map(|x| points_json(x)).collect())
+}
+
+/// WKB format:
+/// [byte-order] [geometry-type] [data]
+/// Byte order is 0 for big endian and 1 for little endian.
+fn write_shape(wkb: &mut Vec<u8>, shape: &Shape) {
+    // Little-endian byte order
+    wkb.push(1);
+    let geometry_type = match shape {
+        Shape::Point(_) => POINT,
+        Shape::LineString(_) => LINE_STRING,
+        Shape::Polygon(_) => POLYGON,
+        Shape::MultiPoint(_) => MULTI_POINT,
+        Shape::MultiLineString(_) => MULTI_LINE_STRING,
+        Shape::MultiPolygon(_) => MULTI_POLYGON,
+        Shape::GeometryCollection(_) => GEOMETRY_COLLECTION,
+    };
+    wkb.extend_from_slice(&geometry_type.to_le_bytes());
+    match shape {
+        Shape::Point(x) => write_point(wkb, x),
+        Shape::LineString(x) => write_point_list(wkb, x),
+        Shape::Polygon(x) => write_ring_list(wkb, x),
+        Shape::MultiPoint(x) => write_shape_list(wkb, x.iter().map(|p| Shape::Point(*p))),
+        Shape::MultiLineString(x) => {
+            write_shape_list(wkb, x.iter().map(|l| Shape::LineString(l.clone())))
+        }
+        Shape::MultiPolygon(x) => {
+            write_shape_list(wkb, x.iter().map(|p| Shape::Polygon(p.clone())))
+        }
+        Shape::GeometryCollection(x) => write_shape_list(wkb, x.iter().cloned()),
+    }
+}
+
+fn write_point(wkb: &mut Vec<u8>, point: &Point) {
+    wkb.extend_from_slice(&point.x.to_le_bytes());
+    wkb.extend_from_slice(&point.y.to_le_bytes());
+}
+
+fn write_point_list(wkb: &mut Vec<u8>, points: &[Point]) {
+    wkb.extend_from_slice(&(points.len() as u32).to_le_bytes());
+    points.iter().for_each(|x| write_point(wkb, x));
+}
+
+fn write_ring_list(wkb: &mut Vec<u8>, rings: &[Vec<Point>]) {
+    wkb.extend_from_slice(&(rings.len() as u32).to_le_bytes());
+    rings.iter().for_each(|x| write_point_list(wkb, x));
+}
+
+fn write_shape_list<I: ExactSizeIterator<Item = Shape>>(wkb: &mut Vec<u8>, shapes: I) {
+    wkb.extend_from_slice(&(shapes.len() as u32).to_le_bytes());
+    shapes.for_each(|x| write_shape(wkb, &x));
+}
+
+fn parse_shape(cursor: &mut Cursor<&[u8]>) -> Result<Shape, Error> {
+    match cursor.read_u8()? {
+        0 => parse_shape_data::<BigEndian>(cursor),
+        1 => parse_shape_data::<LittleEndian>(cursor),
+        x => Err(Error::String(format!("Invalid WKB byte order {}", x))),
+    }
+}
+
+fn parse_shape_data<T: ByteOrder>(cursor: &mut Cursor<&[u8]>) -> Result<Shape, Error> {
+    let shape = match cursor.read_u32::<T>()? {
+        POINT => Shape::Point(parse_point::<T>(cursor)?),
+        LINE_STRING => Shape::LineString(parse_points::<T>(cursor)?),
+        POLYGON => Shape::Polygon(parse_rings::<T>(cursor)?),
+        MULTI_POINT => {
+            let mut points = Vec::new();
+            for shape in parse_shapes::<T>(cursor)? {
+                match shape {
+                    Shape::Point(x) => points.push(x),
+                    _ => return Err(invalid_member("MultiPoint")),
+                }
+            }
+            Shape::MultiPoint(points)
+        }
+        MULTI_LINE_STRING => {
+            let mut lines = Vec::new();
+            for shape in parse_shapes::<T>(cursor)? {
+                match shape {
+                    Shape::LineString(x) => lines.push(x),
+                    _ => return Err(invalid_member("MultiLineString")),
+                }
+            }
+            Shape::MultiLineString(lines)
+        }
+        MULTI_POLYGON => {
+            let mut polygons = Vec::new();
+            for shape in parse_shapes::<T>(cursor)? {
+                match shape {
+                    Shape::Polygon(x) => polygons.push(x),
+                    _ => return Err(invalid_member("MultiPolygon")),
+                }
+            }
+            Shape::MultiPolygon(polygons)
+        }
+        GEOMETRY_COLLECTION => Shape::GeometryCollection(parse_shapes::<T>(cursor)?),
+        x => return Err(Error::String(format!("Unknown WKB geometry type {}", x))),
+    };
+    Ok(shape)
+}
+
+fn parse_point<T: ByteOrder>(cursor: &mut Cursor<&[u8]>) -> Result<Point, Error> {
+    let x = cursor.read_f64::<T>()?;
+    let y = cursor.read_f64::<T>()?;
+    Ok(Point { x, y })
+}
+
+fn parse_points<T: ByteOrder>(cursor: &mut Cursor<&[u8]>) -> Result<Vec<Point>, Error> {
+    let count = cursor.read_u32::<T>()?;
+    let mut points = Vec::new();
+    for _ in 0..count {
+        points.push(parse_point::<T>(cursor)?);
+    }
+    Ok(points)
+}
+
+fn parse_rings<T: ByteOrder>(cursor: &mut Cursor<&[u8]>) -> Result<Vec<Vec<Point>>, Error> {
+    let count = cursor.read_u32::<T>()?;
+    let mut rings = Vec::new();
+    for _ in 0..count {
+        rings.push(parse_points::<T>(cursor)?);
+    }
+    Ok(rings)
+}
+
+/// Members of multi geometries are complete WKB values with their own byte order.
+fn parse_shapes<T: ByteOrder>(cursor: &mut Cursor<&[u8]>) -> Result<Vec<Shape>, Error> {
+    let count = cursor.read_u32::<T>()?;
+    let mut shapes = Vec::new();
+    for _ in 0..count {
+        shapes.push(parse_shape(cursor)?);
+    }
+    Ok(shapes)
+}
+
+fn invalid_member(geometry: &str) -> Error {
+    Error::String(format!("Invalid member of WKB {}", geometry))
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    use super::{Geometry, Point, Shape};
+
+    fn write_point(vec: &mut Vec<u8>, x: f64, y: f64) {
+        vec.write_f64::<LittleEndian>(x).unwrap();
+        vec.write_f64::<LittleEndian>(y).unwrap();
+    }
+
+    #[test]
+    fn parse_point() {
+        let mut data = vec![0xE6, 0x10, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00];
+        write_point(&mut data, 1.5, -2.0);
+
+        let geometry = Geometry::parse(&data).unwrap();
+        assert_eq!(4326, geometry.srid);
+        assert_eq!(Shape::Point(Point { x: 1.5, y: -2.0 }), geometry.shape);
+        assert_eq!("POINT(1.5 -2)", geometry.to_wkt());
+        assert_eq!(
+            r#"{"type": "Point", "coordinates": [1.5, -2.0]}"#,
+            geometry.to_geojson().to_string()
+        );
+    }
+
+    #[test]
+    fn parse_geometry_collection() {
+        // GEOMETRYCOLLECTION(POINT(1 2),POLYGON((0 0,1 0,1 1,0 0)))
+        let mut data = vec![0, 0, 0, 0, 0x01, 0x07, 0, 0, 0, 0x02, 0, 0, 0];
+        data.extend_from_slice(&[0x01, 0x01, 0, 0, 0]);
+        write_point(&mut data, 1.0, 2.0);
+        data.extend_from_slice(&[0x01, 0x03, 0, 0, 0, 0x01, 0, 0, 0, 0x04, 0, 0, 0]);
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)] {
+            write_point(&mut data, x, y);
+        }
+
+        let geometry = Geometry::parse(&data).unwrap();
+        assert_eq!(
+            "GEOMETRYCOLLECTION(POINT(1 2),POLYGON((0 0,1 0,1 1,0 0)))",
+            geometry.to_wkt()
+        );
+        assert_eq!(
+            r#"{"type": "GeometryCollection", "geometries": [{"type": "Point", "coordinates": [1.0, 2.0]}, {"type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]}]}"#,
+            geometry.to_geojson().to_string()
+        );
+        assert_eq!(data[4..], geometry.to_wkb());
+    }
+}
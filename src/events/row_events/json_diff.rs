@@ -0,0 +1,289 @@
+use crate::errors::Error;
+use crate::events::row_events::json_value::JsonValue;
+
+/// Operation of a partial JSON update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsonDiffOperation {
+    /// The value at the path is replaced by a new value.
+    Replace = 0,
+
+    /// New value is added to an object or inserted into an array at the path.
+    Insert = 1,
+
+    /// The value at the path is removed.
+    Remove = 2,
+}
+
+impl JsonDiffOperation {
+    pub fn from_code(code: u8) -> Result<Self, Error> {
+        match code {
+            0 => Ok(JsonDiffOperation::Replace),
+            1 => Ok(JsonDiffOperation::Insert),
+            2 => Ok(JsonDiffOperation::Remove),
+            _ => Err(Error::String(format!(
+                "Unknown JSON diff operation {}",
+                code
+            ))),
+        }
+    }
+}
+
+/// Single modification of a JSON column logged with binlog_row_value_options=PARTIAL_JSON.
+/// <a href="https://dev.mysql.com/doc/refman/8.0/en/replication-options-binary-log.html#sysvar_binlog_row_value_options">See more</a>
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonDiff {
+    /// Gets the operation.
+    pub operation: JsonDiffOperation,
+
+    /// Gets JSON path of the modified value, e.g. $.a[1]."b c"
+    pub path: String,
+
+    /// Gets the new value. None for Remove operation.
+    pub value: Option<JsonValue>,
+}
+
+impl JsonDiff {
+    /// Applies the modification to the value in place.
+    pub fn apply(&self, target: &mut JsonValue) -> Result<(), Error> {
+        let legs = parse_json_path(&self.path)?;
+        let (last, parents) = match legs.split_last() {
+            Some(x) => x,
+            None => {
+                // Only the whole document can be replaced.
+                return match (self.operation, &self.value) {
+                    (JsonDiffOperation::Replace, Some(value)) => {
+                        *target = value.clone();
+                        Ok(())
+                    }
+                    _ => Err(self.error("cannot be applied to the document root")),
+                };
+            }
+        };
+
+        let mut parent = target;
+        for leg in parents {
+            parent = match get_child(parent, leg) {
+                Some(x) => x,
+                None => return Err(self.error("path is not found")),
+            };
+        }
+
+        match (self.operation, &self.value) {
+            (JsonDiffOperation::Replace, Some(value)) => match get_child(parent, last) {
+                Some(x) => *x = value.clone(),
+                None => return Err(self.error("path is not found")),
+            },
+            (JsonDiffOperation::Insert, Some(value)) => match (parent, last) {
+                (JsonValue::Object(members), JsonPathLeg::Member(key)) => {
+                    match members.iter_mut().find(|(k, _)| k == key) {
+                        Some((_, x)) => *x = value.clone(),
+                        None => {
+                            // Keys are stored sorted by length and then by bytes.
+                            let index = members
+                                .iter()
+                                .position(|(k, _)| (k.len(), k.as_str()) > (key.len(), key))
+                                .unwrap_or(members.len());
+                            members.insert(index, (key.clone(), value.clone()));
+                        }
+                    }
+                }
+                (JsonValue::Array(values), JsonPathLeg::Index(index)) => {
+                    let index = (*index).min(values.len());
+                    values.insert(index, value.clone());
+                }
+                _ => return Err(self.error("path is not found")),
+            },
+            (JsonDiffOperation::Remove, None) => match (parent, last) {
+                (JsonValue::Object(members), JsonPathLeg::Member(key)) => {
+                    match members.iter().position(|(k, _)| k == key) {
+                        Some(index) => {
+                            members.remove(index);
+                        }
+                        None => return Err(self.error("path is not found")),
+                    }
+                }
+                (JsonValue::Array(values), JsonPathLeg::Index(index)) if *index < values.len() => {
+                    values.remove(*index);
+                }
+                _ => return Err(self.error("path is not found")),
+            },
+            _ => return Err(self.error("has unexpected value")),
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::String(format!(
+            "JSON diff {:?} {} {}",
+            self.operation, self.path, message
+        ))
+    }
+}
+
+/// Rebuilds after-image of a JSON column by applying the diffs to its before-image.
+pub fn apply_json_diffs(before: &JsonValue, diffs: &[JsonDiff]) -> Result<JsonValue, Error> {
+    let mut value = before.clone();
+    for diff in diffs {
+        diff.apply(&mut value)?;
+    }
+    Ok(value)
+}
+
+/// Leg of a JSON path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonPathLeg {
+    /// Object member, e.g. .name or ."first name"
+    Member(String),
+
+    /// Array element, e.g. [1]
+    Index(usize),
+}
+
+/// Parses JSON path in the form written by MySQL to the binlog.
+/// Wildcards and ranges are not supported as they never appear in JSON diffs.
+pub fn parse_json_path(path: &str) -> Result<Vec<JsonPathLeg>, Error> {
+    let invalid = || Error::String(format!("Invalid JSON path {}", path));
+
+    let mut chars = path.trim().chars().peekable();
+    if chars.next() != Some('$') {
+        return Err(invalid());
+    }
+
+    let mut legs = Vec::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut key = String::new();
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some('n') => key.push('\n'),
+                                Some('t') => key.push('\t'),
+                                Some('r') => key.push('\r'),
+                                Some(x) => key.push(x),
+                                None => return Err(invalid()),
+                            },
+                            Some(x) => key.push(x),
+                            None => return Err(invalid()),
+                        }
+                    }
+                } else {
+                    while let Some(&x) = chars.peek() {
+                        if x == '.' || x == '[' {
+                            break;
+                        }
+                        key.push(x);
+                        chars.next();
+                    }
+                    if key.is_empty() {
+                        return Err(invalid());
+                    }
+                }
+                legs.push(JsonPathLeg::Member(key));
+            }
+            '[' => {
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(x) => index.push(x),
+                        None => return Err(invalid()),
+                    }
+                }
+                let index = index.trim().parse().map_err(|_| invalid())?;
+                legs.push(JsonPathLeg::Index(index));
+            }
+            x if x.is_whitespace() => {}
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(legs)
+}
+
+fn get_child<'a>(value: &'a mut JsonValue, leg: &JsonPathLeg) -> Option<&'a mut JsonValue> {
+    match (value, leg) {
+        (JsonValue::Object(members), JsonPathLeg::Member(key)) => {
+            members.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+        }
+        (JsonValue::Array(values), JsonPathLeg::Index(index)) => values.get_mut(*index),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_json_diffs, parse_json_path, JsonDiff, JsonDiffOperation, JsonPathLeg};
+    use crate::events::row_events::json_value::JsonValue;
+
+    fn diff(operation: JsonDiffOperation, path: &str, value: Option<JsonValue>) -> JsonDiff {
+        JsonDiff {
+            operation,
+            path: path.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn parse_path() {
+        let legs = parse_json_path("$.a[12].\"b c\"").unwrap();
+        assert_eq!(
+            vec![
+                JsonPathLeg::Member("a".to_string()),
+                JsonPathLeg::Index(12),
+                JsonPathLeg::Member("b c".to_string()),
+            ],
+            legs
+        );
+        assert!(parse_json_path("$").unwrap().is_empty());
+        assert!(parse_json_path("a.b").is_err());
+        assert!(parse_json_path("$[x]").is_err());
+    }
+
+    #[test]
+    fn apply_diffs() {
+        // {"id": 1, "tags": ["a", "c"], "name": "x"}
+        let before = JsonValue::Object(vec![
+            ("id".to_string(), JsonValue::Int(1)),
+            ("name".to_string(), JsonValue::String("x".to_string())),
+            (
+                "tags".to_string(),
+                JsonValue::Array(vec![
+                    JsonValue::String("a".to_string()),
+                    JsonValue::String("c".to_string()),
+                ]),
+            ),
+        ]);
+        let diffs = vec![
+            diff(JsonDiffOperation::Replace, "$.id", Some(JsonValue::Int(2))),
+            diff(
+                JsonDiffOperation::Insert,
+                "$.tags[1]",
+                Some(JsonValue::String("b".to_string())),
+            ),
+            diff(JsonDiffOperation::Remove, "$.name", None),
+            diff(
+                JsonDiffOperation::Insert,
+                "$.ok",
+                Some(JsonValue::Bool(true)),
+            ),
+        ];
+
+        let after = apply_json_diffs(&before, &diffs).unwrap();
+        assert_eq!(
+            "{\"id\": 2, \"ok\": true, \"tags\": [\"a\", \"b\", \"c\"]}",
+            after.to_string()
+        );
+
+        let missing = diff(
+            JsonDiffOperation::Replace,
+            "$.unknown",
+            Some(JsonValue::Null),
+        );
+        assert!(apply_json_diffs(&before, &[missing]).is_err());
+    }
+}
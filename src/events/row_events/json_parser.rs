@@ -0,0 +1,457 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::Cursor;
+
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::row_events::decimal::parse_decimal;
+use crate::events::row_events::json_diff::{JsonDiff, JsonDiffOperation};
+use crate::events::row_events::json_value::JsonValue;
+use crate::events::row_events::mysql_value::{Date, DateTime, Time};
+use crate::extensions::read_len_enc_num;
+
+// Parsing MySQL binary JSON format.
+// See <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/json__binary_8h.html">MySQL docs</a>
+// See <a href="https://github.com/shyiko/mysql-binlog-connector-java">JsonBinary</a>
+
+pub(crate) const SMALL_OBJECT: u8 = 0x00;
+pub(crate) const LARGE_OBJECT: u8 = 0x01;
+pub(crate) const SMALL_ARRAY: u8 = 0x02;
+pub(crate) const LARGE_ARRAY: u8 = 0x03;
+pub(crate) const LITERAL: u8 = 0x04;
+pub(crate) const INT16: u8 = 0x05;
+pub(crate) const UINT16: u8 = 0x06;
+pub(crate) const INT32: u8 = 0x07;
+pub(crate) const UINT32: u8 = 0x08;
+pub(crate) const INT64: u8 = 0x09;
+pub(crate) const UINT64: u8 = 0x0a;
+pub(crate) const DOUBLE: u8 = 0x0b;
+pub(crate) const STRING: u8 = 0x0c;
+pub(crate) const OPAQUE: u8 = 0x0f;
+
+/// MySQL limits nesting depth of JSON documents.
+pub(crate) const MAX_DEPTH: usize = 100;
+
+pub(crate) const LITERAL_NULL: u8 = 0x00;
+pub(crate) const LITERAL_TRUE: u8 = 0x01;
+pub(crate) const LITERAL_FALSE: u8 = 0x02;
+
+/// Parses a JSON document. Empty value is stored for JSON null in some cases.
+pub fn parse_json(slice: &[u8]) -> Result<JsonValue, Error> {
+    if slice.is_empty() {
+        return Ok(JsonValue::Null);
+    }
+    parse_value(slice[0], &slice[1..])
+}
+
+/// Parses list of diffs of a partially updated JSON column.
+/// Each diff is stored as:
+/// [operation] [path-length] [path] [value-length] [value]
+/// Value and its length are omitted for Remove operation.
+pub fn parse_json_diffs(slice: &[u8]) -> Result<Vec<JsonDiff>, Error> {
+    let mut cursor = Cursor::new(slice);
+    let mut diffs = Vec::new();
+    while cursor.position() < slice.len() as u64 {
+        let operation = JsonDiffOperation::from_code(cursor.read_u8()?)?;
+        let path_length = read_len_enc_num(&mut cursor)?;
+        let path = String::from_utf8(read_bytes(&mut cursor, path_length)?.to_vec())?;
+
+        let value = match operation {
+            JsonDiffOperation::Remove => None,
+            _ => {
+                let value_length = read_len_enc_num(&mut cursor)?;
+                Some(parse_json(read_bytes(&mut cursor, value_length)?)?)
+            }
+        };
+        diffs.push(JsonDiff {
+            operation,
+            path,
+            value,
+        });
+    }
+    Ok(diffs)
+}
+
+/// Parses value of the specified type located at the beginning of the slice.
+pub fn parse_value(value_type: u8, slice: &[u8]) -> Result<JsonValue, Error> {
+    parse_nested_value(value_type, slice, 0)
+}
+
+/// Parses value of an object or array at the nesting depth.
+fn parse_nested_value(value_type: u8, slice: &[u8], depth: usize) -> Result<JsonValue, Error> {
+    let mut cursor = Cursor::new(slice);
+    let value = match value_type {
+        SMALL_OBJECT => parse_object(slice, false, depth + 1)?,
+        LARGE_OBJECT => parse_object(slice, true, depth + 1)?,
+        SMALL_ARRAY => parse_array(slice, false, depth + 1)?,
+        LARGE_ARRAY => parse_array(slice, true, depth + 1)?,
+        LITERAL => parse_literal(cursor.read_u8()?)?,
+        INT16 => JsonValue::Int(cursor.read_i16::<LittleEndian>()? as i64),
+        UINT16 => JsonValue::UInt(cursor.read_u16::<LittleEndian>()? as u64),
+        INT32 => JsonValue::Int(cursor.read_i32::<LittleEndian>()? as i64),
+        UINT32 => JsonValue::UInt(cursor.read_u32::<LittleEndian>()? as u64),
+        INT64 => JsonValue::Int(cursor.read_i64::<LittleEndian>()?),
+        UINT64 => JsonValue::UInt(cursor.read_u64::<LittleEndian>()?),
+        DOUBLE => JsonValue::Double(cursor.read_f64::<LittleEndian>()?),
+        STRING => {
+            let length = read_variable_length(&mut cursor)?;
+            JsonValue::String(String::from_utf8(
+                read_bytes(&mut cursor, length)?.to_vec(),
+            )?)
+        }
+        OPAQUE => parse_opaque(&mut cursor)?,
+        _ => {
+            return Err(Error::String(format!(
+                "Unknown JSON value type {}",
+                value_type
+            )))
+        }
+    };
+    Ok(value)
+}
+
+fn parse_literal(literal: u8) -> Result<JsonValue, Error> {
+    match literal {
+        LITERAL_NULL => Ok(JsonValue::Null),
+        LITERAL_TRUE => Ok(JsonValue::Bool(true)),
+        LITERAL_FALSE => Ok(JsonValue::Bool(false)),
+        _ => Err(Error::String(format!("Unknown JSON literal {}", literal))),
+    }
+}
+
+fn read_offset(cursor: &mut Cursor<&[u8]>, large: bool) -> Result<usize, Error> {
+    if large {
+        Ok(cursor.read_u32::<LittleEndian>()? as usize)
+    } else {
+        Ok(cursor.read_u16::<LittleEndian>()? as usize)
+    }
+}
+
+/// Reads element count and size of an object or array.
+/// Returns element count and size of the header with key and value entries.
+fn read_header(
+    cursor: &mut Cursor<&[u8]>,
+    large: bool,
+    key_entries: bool,
+    depth: usize,
+) -> Result<(usize, usize), Error> {
+    if depth > MAX_DEPTH {
+        return Err(Error::String(format!(
+            "JSON document is nested deeper than {} levels",
+            MAX_DEPTH
+        )));
+    }
+
+    let element_count = read_offset(cursor, large)?;
+    let _size = read_offset(cursor, large)?;
+
+    let offset_size = if large { 4 } else { 2 };
+    let mut entry_size = 1 + offset_size;
+    if key_entries {
+        entry_size += offset_size + 2;
+    }
+    let header_size = element_count
+        .saturating_mul(entry_size)
+        .saturating_add(2 * offset_size);
+    if header_size > cursor.get_ref().len() {
+        return Err(Error::String("JSON data is out of range".to_string()));
+    }
+    Ok((element_count, header_size))
+}
+
+/// Object format:
+/// [element-count] [size] [key-entry]* [value-entry]* [key]* [value]*
+fn parse_object(slice: &[u8], large: bool, depth: usize) -> Result<JsonValue, Error> {
+    let mut cursor = Cursor::new(slice);
+    let (element_count, header_size) = read_header(&mut cursor, large, true, depth)?;
+
+    let mut key_entries = Vec::with_capacity(element_count);
+    for _ in 0..element_count {
+        let key_offset = read_offset(&mut cursor, large)?;
+        let key_length = cursor.read_u16::<LittleEndian>()? as usize;
+        key_entries.push((key_offset, key_length));
+    }
+
+    let mut values = Vec::with_capacity(element_count);
+    for _ in 0..element_count {
+        values.push(parse_value_entry(
+            slice,
+            &mut cursor,
+            large,
+            header_size,
+            depth,
+        )?);
+    }
+
+    let mut members = Vec::with_capacity(element_count);
+    for ((key_offset, key_length), value) in key_entries.into_iter().zip(values) {
+        let key = get_slice(slice, key_offset, key_length)?;
+        members.push((String::from_utf8(key.to_vec())?, value));
+    }
+    Ok(JsonValue::Object(members))
+}
+
+/// Array format:
+/// [element-count] [size] [value-entry]* [value]*
+fn parse_array(slice: &[u8], large: bool, depth: usize) -> Result<JsonValue, Error> {
+    let mut cursor = Cursor::new(slice);
+    let (element_count, header_size) = read_header(&mut cursor, large, false, depth)?;
+
+    let mut values = Vec::with_capacity(element_count);
+    for _ in 0..element_count {
+        values.push(parse_value_entry(
+            slice,
+            &mut cursor,
+            large,
+            header_size,
+            depth,
+        )?);
+    }
+    Ok(JsonValue::Array(values))
+}
+
+/// Value entry is either an inlined value or an offset of the value from the start of the object or array.
+/// Values are stored after the header, so an offset can't point back into the same container.
+fn parse_value_entry(
+    slice: &[u8],
+    cursor: &mut Cursor<&[u8]>,
+    large: bool,
+    header_size: usize,
+    depth: usize,
+) -> Result<JsonValue, Error> {
+    let value_type = cursor.read_u8()?;
+    let inlined = match value_type {
+        LITERAL | INT16 | UINT16 => true,
+        INT32 | UINT32 => large,
+        _ => false,
+    };
+
+    let entry_size = if large { 4 } else { 2 };
+    if inlined {
+        let position = cursor.position() as usize;
+        cursor.set_position((position + entry_size) as u64);
+        return parse_value(value_type, get_slice(slice, position, entry_size)?);
+    }
+
+    let offset = read_offset(cursor, large)?;
+    if offset < header_size || offset >= slice.len() {
+        return Err(Error::String(
+            "JSON value offset is out of range".to_string(),
+        ));
+    }
+    parse_nested_value(value_type, &slice[offset..], depth)
+}
+
+fn get_slice(slice: &[u8], offset: usize, length: usize) -> Result<&[u8], Error> {
+    match slice.get(offset..offset.saturating_add(length)) {
+        Some(x) => Ok(x),
+        None => Err(Error::String("JSON data is out of range".to_string())),
+    }
+}
+
+/// Reads bytes checking the length before allocating anything for them.
+fn read_bytes<'a>(cursor: &mut Cursor<&'a [u8]>, length: usize) -> Result<&'a [u8], Error> {
+    let position = cursor.position() as usize;
+    let bytes = get_slice(cursor.get_ref(), position, length)?;
+    cursor.set_position((position + length) as u64);
+    Ok(bytes)
+}
+
+/// Length of strings and opaque values uses 7 bits per byte.
+/// High bit is set if more bytes follow.
+pub fn read_variable_length(cursor: &mut Cursor<&[u8]>) -> Result<usize, Error> {
+    let mut length = 0;
+    for i in 0..5 {
+        let byte = cursor.read_u8()?;
+        length |= ((byte & 0x7F) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(length);
+        }
+    }
+    Err(Error::String("Invalid JSON variable length".to_string()))
+}
+
+/// Opaque format:
+/// [column-type] [data-length] [data]
+fn parse_opaque(cursor: &mut Cursor<&[u8]>) -> Result<JsonValue, Error> {
+    let column_type = cursor.read_u8()?;
+    let length = read_variable_length(cursor)?;
+    let data = read_bytes(cursor, length)?;
+
+    let mut buffer = Cursor::new(data);
+    let value = match ColumnType::from_code(column_type) {
+        Ok(ColumnType::NewDecimal) => {
+            let precision = buffer.read_u8()? as u16;
+            let scale = buffer.read_u8()? as u16;
+            JsonValue::Decimal(parse_decimal(&mut buffer, precision | scale << 8)?)
+        }
+        Ok(ColumnType::Date) => {
+            let x = parse_date_time(buffer.read_i64::<LittleEndian>()?);
+            JsonValue::Date(Date {
+                year: x.year,
+                month: x.month,
+                day: x.day,
+            })
+        }
+        Ok(ColumnType::DateTime) | Ok(ColumnType::TimeStamp) => {
+            JsonValue::DateTime(parse_date_time(buffer.read_i64::<LittleEndian>()?))
+        }
+        Ok(ColumnType::Time) => JsonValue::Time(parse_time(buffer.read_i64::<LittleEndian>()?)),
+        _ => JsonValue::Opaque {
+            column_type,
+            data: data.to_vec(),
+        },
+    };
+    Ok(value)
+}
+
+/// Date and time values are stored in packed format used by MySQL server internally.
+fn parse_date_time(packed: i64) -> DateTime {
+    let packed = packed.abs();
+    let fraction = packed % (1 << 24);
+    let ymdhms = packed >> 24;
+    let ymd = ymdhms >> 17;
+    let ym = ymd >> 5;
+    let hms = ymdhms % (1 << 17);
+
+    DateTime {
+        year: (ym / 13) as u16,
+        month: (ym % 13) as u8,
+        day: (ymd % (1 << 5)) as u8,
+        hour: (hms >> 12) as u8,
+        minute: ((hms >> 6) % (1 << 6)) as u8,
+        second: (hms % (1 << 6)) as u8,
+        millis: (fraction / 1000) as u32,
+    }
+}
+
+fn parse_time(packed: i64) -> Time {
+    let negative = packed < 0;
+    let packed = packed.abs();
+    let fraction = packed % (1 << 24);
+    let hms = packed >> 24;
+
+    let hour = ((hms >> 12) % (1 << 10)) as i16;
+    Time {
+        hour: if negative { -hour } else { hour },
+        minute: ((hms >> 6) % (1 << 6)) as u8,
+        second: (hms % (1 << 6)) as u8,
+        millis: (fraction / 1000) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_json;
+    use crate::events::row_events::json_value::JsonValue;
+
+    #[test]
+    fn parse_literals_and_scalars() {
+        assert_eq!(JsonValue::Null, parse_json(&[]).unwrap());
+        assert_eq!(JsonValue::Bool(true), parse_json(&[0x04, 0x01]).unwrap());
+        assert_eq!(JsonValue::Int(-2), parse_json(&[0x05, 0xFE, 0xFF]).unwrap());
+        assert_eq!(
+            JsonValue::UInt(u64::MAX),
+            parse_json(&[0x0a, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap()
+        );
+        assert_eq!(
+            JsonValue::String("abc".to_string()),
+            parse_json(&[0x0c, 0x03, b'a', b'b', b'c']).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_small_object() {
+        // {"a": 1, "bc": [true, "x"]}
+        let data = [
+            0x00, // small object
+            0x02, 0x00, // element count
+            0x21, 0x00, // size
+            0x12, 0x00, 0x01, 0x00, // key "a"
+            0x13, 0x00, 0x02, 0x00, // key "bc"
+            0x05, 0x01, 0x00, // int16 1 inlined
+            0x02, 0x15, 0x00, // small array at 21
+            b'a', b'b', b'c', // keys
+            0x02, 0x00, // element count
+            0x0c, 0x00, // size
+            0x04, 0x01, 0x00, // true inlined
+            0x0c, 0x0a, 0x00, // string at 10
+            0x01, b'x',
+        ];
+        let value = parse_json(&data).unwrap();
+
+        assert_eq!(Some(&JsonValue::Int(1)), value.get("a"));
+        assert_eq!(r#"{"a": 1, "bc": [true, "x"]}"#, value.to_string());
+    }
+
+    #[test]
+    fn reject_corrupted_documents() {
+        // Array containing itself
+        let data = [0x02, 0x01, 0x00, 0x07, 0x00, 0x02, 0x00, 0x00];
+        assert!(parse_json(&data).is_err());
+
+        // Element count larger than the data
+        assert!(parse_json(&[0x00, 0xFF, 0xFF, 0x00, 0x00]).is_err());
+        assert!(parse_json(&[0x03, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00]).is_err());
+
+        // String length larger than the data
+        assert!(parse_json(&[0x0c, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, b'a']).is_err());
+    }
+
+    #[test]
+    fn limit_nesting_depth() {
+        let nested = |depth: usize| {
+            // Empty array wrapped in arrays of one element
+            let mut data = vec![0x00, 0x00, 0x04, 0x00];
+            for _ in 1..depth {
+                let size = (data.len() + 7) as u16;
+                let mut array = vec![0x01, 0x00];
+                array.extend_from_slice(&size.to_le_bytes());
+                array.extend_from_slice(&[0x02, 0x07, 0x00]);
+                array.extend_from_slice(&data);
+                data = array;
+            }
+            data.insert(0, 0x02);
+            data
+        };
+        assert!(parse_json(&nested(100)).is_ok());
+        assert!(parse_json(&nested(101)).is_err());
+    }
+
+    #[test]
+    fn parse_opaque_values() {
+        // DECIMAL(4,2) -12.34
+        let decimal = [0x0f, 246, 0x04, 0x04, 0x02, 0x73, 0xdd];
+        assert_eq!(
+            JsonValue::Decimal("-12.34".to_string()),
+            parse_json(&decimal).unwrap()
+        );
+        // DECIMAL(2,4) is invalid
+        assert!(parse_json(&[0x0f, 246, 0x04, 0x02, 0x04, 0x73, 0xdd]).is_err());
+
+        // DATETIME 2015-01-15 23:24:25.000000
+        let ymd: i64 = (2015 * 13 + 1) << 5 | 15;
+        let packed = (ymd << 17 | (23 << 12 | 24 << 6 | 25)) << 24;
+        let mut datetime = vec![0x0f, 12, 0x08];
+        datetime.extend_from_slice(&packed.to_le_bytes());
+        assert_eq!(
+            r#""2015-01-15 23:24:25.000000""#,
+            parse_json(&datetime).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn render_escaped_strings_and_opaque() {
+        let value = JsonValue::Array(vec![
+            JsonValue::String("a\"b\n".to_string()),
+            JsonValue::Double(1.0),
+            JsonValue::Opaque {
+                column_type: 252,
+                data: b"hi!".to_vec(),
+            },
+        ]);
+        assert_eq!(
+            r#"["a\"b\n", 1.0, "base64:type252:aGkh"]"#,
+            value.to_string()
+        );
+    }
+}
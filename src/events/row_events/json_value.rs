@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::events::row_events::mysql_value::{Date, DateTime, Time};
+use crate::extensions::encode_base64;
+
+/// Decoded value of a MySQL JSON column.
+/// Object members keep the order in which they are stored in the binary representation.
+/// <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/json__binary_8h.html">See more</a>
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Double(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+
+    /// DECIMAL value stored as an opaque type.
+    Decimal(String),
+
+    /// DATE value stored as an opaque type.
+    Date(Date),
+
+    /// TIME value stored as an opaque type.
+    Time(Time),
+
+    /// DATETIME or TIMESTAMP value stored as an opaque type.
+    DateTime(DateTime),
+
+    /// Any other opaque value, e.g. BLOB or GEOMETRY.
+    Opaque {
+        column_type: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl JsonValue {
+    /// Gets member of the object by key.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Renders the value as JSON text the same way MySQL does.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(x) => write!(f, "{}", x),
+            JsonValue::Int(x) => write!(f, "{}", x),
+            JsonValue::UInt(x) => write!(f, "{}", x),
+            JsonValue::Double(x) => write!(f, "{:?}", x),
+            JsonValue::String(x) => write_escaped(f, x),
+            JsonValue::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            }
+            JsonValue::Decimal(x) => write!(f, "{}", x),
+            JsonValue::Date(x) => write!(f, "\"{:04}-{:02}-{:02}\"", x.year, x.month, x.day),
+            JsonValue::Time(x) => {
+                let sign = if x.hour < 0 { "-" } else { "" };
+                write!(
+                    f,
+                    "\"{}{:02}:{:02}:{:02}.{:06}\"",
+                    sign,
+                    x.hour.abs(),
+                    x.minute,
+                    x.second,
+                    x.millis * 1000
+                )
+            }
+            JsonValue::DateTime(x) => write!(
+                f,
+                "\"{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}\"",
+                x.year,
+                x.month,
+                x.day,
+                x.hour,
+                x.minute,
+                x.second,
+                x.millis * 1000
+            ),
+            JsonValue::Opaque { column_type, data } => {
+                write!(f, "\"base64:type{}:{}\"", column_type, encode_base64(data))
+            }
+        }
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            '\u{08}' => write!(f, "\\b")?,
+            '\u{0C}' => write!(f, "\\f")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
@@ -0,0 +1,334 @@
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::row_events::decimal::serialize_decimal;
+use crate::events::row_events::json_parser::{
+    DOUBLE, INT16, INT32, INT64, LARGE_ARRAY, LARGE_OBJECT, LITERAL, LITERAL_FALSE, LITERAL_NULL,
+    LITERAL_TRUE, MAX_DEPTH, OPAQUE, SMALL_ARRAY, SMALL_OBJECT, STRING, UINT16, UINT32, UINT64,
+};
+use crate::events::row_events::json_value::JsonValue;
+use crate::events::row_events::mysql_value::{DateTime, Time};
+
+// Writing MySQL binary JSON format. See json_parser.
+// See <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/json__binary_8h.html">MySQL docs</a>
+
+/// Serializes a JSON document as [type] [value].
+pub fn serialize_json(value: &JsonValue) -> Result<Vec<u8>, Error> {
+    let (value_type, data) = serialize_value(value, 0)?;
+    let mut vec = Vec::with_capacity(data.len() + 1);
+    vec.push(value_type);
+    vec.extend_from_slice(&data);
+    Ok(vec)
+}
+
+/// Gets type and data of the value. The smallest type that fits is used like MySQL does.
+fn serialize_value(value: &JsonValue, depth: usize) -> Result<(u8, Vec<u8>), Error> {
+    let value = match value {
+        JsonValue::Null => (LITERAL, vec![LITERAL_NULL]),
+        JsonValue::Bool(true) => (LITERAL, vec![LITERAL_TRUE]),
+        JsonValue::Bool(false) => (LITERAL, vec![LITERAL_FALSE]),
+        JsonValue::Int(x) => {
+            if let Ok(x) = i16::try_from(*x) {
+                (INT16, x.to_le_bytes().to_vec())
+            } else if let Ok(x) = i32::try_from(*x) {
+                (INT32, x.to_le_bytes().to_vec())
+            } else {
+                (INT64, x.to_le_bytes().to_vec())
+            }
+        }
+        JsonValue::UInt(x) => {
+            if let Ok(x) = u16::try_from(*x) {
+                (UINT16, x.to_le_bytes().to_vec())
+            } else if let Ok(x) = u32::try_from(*x) {
+                (UINT32, x.to_le_bytes().to_vec())
+            } else {
+                (UINT64, x.to_le_bytes().to_vec())
+            }
+        }
+        JsonValue::Double(x) => (DOUBLE, x.to_le_bytes().to_vec()),
+        JsonValue::String(x) => {
+            let mut vec = Vec::new();
+            write_variable_length(&mut vec, x.len());
+            vec.extend_from_slice(x.as_bytes());
+            (STRING, vec)
+        }
+        JsonValue::Array(x) => {
+            let values: Vec<&JsonValue> = x.iter().collect();
+            serialize_container(None, &values, depth + 1)?
+        }
+        JsonValue::Object(x) => {
+            // MySQL looks up keys by binary search. Keys are sorted by length, then by bytes.
+            let mut members: Vec<&(String, JsonValue)> = x.iter().collect();
+            members.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then(a.0.cmp(&b.0)));
+            let keys: Vec<&str> = members.iter().map(|(key, _)| key.as_str()).collect();
+            let values: Vec<&JsonValue> = members.iter().map(|(_, value)| value).collect();
+            serialize_container(Some(&keys), &values, depth + 1)?
+        }
+        JsonValue::Decimal(x) => {
+            // Precision and scale are taken from the digits of the value.
+            let digits = x.trim_start_matches('-');
+            let (integral, fractional) = digits.split_once('.').unwrap_or((digits, ""));
+            let scale = fractional.len();
+            let precision = (integral.trim_start_matches('0').len() + scale).max(1);
+            if precision > u8::MAX as usize {
+                return Err(Error::String(format!("Invalid JSON decimal {}", x)));
+            }
+            let mut data = vec![precision as u8, scale as u8];
+            data.extend(serialize_decimal(
+                x,
+                precision as u16 | (scale as u16) << 8,
+            )?);
+            serialize_opaque(ColumnType::NewDecimal as u8, &data)
+        }
+        JsonValue::Date(x) => {
+            let packed = pack_date_time(&DateTime {
+                year: x.year,
+                month: x.month,
+                day: x.day,
+                hour: 0,
+                minute: 0,
+                second: 0,
+                millis: 0,
+            });
+            serialize_opaque(ColumnType::Date as u8, &packed.to_le_bytes())
+        }
+        JsonValue::Time(x) => serialize_opaque(ColumnType::Time as u8, &pack_time(x).to_le_bytes()),
+        JsonValue::DateTime(x) => {
+            serialize_opaque(ColumnType::DateTime as u8, &pack_date_time(x).to_le_bytes())
+        }
+        JsonValue::Opaque { column_type, data } => serialize_opaque(*column_type, data),
+    };
+    Ok(value)
+}
+
+/// Serializes object members or array elements when keys are not specified.
+/// Small format is used unless the container is larger than 64KB.
+fn serialize_container(
+    keys: Option<&[&str]>,
+    values: &[&JsonValue],
+    depth: usize,
+) -> Result<(u8, Vec<u8>), Error> {
+    if depth > MAX_DEPTH {
+        return Err(Error::String(format!(
+            "JSON document is nested deeper than {} levels",
+            MAX_DEPTH
+        )));
+    }
+    if keys.is_some_and(|x| x.iter().any(|key| key.len() > u16::MAX as usize)) {
+        return Err(Error::String("JSON object key is too long".to_string()));
+    }
+
+    let values = values
+        .iter()
+        .map(|x| serialize_value(x, depth))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let (small_type, large_type) = match keys {
+        Some(_) => (SMALL_OBJECT, LARGE_OBJECT),
+        None => (SMALL_ARRAY, LARGE_ARRAY),
+    };
+    if let Some(data) = write_container(keys, &values, false) {
+        return Ok((small_type, data));
+    }
+    match write_container(keys, &values, true) {
+        Some(data) => Ok((large_type, data)),
+        None => Err(Error::String("JSON document is too large".to_string())),
+    }
+}
+
+/// Object format:
+/// [element-count] [size] [key-entry]* [value-entry]* [key]* [value]*
+/// Array format:
+/// [element-count] [size] [value-entry]* [value]*
+/// Returns None if offsets don't fit the format.
+fn write_container(
+    keys: Option<&[&str]>,
+    values: &[(u8, Vec<u8>)],
+    large: bool,
+) -> Option<Vec<u8>> {
+    let offset_size = if large { 4 } else { 2 };
+    let max_offset = if large {
+        u32::MAX as usize
+    } else {
+        u16::MAX as usize
+    };
+
+    let mut header_size = 2 * offset_size + values.len() * (1 + offset_size);
+    if keys.is_some() {
+        header_size += values.len() * (offset_size + 2);
+    }
+
+    let mut header = Vec::with_capacity(header_size);
+    let mut data = Vec::new();
+    write_offset(&mut header, values.len(), large);
+    // Size is known when the data is written.
+    write_offset(&mut header, 0, large);
+    for key in keys.unwrap_or_default() {
+        write_offset(&mut header, header_size + data.len(), large);
+        header.extend_from_slice(&(key.len() as u16).to_le_bytes());
+        data.extend_from_slice(key.as_bytes());
+    }
+    for (value_type, value) in values {
+        header.push(*value_type);
+        let inlined = match *value_type {
+            LITERAL | INT16 | UINT16 => true,
+            INT32 | UINT32 => large,
+            _ => false,
+        };
+        if inlined {
+            let mut entry = value.clone();
+            entry.resize(offset_size, 0);
+            header.extend_from_slice(&entry);
+        } else {
+            write_offset(&mut header, header_size + data.len(), large);
+            data.extend_from_slice(value);
+        }
+    }
+
+    let size = header_size + data.len();
+    if values.len() > max_offset || size > max_offset {
+        return None;
+    }
+    let mut size_field = Vec::with_capacity(offset_size);
+    write_offset(&mut size_field, size, large);
+    header[offset_size..2 * offset_size].copy_from_slice(&size_field);
+    header.extend_from_slice(&data);
+    Some(header)
+}
+
+fn write_offset(vec: &mut Vec<u8>, offset: usize, large: bool) {
+    if large {
+        vec.extend_from_slice(&(offset as u32).to_le_bytes());
+    } else {
+        vec.extend_from_slice(&(offset as u16).to_le_bytes());
+    }
+}
+
+/// Length of strings and opaque values uses 7 bits per byte.
+/// High bit is set if more bytes follow.
+fn write_variable_length(vec: &mut Vec<u8>, mut length: usize) {
+    loop {
+        let byte = (length & 0x7F) as u8;
+        length >>= 7;
+        if length == 0 {
+            vec.push(byte);
+            return;
+        }
+        vec.push(byte | 0x80);
+    }
+}
+
+/// Opaque format:
+/// [column-type] [data-length] [data]
+fn serialize_opaque(column_type: u8, data: &[u8]) -> (u8, Vec<u8>) {
+    let mut vec = vec![column_type];
+    write_variable_length(&mut vec, data.len());
+    vec.extend_from_slice(data);
+    (OPAQUE, vec)
+}
+
+/// Date and time values are stored in packed format used by MySQL server internally.
+fn pack_date_time(value: &DateTime) -> i64 {
+    let ym = value.year as i64 * 13 + value.month as i64;
+    let ymd = ym << 5 | value.day as i64;
+    let hms = (value.hour as i64) << 12 | (value.minute as i64) << 6 | value.second as i64;
+    ((ymd << 17 | hms) << 24) + value.millis as i64 * 1000
+}
+
+fn pack_time(value: &Time) -> i64 {
+    let hour = value.hour.unsigned_abs() as i64;
+    let hms = hour << 12 | (value.minute as i64) << 6 | value.second as i64;
+    let packed = (hms << 24) + value.millis as i64 * 1000;
+    if value.hour < 0 {
+        -packed
+    } else {
+        packed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::serialize_json;
+    use crate::events::row_events::json_parser::parse_json;
+    use crate::events::row_events::json_value::JsonValue;
+    use crate::events::row_events::mysql_value::{Date, DateTime, Time};
+
+    #[test]
+    fn serialize_small_object() {
+        // Document of json_parser tests with keys in reverse order
+        let value = JsonValue::Object(vec![
+            (
+                "bc".to_string(),
+                JsonValue::Array(vec![
+                    JsonValue::Bool(true),
+                    JsonValue::String("x".to_string()),
+                ]),
+            ),
+            ("a".to_string(), JsonValue::Int(1)),
+        ]);
+        let expected = [
+            0x00, 0x02, 0x00, 0x21, 0x00, 0x12, 0x00, 0x01, 0x00, 0x13, 0x00, 0x02, 0x00, 0x05,
+            0x01, 0x00, 0x02, 0x15, 0x00, b'a', b'b', b'c', 0x02, 0x00, 0x0c, 0x00, 0x04, 0x01,
+            0x00, 0x0c, 0x0a, 0x00, 0x01, b'x',
+        ];
+        assert_eq!(expected.to_vec(), serialize_json(&value).unwrap());
+    }
+
+    #[test]
+    fn serialize_values() {
+        let values = vec![
+            JsonValue::Null,
+            JsonValue::Int(-2),
+            JsonValue::Int(i64::MIN),
+            JsonValue::UInt(70000),
+            JsonValue::Double(1.5),
+            JsonValue::Decimal("-12.340".to_string()),
+            JsonValue::Decimal("0.5".to_string()),
+            JsonValue::Date(Date {
+                year: 2023,
+                month: 7,
+                day: 15,
+            }),
+            JsonValue::Time(Time {
+                hour: -838,
+                minute: 59,
+                second: 58,
+                millis: 500,
+            }),
+            JsonValue::DateTime(DateTime {
+                year: 2023,
+                month: 7,
+                day: 15,
+                hour: 10,
+                minute: 20,
+                second: 30,
+                millis: 123,
+            }),
+            JsonValue::Opaque {
+                column_type: 252,
+                data: vec![0xFF, 0x00],
+            },
+            // Inlined in large format only
+            JsonValue::Int(100000),
+            // Forces large format
+            JsonValue::String("a".repeat(70000)),
+        ];
+        for value in values.iter() {
+            assert_eq!(*value, parse_json(&serialize_json(value).unwrap()).unwrap());
+        }
+
+        let array = JsonValue::Array(values);
+        let bytes = serialize_json(&array).unwrap();
+        assert_eq!(0x03, bytes[0]);
+        assert_eq!(array, parse_json(&bytes).unwrap());
+    }
+
+    #[test]
+    fn limit_nesting_depth() {
+        let mut value = JsonValue::Array(Vec::new());
+        for _ in 1..100 {
+            value = JsonValue::Array(vec![value]);
+        }
+        assert!(serialize_json(&value).is_ok());
+        assert!(serialize_json(&JsonValue::Array(vec![value])).is_err());
+    }
+}
@@ -1,10 +1,21 @@
 pub mod delete_rows_event;
+pub mod geometry;
+pub mod json_diff;
+pub mod json_value;
 pub mod mysql_value;
+pub mod partial_update_rows_event;
 pub mod row_data;
+pub mod row_decoder;
+pub mod skipped_rows_event;
+pub mod typed_row;
 pub mod update_rows_event;
 pub mod write_rows_event;
 
-mod actual_string_type;
+pub(crate) mod actual_string_type;
 mod col_parser;
+mod col_writer;
 mod decimal;
+mod json_parser;
+mod json_writer;
 mod row_parser;
+mod row_writer;
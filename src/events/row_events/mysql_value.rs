@@ -1,11 +1,17 @@
-#[derive(Debug)]
+use crate::events::row_events::geometry::Geometry;
+use crate::events::row_events::json_diff::JsonDiff;
+use crate::events::row_events::json_value::JsonValue;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Date {
     pub year: u16,
     pub month: u8,
     pub day: u8,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
     pub hour: i16, // Signed value from -838 to 838
     pub minute: u8,
@@ -13,7 +19,8 @@ pub struct Time {
     pub millis: u32,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateTime {
     pub year: u16,
     pub month: u8,
@@ -24,7 +31,9 @@ pub struct DateTime {
     pub millis: u32,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum MySqlValue {
     TinyInt(u8),
     SmallInt(u16),
@@ -44,4 +53,7 @@ pub enum MySqlValue {
     Time(Time),
     DateTime(DateTime),
     Timestamp(u64), // millis from unix time
+    Json(JsonValue),
+    JsonDiff(Vec<JsonDiff>), // partial update of JSON column
+    Geometry(Geometry),
 }
@@ -0,0 +1,127 @@
+use crate::errors::Error;
+use crate::events::row_events::row_data::UpdateRowData;
+use crate::events::row_events::row_parser::{parse_head, parse_partial_update_row_data_list};
+use crate::events::table_map_event::TableMapEvent;
+use crate::extensions::read_bitmap_little_endian;
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// Represents one or many updated rows logged with binlog_row_value_options=PARTIAL_JSON.
+/// JSON columns of the after image can be logged as MySqlValue::JsonDiff,
+/// see apply_json_diffs to rebuild the full value from the before image.
+/// <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/classbinary__log_1_1Rows__event.html">See more</a>
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartialUpdateRowsEvent {
+    /// Gets id of the table where rows were updated
+    pub table_id: u64,
+
+    /// Gets <a href="https://mariadb.com/kb/en/rows_event_v1/#flags">flags</a>
+    pub flags: u16,
+
+    /// Gets number of columns in the table
+    pub columns_number: usize,
+
+    /// Gets bitmap of columns present in row event before update. See binlog_row_image parameter.
+    pub columns_before_update: Vec<bool>,
+
+    /// Gets bitmap of columns present in row event after update. See binlog_row_image parameter.
+    pub columns_after_update: Vec<bool>,
+
+    /// Gets updated rows
+    pub rows: Vec<UpdateRowData>,
+}
+
+impl PartialUpdateRowsEvent {
+    /// Supports MySQL 8.0.3+ (V2 row events only).
+    pub fn parse(
+        cursor: &mut Cursor<&[u8]>,
+        table_map: &HashMap<u64, TableMapEvent>,
+    ) -> Result<Self, Error> {
+        let (table_id, flags, columns_number) = parse_head(cursor, 2)?;
+        let columns_before_update = read_bitmap_little_endian(cursor, columns_number)?;
+        let columns_after_update = read_bitmap_little_endian(cursor, columns_number)?;
+        let rows = parse_partial_update_row_data_list(
+            cursor,
+            table_map,
+            table_id,
+            &columns_before_update,
+            &columns_after_update,
+        )?;
+        Ok(Self {
+            table_id,
+            flags,
+            columns_number,
+            columns_before_update,
+            columns_after_update,
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use super::PartialUpdateRowsEvent;
+    use crate::constants::column_type::ColumnType;
+    use crate::events::row_events::json_diff::{apply_json_diffs, JsonDiffOperation};
+    use crate::events::row_events::json_value::JsonValue;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::events::table_map_event::TableMapEvent;
+
+    #[test]
+    fn parse_json_diffs() {
+        let table = TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![ColumnType::Long as u8, ColumnType::Json as u8],
+            column_metadata: vec![0, 4],
+            null_bitmap: vec![false, true],
+            table_metadata: None,
+        };
+        let table_map = HashMap::from([(1, table)]);
+
+        let mut payload = vec![
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // table id
+            0x01, 0x00, // flags
+            0x02, 0x00, // extra data length
+            0x02, // columns number
+            0x03, 0x03, // columns before and after update
+        ];
+        // Before image: 7, {"a": 1}
+        payload.extend_from_slice(&[0x00, 0x07, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00]);
+        payload.extend_from_slice(&[
+            0x00, 0x01, 0x00, 0x0c, 0x00, 0x0b, 0x00, 0x01, 0x00, 0x05, 0x01, 0x00, b'a',
+        ]);
+        // After image: value options, partial bits, 7, [REPLACE $.a 2]
+        payload.extend_from_slice(&[
+            0x01, 0x01, 0x00, 0x07, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00,
+        ]);
+        payload.extend_from_slice(&[0x00, 0x03, b'$', b'.', b'a', 0x03, 0x05, 0x02, 0x00]);
+
+        let mut cursor = Cursor::new(payload.as_slice());
+        let event = PartialUpdateRowsEvent::parse(&mut cursor, &table_map).unwrap();
+        assert_eq!(1, event.rows.len());
+
+        let row = &event.rows[0];
+        assert_eq!(Some(MySqlValue::Int(7)), row.after_update.cells[0]);
+        let before = match &row.before_update.cells[1] {
+            Some(MySqlValue::Json(x)) => x,
+            x => panic!("Unexpected before image {:?}", x),
+        };
+        let diffs = match &row.after_update.cells[1] {
+            Some(MySqlValue::JsonDiff(x)) => x,
+            x => panic!("Unexpected after image {:?}", x),
+        };
+        assert_eq!(JsonDiffOperation::Replace, diffs[0].operation);
+        assert_eq!("$.a", diffs[0].path);
+        assert_eq!(Some(JsonValue::Int(2)), diffs[0].value);
+        assert_eq!(
+            r#"{"a": 2}"#,
+            apply_json_diffs(before, diffs).unwrap().to_string()
+        );
+    }
+}
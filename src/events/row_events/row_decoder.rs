@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::row_events::json_diff::apply_json_diffs;
+use crate::events::row_events::mysql_value::MySqlValue;
+use crate::events::row_events::row_data::{RowData, UpdateRowData};
+use crate::events::row_events::row_parser::TABLE_MAP_NOT_FOUND;
+use crate::events::row_events::typed_row::TypedRow;
+use crate::events::table_map_event::TableMapEvent;
+
+/// Row change decoded from a row event.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RowChange {
+    Insert(TypedRow),
+    Update { before: TypedRow, after: TypedRow },
+    Delete(TypedRow),
+}
+
+/// Decodes rows of row events into rows keyed by column names.
+/// Caches TableMapEvent events, so all events of the stream must be passed in order.
+pub struct RowDecoder {
+    table_map: HashMap<u64, TableMapEvent>,
+}
+
+impl RowDecoder {
+    pub fn new() -> Self {
+        Self {
+            table_map: HashMap::new(),
+        }
+    }
+
+    /// Gets cached TableMapEvent of the table.
+    pub fn table(&self, table_id: u64) -> Option<&TableMapEvent> {
+        self.table_map.get(&table_id)
+    }
+
+    /// Returns changed rows of a row event. Returns empty list for other events.
+    pub fn decode(&mut self, event: &BinlogEvent) -> Result<Vec<RowChange>, Error> {
+        let changes = match event {
+            BinlogEvent::TableMapEvent(x) => {
+                self.table_map.insert(x.table_id, x.clone());
+                Vec::new()
+            }
+            BinlogEvent::WriteRowsEvent(x) => {
+                let table = self.get_table(x.table_id)?;
+                let mut changes = Vec::with_capacity(x.rows.len());
+                for row in &x.rows {
+                    changes.push(RowChange::Insert(TypedRow::decode(table, row)?));
+                }
+                changes
+            }
+            BinlogEvent::UpdateRowsEvent(x) => {
+                let table = self.get_table(x.table_id)?;
+                let mut changes = Vec::with_capacity(x.rows.len());
+                for row in &x.rows {
+                    changes.push(RowChange::Update {
+                        before: TypedRow::decode(table, &row.before_update)?,
+                        after: TypedRow::decode(table, &row.after_update)?,
+                    });
+                }
+                changes
+            }
+            BinlogEvent::MySqlPartialUpdateRowsEvent(x) => {
+                let table = self.get_table(x.table_id)?;
+                let mut changes = Vec::with_capacity(x.rows.len());
+                for row in &x.rows {
+                    changes.push(RowChange::Update {
+                        before: TypedRow::decode(table, &row.before_update)?,
+                        after: TypedRow::decode(table, &rebuild_after_update(row)?)?,
+                    });
+                }
+                changes
+            }
+            BinlogEvent::DeleteRowsEvent(x) => {
+                let table = self.get_table(x.table_id)?;
+                let mut changes = Vec::with_capacity(x.rows.len());
+                for row in &x.rows {
+                    changes.push(RowChange::Delete(TypedRow::decode(table, row)?));
+                }
+                changes
+            }
+            _ => Vec::new(),
+        };
+        Ok(changes)
+    }
+
+    fn get_table(&self, table_id: u64) -> Result<&TableMapEvent, Error> {
+        match self.table_map.get(&table_id) {
+            Some(x) => Ok(x),
+            None => Err(Error::String(TABLE_MAP_NOT_FOUND.to_string())),
+        }
+    }
+}
+
+/// Applies JSON diffs to the before image of the columns.
+/// Diffs are kept as is if the before image of the column is not logged.
+fn rebuild_after_update(row: &UpdateRowData) -> Result<RowData, Error> {
+    let mut cells = Vec::with_capacity(row.after_update.cells.len());
+    for (i, cell) in row.after_update.cells.iter().enumerate() {
+        let before = row.before_update.cells.get(i).and_then(|x| x.as_ref());
+        let cell = match (cell, before) {
+            (Some(MySqlValue::JsonDiff(diffs)), Some(MySqlValue::Json(value))) => {
+                Some(MySqlValue::Json(apply_json_diffs(value, diffs)?))
+            }
+            _ => cell.clone(),
+        };
+        cells.push(cell);
+    }
+    Ok(RowData::new(cells))
+}
+
+impl Default for RowDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
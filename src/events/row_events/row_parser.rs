@@ -1,9 +1,11 @@
+use crate::charset::decode_string;
 use crate::constants::column_type::ColumnType;
 use crate::errors::Error;
 use crate::events::row_events::col_parser::{
     parse_bit, parse_blob, parse_date, parse_date_time, parse_date_time2, parse_string, parse_time,
     parse_time2, parse_timestamp, parse_timestamp2, parse_year,
 };
+use crate::events::row_events::geometry::Geometry;
 use crate::events::row_events::mysql_value::MySqlValue;
 use crate::events::row_events::row_data::{RowData, UpdateRowData};
 use crate::events::table_map_event::TableMapEvent;
@@ -14,6 +16,10 @@ use std::io::{Cursor, Seek, SeekFrom};
 
 use super::actual_string_type::get_actual_string_type;
 use super::decimal::parse_decimal;
+use super::json_parser::{parse_json, parse_json_diffs};
+
+/// Bit of value options signaling that JSON columns can be logged as diffs.
+const PARTIAL_JSON_UPDATES: usize = 1;
 
 pub const TABLE_MAP_NOT_FOUND: &str =
     "No preceding TableMapEvent event was found for the row event. \
@@ -76,6 +82,57 @@ pub fn parse_update_row_data_list(
     Ok(rows)
 }
 
+/// After image of PARTIAL_UPDATE_ROWS_EVENT starts with value options.
+/// If PARTIAL_JSON_UPDATES is set, they are followed by a bitmap with a bit per JSON column
+/// of the table that tells whether the column is logged as a list of diffs.
+pub fn parse_partial_update_row_data_list(
+    cursor: &mut Cursor<&[u8]>,
+    table_map: &HashMap<u64, TableMapEvent>,
+    table_id: u64,
+    columns_before_update: &Vec<bool>,
+    columns_after_update: &Vec<bool>,
+) -> Result<Vec<UpdateRowData>, Error> {
+    let table = match table_map.get(&table_id) {
+        Some(x) => x,
+        None => return Err(Error::String(TABLE_MAP_NOT_FOUND.to_string())),
+    };
+
+    let json_columns: Vec<usize> = (0..table.column_types.len())
+        .filter(|&i| table.column_types[i] == ColumnType::Json as u8)
+        .collect();
+
+    let cells_included_before_update = get_bits_number(columns_before_update);
+    let cells_included_after_update = get_bits_number(columns_after_update);
+    let mut rows = Vec::new();
+    while cursor.position() < cursor.get_ref().len() as u64 {
+        let row_before_update = parse_row(
+            cursor,
+            table,
+            columns_before_update,
+            cells_included_before_update,
+        )?;
+
+        let mut partial_columns = vec![false; table.column_types.len()];
+        let value_options = read_len_enc_num(cursor)?;
+        if value_options & PARTIAL_JSON_UPDATES != 0 {
+            let partial_bits = read_bitmap_little_endian(cursor, json_columns.len())?;
+            for (i, column) in json_columns.iter().enumerate() {
+                partial_columns[*column] = partial_bits[i];
+            }
+        }
+
+        let row_after_update = parse_row_image(
+            cursor,
+            table,
+            columns_after_update,
+            cells_included_after_update,
+            &partial_columns,
+        )?;
+        rows.push(UpdateRowData::new(row_before_update, row_after_update));
+    }
+    Ok(rows)
+}
+
 pub fn parse_head(
     cursor: &mut Cursor<&[u8]>,
     row_event_version: u8,
@@ -99,9 +156,27 @@ pub fn parse_row(
     table_map: &TableMapEvent,
     columns_present: &Vec<bool>,
     cells_included: usize,
+) -> Result<RowData, Error> {
+    let partial_columns = vec![false; table_map.column_types.len()];
+    parse_row_image(
+        cursor,
+        table_map,
+        columns_present,
+        cells_included,
+        &partial_columns,
+    )
+}
+
+fn parse_row_image(
+    cursor: &mut Cursor<&[u8]>,
+    table_map: &TableMapEvent,
+    columns_present: &Vec<bool>,
+    cells_included: usize,
+    partial_columns: &[bool],
 ) -> Result<RowData, Error> {
     let mut row = Vec::with_capacity(table_map.column_types.len());
     let null_bitmap = read_bitmap_little_endian(cursor, cells_included)?;
+    let collations = get_collations(table_map);
 
     let mut skipped_columns = 0;
     for i in 0..table_map.column_types.len() {
@@ -114,6 +189,12 @@ pub fn parse_row(
         else if null_bitmap[i - skipped_columns] {
             row.push(None);
         }
+        // JSON column is logged as a list of diffs
+        else if partial_columns[i] {
+            let metadata = table_map.column_metadata[i];
+            let diffs = parse_json_diffs(&parse_blob(cursor, metadata)?)?;
+            row.push(Some(MySqlValue::JsonDiff(diffs)));
+        }
         // Column has data
         else {
             let mut column_type = table_map.column_types[i];
@@ -121,16 +202,67 @@ pub fn parse_row(
             if ColumnType::from_code(column_type)? == ColumnType::String {
                 get_actual_string_type(&mut column_type, &mut metadata);
             }
-            row.push(Some(parse_cell(cursor, column_type, metadata)?));
+            row.push(Some(parse_cell(
+                cursor,
+                column_type,
+                metadata,
+                collations[i],
+            )?));
         }
     }
     Ok(RowData::new(row))
 }
 
+/// Gets collations of character columns. Collations are logged with binlog_row_metadata=FULL.
+pub fn get_collations(table_map: &TableMapEvent) -> Vec<Option<u32>> {
+    let table_metadata = table_map.table_metadata.as_ref();
+    let default_charset = table_metadata.and_then(|x| x.default_charset.as_ref());
+    let column_charsets = table_metadata.and_then(|x| x.column_charsets.as_ref());
+
+    let mut collations = Vec::with_capacity(table_map.column_types.len());
+    let mut char_index = 0;
+    for i in 0..table_map.column_types.len() {
+        let mut column_type = table_map.column_types[i];
+        let mut metadata = table_map.column_metadata[i];
+        if column_type == ColumnType::String as u8 {
+            get_actual_string_type(&mut column_type, &mut metadata);
+        }
+
+        // ENUM and SET columns have separate charset metadata.
+        let collation = match ColumnType::from_code(column_type) {
+            Ok(ColumnType::String)
+            | Ok(ColumnType::VarChar)
+            | Ok(ColumnType::VarString)
+            | Ok(ColumnType::TinyBlob)
+            | Ok(ColumnType::MediumBlob)
+            | Ok(ColumnType::LongBlob)
+            | Ok(ColumnType::Blob) => {
+                let collation = match (column_charsets, default_charset) {
+                    (Some(x), _) => x.get(char_index).cloned(),
+                    (None, Some(x)) => Some(
+                        x.charset_collations
+                            .iter()
+                            .find(|(index, _)| *index as usize == char_index)
+                            .map(|(_, collation)| *collation)
+                            .unwrap_or(x.default_charset_collation),
+                    ),
+                    (None, None) => None,
+                };
+                char_index += 1;
+                collation
+            }
+            _ => None,
+        };
+        collations.push(collation);
+    }
+    collations
+}
+
 fn parse_cell(
     cursor: &mut Cursor<&[u8]>,
     column_type: u8,
     metadata: u16,
+    collation: Option<u32>,
 ) -> Result<MySqlValue, Error> {
     let value = match ColumnType::from_code(column_type)? {
         /* Numeric types. The only place where numbers can be negative */
@@ -143,9 +275,13 @@ fn parse_cell(
         ColumnType::Double => MySqlValue::Double(cursor.read_f64::<LittleEndian>()?),
         ColumnType::NewDecimal => MySqlValue::Decimal(parse_decimal(cursor, metadata)?),
         /* String types, includes varchar, varbinary & fixed char, binary */
-        ColumnType::String => MySqlValue::String(parse_string(cursor, metadata)?),
-        ColumnType::VarChar => MySqlValue::String(parse_string(cursor, metadata)?),
-        ColumnType::VarString => MySqlValue::String(parse_string(cursor, metadata)?),
+        ColumnType::String | ColumnType::VarChar | ColumnType::VarString => {
+            // Binary strings and malformed data are returned as bytes.
+            match decode_string(parse_string(cursor, metadata)?, collation) {
+                Ok(x) => MySqlValue::String(x),
+                Err(x) => MySqlValue::Blob(x),
+            }
+        }
         /* BIT, ENUM, SET types */
         ColumnType::Bit => MySqlValue::Bit(parse_bit(cursor, metadata)?),
         ColumnType::Enum => {
@@ -155,10 +291,17 @@ fn parse_cell(
             MySqlValue::Set(cursor.read_uint::<LittleEndian>(metadata as usize)? as u64)
         }
         /* Blob types. MariaDB always creates BLOB for first three */
-        ColumnType::TinyBlob => MySqlValue::Blob(parse_blob(cursor, metadata)?),
-        ColumnType::MediumBlob => MySqlValue::Blob(parse_blob(cursor, metadata)?),
-        ColumnType::LongBlob => MySqlValue::Blob(parse_blob(cursor, metadata)?),
-        ColumnType::Blob => MySqlValue::Blob(parse_blob(cursor, metadata)?),
+        ColumnType::TinyBlob | ColumnType::MediumBlob | ColumnType::LongBlob | ColumnType::Blob => {
+            let bytes = parse_blob(cursor, metadata)?;
+            // TEXT columns are logged as BLOB with a non-binary collation.
+            match collation {
+                Some(_) => match decode_string(bytes, collation) {
+                    Ok(x) => MySqlValue::String(x),
+                    Err(x) => MySqlValue::Blob(x),
+                },
+                None => MySqlValue::Blob(bytes),
+            }
+        }
         /* Date and time types */
         ColumnType::Year => MySqlValue::Year(parse_year(cursor, metadata)?),
         ColumnType::Date => MySqlValue::Date(parse_date(cursor, metadata)?),
@@ -171,8 +314,10 @@ fn parse_cell(
         ColumnType::TimeStamp2 => MySqlValue::Timestamp(parse_timestamp2(cursor, metadata)?),
         ColumnType::DateTime2 => MySqlValue::DateTime(parse_date_time2(cursor, metadata)?),
         /* MySQL-specific data types */
-        ColumnType::Geometry => MySqlValue::Blob(parse_blob(cursor, metadata)?),
-        ColumnType::Json => MySqlValue::Blob(parse_blob(cursor, metadata)?),
+        ColumnType::Geometry => {
+            MySqlValue::Geometry(Geometry::parse(&parse_blob(cursor, metadata)?)?)
+        }
+        ColumnType::Json => MySqlValue::Json(parse_json(&parse_blob(cursor, metadata)?)?),
         _ => {
             return Err(Error::String(format!(
                 "Parsing column type {:?} is not supported",
@@ -187,3 +332,56 @@ fn parse_cell(
 fn get_bits_number(bitmap: &Vec<bool>) -> usize {
     bitmap.iter().filter(|&x| *x == true).count()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::parse_row;
+    use crate::constants::column_type::ColumnType;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::metadata::table_metadata::TableMetadata;
+
+    fn create_table(column_charsets: Option<Vec<u32>>) -> TableMapEvent {
+        TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("notes"),
+            column_types: vec![ColumnType::Blob as u8, ColumnType::Blob as u8],
+            column_metadata: vec![2, 2],
+            null_bitmap: vec![true, true],
+            table_metadata: Some(TableMetadata {
+                column_charsets,
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn parse_text_columns() {
+        // latin1 TEXT 'café' and BLOB x'ff00'
+        let payload: &[u8] = &[0, 4, 0, 0x63, 0x61, 0x66, 0xE9, 2, 0, 0xFF, 0x00];
+
+        // latin1_swedish_ci and binary collations
+        let table = create_table(Some(vec![8, 63]));
+        let mut cursor = Cursor::new(payload);
+        let row = parse_row(&mut cursor, &table, &vec![true, true], 2).unwrap();
+        assert_eq!(
+            vec![
+                Some(MySqlValue::String(String::from("café"))),
+                Some(MySqlValue::Blob(vec![0xFF, 0x00])),
+            ],
+            row.cells
+        );
+
+        // Without collations TEXT and BLOB columns cannot be told apart.
+        let table = create_table(None);
+        let mut cursor = Cursor::new(payload);
+        let row = parse_row(&mut cursor, &table, &vec![true, true], 2).unwrap();
+        assert_eq!(
+            Some(MySqlValue::Blob(vec![0x63, 0x61, 0x66, 0xE9])),
+            row.cells[0]
+        );
+    }
+}
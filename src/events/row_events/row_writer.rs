@@ -0,0 +1,413 @@
+use crate::charset::encode_string;
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::row_events::col_writer::{
+    write_bit, write_blob, write_date, write_date_time, write_date_time2, write_string, write_time,
+    write_time2, write_timestamp, write_timestamp2, write_uint, write_year,
+};
+use crate::events::row_events::mysql_value::MySqlValue;
+use crate::events::row_events::row_data::{RowData, UpdateRowData};
+use crate::events::table_map_event::TableMapEvent;
+use crate::extensions::{write_bitmap_little_endian, write_len_enc_num};
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+
+use super::actual_string_type::get_actual_string_type;
+use super::decimal::serialize_decimal;
+use super::json_writer::serialize_json;
+use super::row_parser::{get_collations, TABLE_MAP_NOT_FOUND};
+
+// Writing row based events in version 2 format. See row_parser.
+
+pub fn write_row_data_list(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    table_map: &HashMap<u64, TableMapEvent>,
+    table_id: u64,
+    columns_present: &[bool],
+    rows: &[RowData],
+) -> Result<(), Error> {
+    let table = match table_map.get(&table_id) {
+        Some(x) => x,
+        None => return Err(Error::String(TABLE_MAP_NOT_FOUND.to_string())),
+    };
+
+    for row in rows {
+        write_row(cursor, table, columns_present, row)?;
+    }
+    Ok(())
+}
+
+pub fn write_update_row_data_list(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    table_map: &HashMap<u64, TableMapEvent>,
+    table_id: u64,
+    columns_before_update: &[bool],
+    columns_after_update: &[bool],
+    rows: &[UpdateRowData],
+) -> Result<(), Error> {
+    let table = match table_map.get(&table_id) {
+        Some(x) => x,
+        None => return Err(Error::String(TABLE_MAP_NOT_FOUND.to_string())),
+    };
+
+    for row in rows {
+        write_row(cursor, table, columns_before_update, &row.before_update)?;
+        write_row(cursor, table, columns_after_update, &row.after_update)?;
+    }
+    Ok(())
+}
+
+/// Writes table id, flags and number of columns.
+/// Extra data of version 2 events is left empty.
+pub fn write_head(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    table_id: u64,
+    flags: u16,
+    columns_number: usize,
+) -> Result<(), Error> {
+    cursor.write_u48::<LittleEndian>(table_id)?;
+    cursor.write_u16::<LittleEndian>(flags)?;
+    cursor.write_u16::<LittleEndian>(2)?;
+    write_len_enc_num(cursor, columns_number)?;
+    Ok(())
+}
+
+/// Writes null bitmap and values of the present columns.
+pub fn write_row(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    table_map: &TableMapEvent,
+    columns_present: &[bool],
+    row: &RowData,
+) -> Result<(), Error> {
+    let columns_number = table_map.column_types.len();
+    if columns_present.len() != columns_number || row.cells.len() != columns_number {
+        return Err(Error::String(format!(
+            "Row doesn't match {} columns of table {}",
+            columns_number, table_map.table_name
+        )));
+    }
+
+    let null_bitmap: Vec<bool> = (0..columns_number)
+        .filter(|&i| columns_present[i])
+        .map(|i| row.cells[i].is_none())
+        .collect();
+    write_bitmap_little_endian(cursor, &null_bitmap)?;
+
+    let collations = get_collations(table_map);
+    for i in 0..columns_number {
+        if let (true, Some(value)) = (columns_present[i], &row.cells[i]) {
+            let mut column_type = table_map.column_types[i];
+            let mut metadata = table_map.column_metadata[i];
+            if ColumnType::from_code(column_type)? == ColumnType::String {
+                get_actual_string_type(&mut column_type, &mut metadata);
+            }
+            write_cell(cursor, column_type, metadata, collations[i], value)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_cell(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    column_type: u8,
+    metadata: u16,
+    collation: Option<u32>,
+    value: &MySqlValue,
+) -> Result<(), Error> {
+    let column_type = ColumnType::from_code(column_type)?;
+    match (&column_type, value) {
+        /* Numeric types */
+        (ColumnType::Tiny, MySqlValue::TinyInt(x)) => cursor.write_u8(*x)?,
+        (ColumnType::Short, MySqlValue::SmallInt(x)) => cursor.write_u16::<LittleEndian>(*x)?,
+        (ColumnType::Int24, MySqlValue::MediumInt(x)) => {
+            write_uint::<LittleEndian>(cursor, *x as u64, 3)?
+        }
+        (ColumnType::Long, MySqlValue::Int(x)) => cursor.write_u32::<LittleEndian>(*x)?,
+        (ColumnType::LongLong, MySqlValue::BigInt(x)) => cursor.write_u64::<LittleEndian>(*x)?,
+        (ColumnType::Float, MySqlValue::Float(x)) => cursor.write_f32::<LittleEndian>(*x)?,
+        (ColumnType::Double, MySqlValue::Double(x)) => cursor.write_f64::<LittleEndian>(*x)?,
+        (ColumnType::NewDecimal, MySqlValue::Decimal(x)) => {
+            cursor.write_all(&serialize_decimal(x, metadata)?)?
+        }
+        /* String types. Strings are encoded in the column charset */
+        (ColumnType::String | ColumnType::VarChar | ColumnType::VarString, _) => {
+            write_string(cursor, &get_bytes(value, collation)?, metadata)?
+        }
+        /* BIT, ENUM, SET types */
+        (ColumnType::Bit, MySqlValue::Bit(x)) => write_bit(cursor, x, metadata)?,
+        (ColumnType::Enum, MySqlValue::Enum(x)) => {
+            write_uint::<LittleEndian>(cursor, *x as u64, metadata as usize)?
+        }
+        (ColumnType::Set, MySqlValue::Set(x)) => {
+            write_uint::<LittleEndian>(cursor, *x, metadata as usize)?
+        }
+        /* Blob types */
+        (
+            ColumnType::TinyBlob | ColumnType::MediumBlob | ColumnType::LongBlob | ColumnType::Blob,
+            _,
+        ) => write_blob(cursor, &get_bytes(value, collation)?, metadata)?,
+        /* Date and time types */
+        (ColumnType::Year, MySqlValue::Year(x)) => write_year(cursor, *x, metadata)?,
+        (ColumnType::Date, MySqlValue::Date(x)) => write_date(cursor, x, metadata)?,
+        (ColumnType::Time, MySqlValue::Time(x)) => write_time(cursor, x, metadata)?,
+        (ColumnType::TimeStamp, MySqlValue::Timestamp(x)) => write_timestamp(cursor, *x, metadata)?,
+        (ColumnType::DateTime, MySqlValue::DateTime(x)) => write_date_time(cursor, x, metadata)?,
+        (ColumnType::Time2, MySqlValue::Time(x)) => write_time2(cursor, x, metadata)?,
+        (ColumnType::TimeStamp2, MySqlValue::Timestamp(x)) => {
+            write_timestamp2(cursor, *x, metadata)?
+        }
+        (ColumnType::DateTime2, MySqlValue::DateTime(x)) => write_date_time2(cursor, x, metadata)?,
+        /* MySQL-specific data types */
+        (ColumnType::Geometry, MySqlValue::Geometry(x)) => {
+            let mut bytes = x.srid.to_le_bytes().to_vec();
+            bytes.extend(x.to_wkb());
+            write_blob(cursor, &bytes, metadata)?
+        }
+        (ColumnType::Json, MySqlValue::Json(x)) => {
+            write_blob(cursor, &serialize_json(x)?, metadata)?
+        }
+        _ => {
+            return Err(Error::String(format!(
+                "Writing {:?} to column type {:?} is not supported",
+                value, column_type
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Gets bytes of a string or binary value.
+fn get_bytes(value: &MySqlValue, collation: Option<u32>) -> Result<Vec<u8>, Error> {
+    match value {
+        MySqlValue::Blob(x) => Ok(x.clone()),
+        MySqlValue::String(x) => match encode_string(x, collation) {
+            Some(x) => Ok(x),
+            None => Err(Error::String(format!(
+                "String {:?} can't be encoded in collation {:?}",
+                x, collation
+            ))),
+        },
+        _ => Err(Error::String(format!(
+            "Writing {:?} to a string column is not supported",
+            value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use super::{write_head, write_row};
+    use crate::constants::column_type::ColumnType;
+    use crate::events::row_events::geometry::{Geometry, Point, Shape};
+    use crate::events::row_events::json_value::JsonValue;
+    use crate::events::row_events::mysql_value::{Date, DateTime, MySqlValue, Time};
+    use crate::events::row_events::row_data::RowData;
+    use crate::events::row_events::row_parser::{parse_head, parse_row_data_list};
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::extensions::{read_bitmap_little_endian, write_bitmap_little_endian};
+    use crate::metadata::table_metadata::TableMetadata;
+
+    #[test]
+    fn write_rows_of_all_types() {
+        let columns = [
+            (ColumnType::Tiny as u8, 0, MySqlValue::TinyInt(255)),
+            (ColumnType::Short as u8, 0, MySqlValue::SmallInt(65535)),
+            (ColumnType::Int24 as u8, 0, MySqlValue::MediumInt(0xFFFFFF)),
+            (ColumnType::Long as u8, 0, MySqlValue::Int(7)),
+            (ColumnType::LongLong as u8, 0, MySqlValue::BigInt(u64::MAX)),
+            (ColumnType::Float as u8, 4, MySqlValue::Float(1.5)),
+            (ColumnType::Double as u8, 8, MySqlValue::Double(-2.25)),
+            (
+                ColumnType::NewDecimal as u8,
+                10 | 2 << 8,
+                MySqlValue::Decimal("-12345678.90".to_string()),
+            ),
+            // latin1 VARCHAR(300)
+            (
+                ColumnType::VarChar as u8,
+                300,
+                MySqlValue::String("café".to_string()),
+            ),
+            // CHAR(10) and ENUM logged as STRING
+            (
+                ColumnType::String as u8,
+                (ColumnType::String as u16) << 8 | 40,
+                MySqlValue::String("x".to_string()),
+            ),
+            (
+                ColumnType::String as u8,
+                (ColumnType::Enum as u16) << 8 | 1,
+                MySqlValue::Enum(2),
+            ),
+            (
+                ColumnType::String as u8,
+                (ColumnType::Set as u16) << 8 | 2,
+                MySqlValue::Set(0x101),
+            ),
+            (
+                ColumnType::Bit as u8,
+                1 << 8 | 2,
+                MySqlValue::Bit(vec![
+                    true, false, true, false, false, false, false, false, false, true,
+                ]),
+            ),
+            (
+                ColumnType::Blob as u8,
+                2,
+                MySqlValue::Blob(vec![0xFF, 0x00]),
+            ),
+            (ColumnType::Year as u8, 0, MySqlValue::Year(2023)),
+            (
+                ColumnType::Date as u8,
+                0,
+                MySqlValue::Date(Date {
+                    year: 2023,
+                    month: 7,
+                    day: 15,
+                }),
+            ),
+            (
+                ColumnType::Time as u8,
+                0,
+                MySqlValue::Time(Time {
+                    hour: 838,
+                    minute: 59,
+                    second: 59,
+                    millis: 0,
+                }),
+            ),
+            (
+                ColumnType::TimeStamp as u8,
+                0,
+                MySqlValue::Timestamp(1689416430000),
+            ),
+            (
+                ColumnType::DateTime as u8,
+                0,
+                MySqlValue::DateTime(DateTime {
+                    year: 2023,
+                    month: 7,
+                    day: 15,
+                    hour: 10,
+                    minute: 20,
+                    second: 30,
+                    millis: 0,
+                }),
+            ),
+            (
+                ColumnType::Time2 as u8,
+                3,
+                MySqlValue::Time(Time {
+                    hour: 12,
+                    minute: 34,
+                    second: 56,
+                    millis: 789,
+                }),
+            ),
+            (
+                ColumnType::TimeStamp2 as u8,
+                2,
+                MySqlValue::Timestamp(1689416430120),
+            ),
+            (
+                ColumnType::DateTime2 as u8,
+                6,
+                MySqlValue::DateTime(DateTime {
+                    year: 2023,
+                    month: 7,
+                    day: 15,
+                    hour: 10,
+                    minute: 20,
+                    second: 30,
+                    millis: 456,
+                }),
+            ),
+            (
+                ColumnType::Geometry as u8,
+                4,
+                MySqlValue::Geometry(Geometry {
+                    srid: 4326,
+                    shape: Shape::Point(Point { x: 1.0, y: 2.0 }),
+                }),
+            ),
+            (
+                ColumnType::Json as u8,
+                4,
+                MySqlValue::Json(JsonValue::Object(vec![(
+                    "a".to_string(),
+                    JsonValue::Int(1),
+                )])),
+            ),
+        ];
+        let table = TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("items"),
+            column_types: columns.iter().map(|x| x.0).collect(),
+            column_metadata: columns.iter().map(|x| x.1).collect(),
+            null_bitmap: vec![true; columns.len()],
+            table_metadata: Some(TableMetadata {
+                // latin1_swedish_ci VARCHAR, utf8mb4 CHAR and binary BLOB
+                column_charsets: Some(vec![8, 45, 63]),
+                ..Default::default()
+            }),
+        };
+        let row = RowData::new(columns.iter().map(|x| Some(x.2.clone())).collect());
+        // Nulls and a column missing from the row image
+        let mut nulls = vec![None; columns.len()];
+        nulls[0] = Some(MySqlValue::TinyInt(1));
+        let nulls = RowData::new(nulls);
+        let mut columns_present = vec![true; columns.len()];
+        columns_present[1] = false;
+
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        write_head(&mut cursor, 1, 0, columns.len()).unwrap();
+        write_bitmap_little_endian(&mut cursor, &columns_present).unwrap();
+        write_row(&mut cursor, &table, &columns_present, &row).unwrap();
+        write_row(&mut cursor, &table, &columns_present, &nulls).unwrap();
+
+        let mut cursor = Cursor::new(vec.as_slice());
+        assert_eq!((1, 0, columns.len()), parse_head(&mut cursor, 2).unwrap());
+        let present = read_bitmap_little_endian(&mut cursor, columns.len()).unwrap();
+        let table_map = HashMap::from([(1, table)]);
+        let rows = parse_row_data_list(&mut cursor, &table_map, 1, &present).unwrap();
+
+        let mut expected = row.cells.clone();
+        expected[1] = None;
+        assert_eq!(expected, rows[0].cells);
+        assert_eq!(nulls.cells, rows[1].cells);
+    }
+
+    #[test]
+    fn reject_values_of_other_types() {
+        let table = TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("items"),
+            column_types: vec![ColumnType::Long as u8, ColumnType::VarChar as u8],
+            column_metadata: vec![0, 10],
+            null_bitmap: vec![true, true],
+            table_metadata: Some(TableMetadata {
+                column_charsets: Some(vec![8]),
+                ..Default::default()
+            }),
+        };
+        let columns_present = [true, true];
+        for cells in [
+            vec![Some(MySqlValue::BigInt(1)), None],
+            vec![None, Some(MySqlValue::String("中文".to_string()))],
+            vec![None, Some(MySqlValue::Blob(vec![0; 11]))],
+            vec![None],
+        ] {
+            let mut vec = Vec::new();
+            let mut cursor = Cursor::new(&mut vec);
+            let row = RowData::new(cells);
+            assert!(write_row(&mut cursor, &table, &columns_present, &row).is_err());
+        }
+    }
+}
@@ -0,0 +1,7 @@
+/// Represents a row event of a table excluded by the table filter. Rows are not decoded.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkippedRowsEvent {
+    /// Gets id of the table the rows belong to.
+    pub table_id: u64,
+}
@@ -0,0 +1,239 @@
+use crate::constants::column_type::ColumnType;
+use crate::errors::Error;
+use crate::events::row_events::mysql_value::MySqlValue;
+use crate::events::row_events::row_data::RowData;
+use crate::events::table_map_event::TableMapEvent;
+
+/// Column value with resolved signedness and ENUM/SET labels.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum ColumnValue {
+    /// Signed TINYINT, SMALLINT, MEDIUMINT, INT or BIGINT value.
+    Signed(i64),
+
+    /// Unsigned TINYINT, SMALLINT, MEDIUMINT, INT or BIGINT value.
+    Unsigned(u64),
+
+    /// ENUM label. Empty string is returned for invalid value with zero index.
+    Enum(String),
+
+    /// Labels of SET members.
+    Set(Vec<String>),
+
+    /// Any other value as it was parsed from the row event.
+    Value(MySqlValue),
+}
+
+/// Column of a decoded row.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypedColumn {
+    /// Gets column name. Column index prefixed by '@' (starting from @1)
+    /// is used when column names are not logged (binlog_row_metadata=MINIMAL).
+    pub name: String,
+
+    /// Gets column value. None for NULL or missing value (see binlog_row_image).
+    pub value: Option<ColumnValue>,
+}
+
+/// Row with values keyed by column names.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypedRow {
+    /// Gets columns in table order.
+    pub columns: Vec<TypedColumn>,
+}
+
+impl TypedRow {
+    /// Decodes row using metadata of the preceding TableMapEvent.
+    pub fn decode(table: &TableMapEvent, row: &RowData) -> Result<Self, Error> {
+        if row.cells.len() != table.column_types.len() {
+            return Err(Error::String(format!(
+                "Row has {} cells while table {}.{} has {} columns",
+                row.cells.len(),
+                table.database_name,
+                table.table_name,
+                table.column_types.len()
+            )));
+        }
+
+        let mut columns = Vec::with_capacity(row.cells.len());
+        for (column, cell) in table.columns()?.into_iter().zip(row.cells.iter()) {
+            let value = match ColumnType::from_code(column.column_type)? {
+                ColumnType::Tiny
+                | ColumnType::Short
+                | ColumnType::Int24
+                | ColumnType::Long
+                | ColumnType::LongLong => cell.as_ref().map(|x| decode_integer(x, column.unsigned)),
+                ColumnType::Enum => cell
+                    .as_ref()
+                    .map(|x| decode_enum(x, column.labels.as_ref())),
+                ColumnType::Set => cell.as_ref().map(|x| decode_set(x, column.labels.as_ref())),
+                _ => cell.clone().map(ColumnValue::Value),
+            };
+            columns.push(TypedColumn {
+                name: column.name,
+                value,
+            });
+        }
+        Ok(Self { columns })
+    }
+
+    /// Gets value of the column. Returns None for NULL values and unknown columns.
+    pub fn get(&self, name: &str) -> Option<&ColumnValue> {
+        self.columns
+            .iter()
+            .find(|x| x.name == name)
+            .and_then(|x| x.value.as_ref())
+    }
+}
+
+fn decode_integer(value: &MySqlValue, unsigned: bool) -> ColumnValue {
+    match (value, unsigned) {
+        (MySqlValue::TinyInt(x), false) => ColumnValue::Signed(*x as i8 as i64),
+        (MySqlValue::SmallInt(x), false) => ColumnValue::Signed(*x as i16 as i64),
+        (MySqlValue::MediumInt(x), false) => ColumnValue::Signed(((*x << 8) as i32 >> 8) as i64),
+        (MySqlValue::Int(x), false) => ColumnValue::Signed(*x as i32 as i64),
+        (MySqlValue::BigInt(x), false) => ColumnValue::Signed(*x as i64),
+        (MySqlValue::TinyInt(x), true) => ColumnValue::Unsigned(*x as u64),
+        (MySqlValue::SmallInt(x), true) => ColumnValue::Unsigned(*x as u64),
+        (MySqlValue::MediumInt(x), true) => ColumnValue::Unsigned(*x as u64),
+        (MySqlValue::Int(x), true) => ColumnValue::Unsigned(*x as u64),
+        (MySqlValue::BigInt(x), true) => ColumnValue::Unsigned(*x),
+        (value, _) => ColumnValue::Value(value.clone()),
+    }
+}
+
+fn decode_enum(value: &MySqlValue, labels: Option<&Vec<String>>) -> ColumnValue {
+    match (value, labels) {
+        (MySqlValue::Enum(0), Some(_)) => ColumnValue::Enum(String::new()),
+        (MySqlValue::Enum(x), Some(labels)) => match labels.get(*x as usize - 1) {
+            Some(label) => ColumnValue::Enum(label.clone()),
+            None => ColumnValue::Value(value.clone()),
+        },
+        (value, _) => ColumnValue::Value(value.clone()),
+    }
+}
+
+fn decode_set(value: &MySqlValue, labels: Option<&Vec<String>>) -> ColumnValue {
+    match (value, labels) {
+        (MySqlValue::Set(x), Some(labels)) => {
+            let members = labels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i < 64 && x & (1 << i) != 0)
+                .map(|(_, label)| label.clone())
+                .collect();
+            ColumnValue::Set(members)
+        }
+        (value, _) => ColumnValue::Value(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnValue, TypedRow};
+    use crate::constants::column_type::ColumnType;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::events::row_events::row_data::RowData;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::metadata::table_metadata::TableMetadata;
+
+    fn create_table(table_metadata: Option<TableMetadata>) -> TableMapEvent {
+        TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![
+                ColumnType::Tiny as u8,
+                ColumnType::Int24 as u8,
+                ColumnType::Long as u8,
+                ColumnType::String as u8,
+                ColumnType::String as u8,
+                ColumnType::VarChar as u8,
+            ],
+            column_metadata: vec![
+                0,
+                0,
+                0,
+                (ColumnType::Enum as u16) << 8 | 1,
+                (ColumnType::Set as u16) << 8 | 1,
+                100,
+            ],
+            null_bitmap: vec![true; 6],
+            table_metadata,
+        }
+    }
+
+    fn create_metadata() -> TableMetadata {
+        TableMetadata {
+            signedness: Some(vec![false, false, true]),
+            default_charset: None,
+            column_charsets: None,
+            column_names: Some(
+                ["delta", "offset", "quantity", "status", "tags", "note"]
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect(),
+            ),
+            set_string_values: Some(vec![vec!["red".to_string(), "blue".to_string()]]),
+            enum_string_values: Some(vec![vec!["new".to_string(), "paid".to_string()]]),
+            geometry_types: None,
+            simple_primary_keys: None,
+            primary_keys_with_prefix: None,
+            enum_and_set_default_charset: None,
+            enum_and_set_column_charsets: None,
+            column_visibility: None,
+        }
+    }
+
+    fn create_row() -> RowData {
+        RowData::new(vec![
+            Some(MySqlValue::TinyInt(0xFF)),
+            Some(MySqlValue::MediumInt(0xFFFFFE)),
+            Some(MySqlValue::Int(0xFFFFFFFF)),
+            Some(MySqlValue::Enum(2)),
+            Some(MySqlValue::Set(3)),
+            None,
+        ])
+    }
+
+    #[test]
+    fn decode_row_with_full_metadata() {
+        let table = create_table(Some(create_metadata()));
+        let row = TypedRow::decode(&table, &create_row()).unwrap();
+
+        assert_eq!(Some(&ColumnValue::Signed(-1)), row.get("delta"));
+        assert_eq!(Some(&ColumnValue::Signed(-2)), row.get("offset"));
+        assert_eq!(
+            Some(&ColumnValue::Unsigned(0xFFFFFFFF)),
+            row.get("quantity")
+        );
+        assert_eq!(
+            Some(&ColumnValue::Enum("paid".to_string())),
+            row.get("status")
+        );
+        assert_eq!(
+            Some(&ColumnValue::Set(vec![
+                "red".to_string(),
+                "blue".to_string()
+            ])),
+            row.get("tags")
+        );
+        assert_eq!(None, row.get("note"));
+    }
+
+    #[test]
+    fn decode_row_without_metadata() {
+        let table = create_table(None);
+        let row = TypedRow::decode(&table, &create_row()).unwrap();
+
+        assert_eq!(Some(&ColumnValue::Signed(-1)), row.get("@1"));
+        assert_eq!(Some(&ColumnValue::Signed(-1)), row.get("@3"));
+        assert_eq!(
+            Some(&ColumnValue::Value(MySqlValue::Enum(2))),
+            row.get("@4")
+        );
+    }
+}
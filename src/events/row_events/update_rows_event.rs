@@ -1,15 +1,17 @@
 use crate::errors::Error;
 use crate::events::row_events::row_data::UpdateRowData;
 use crate::events::row_events::row_parser::{parse_head, parse_update_row_data_list};
+use crate::events::row_events::row_writer::{write_head, write_update_row_data_list};
 use crate::events::table_map_event::TableMapEvent;
-use crate::extensions::read_bitmap_little_endian;
+use crate::extensions::{read_bitmap_little_endian, write_bitmap_little_endian};
 use std::collections::HashMap;
 use std::io::Cursor;
 
 /// Represents one or many updated rows in row based replication.
 /// Includes versions before and after update.
 /// <a href="https://mariadb.com/kb/en/library/rows_event_v1/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UpdateRowsEvent {
     /// Gets id of the table where rows were updated
     pub table_id: u64,
@@ -56,4 +58,22 @@ impl UpdateRowsEvent {
             rows,
         })
     }
+
+    /// Serializes the event in version 2 format using column types of the TableMapEvent.
+    pub fn serialize(&self, table_map: &HashMap<u64, TableMapEvent>) -> Result<Vec<u8>, Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        write_head(&mut cursor, self.table_id, self.flags, self.columns_number)?;
+        write_bitmap_little_endian(&mut cursor, &self.columns_before_update)?;
+        write_bitmap_little_endian(&mut cursor, &self.columns_after_update)?;
+        write_update_row_data_list(
+            &mut cursor,
+            table_map,
+            self.table_id,
+            &self.columns_before_update,
+            &self.columns_after_update,
+            &self.rows,
+        )?;
+        Ok(vec)
+    }
 }
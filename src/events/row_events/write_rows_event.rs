@@ -1,14 +1,16 @@
 use crate::errors::Error;
 use crate::events::row_events::row_data::RowData;
 use crate::events::row_events::row_parser::{parse_head, parse_row_data_list};
+use crate::events::row_events::row_writer::{write_head, write_row_data_list};
 use crate::events::table_map_event::TableMapEvent;
-use crate::extensions::read_bitmap_little_endian;
+use crate::extensions::{read_bitmap_little_endian, write_bitmap_little_endian};
 use std::collections::HashMap;
 use std::io::Cursor;
 
 /// Represents one or many inserted rows in row based replication.
 /// <a href="https://mariadb.com/kb/en/library/rows_event_v1/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WriteRowsEvent {
     /// Gets id of the table where rows were inserted
     pub table_id: u64,
@@ -44,4 +46,20 @@ impl WriteRowsEvent {
             rows,
         })
     }
+
+    /// Serializes the event in version 2 format using column types of the TableMapEvent.
+    pub fn serialize(&self, table_map: &HashMap<u64, TableMapEvent>) -> Result<Vec<u8>, Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        write_head(&mut cursor, self.table_id, self.flags, self.columns_number)?;
+        write_bitmap_little_endian(&mut cursor, &self.columns_present)?;
+        write_row_data_list(
+            &mut cursor,
+            table_map,
+            self.table_id,
+            &self.columns_present,
+            &self.rows,
+        )?;
+        Ok(vec)
+    }
 }
@@ -0,0 +1,282 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::Cursor;
+
+use crate::{errors::Error, extensions::read_string};
+
+const Q_FLAGS2_CODE: u8 = 0;
+const Q_SQL_MODE_CODE: u8 = 1;
+const Q_CATALOG_CODE: u8 = 2;
+const Q_AUTO_INCREMENT: u8 = 3;
+pub(crate) const Q_CHARSET_CODE: u8 = 4;
+const Q_TIME_ZONE_CODE: u8 = 5;
+const Q_CATALOG_NZ_CODE: u8 = 6;
+const Q_LC_TIME_NAMES_CODE: u8 = 7;
+const Q_CHARSET_DATABASE_CODE: u8 = 8;
+const Q_TABLE_MAP_FOR_UPDATE_CODE: u8 = 9;
+const Q_MASTER_DATA_WRITTEN_CODE: u8 = 10;
+const Q_INVOKER: u8 = 11;
+const Q_UPDATED_DB_NAMES: u8 = 12;
+const Q_MICROSECONDS: u8 = 13;
+const Q_EXPLICIT_DEFAULTS_FOR_TIMESTAMP: u8 = 16;
+const Q_DDL_LOGGED_WITH_XID: u8 = 17;
+const Q_DEFAULT_COLLATION_FOR_UTF8MB4: u8 = 18;
+const Q_SQL_REQUIRE_PRIMARY_KEY: u8 = 19;
+const Q_DEFAULT_TABLE_ENCRYPTION: u8 = 20;
+
+// MariaDB specific status variables
+const Q_HRNOW: u8 = 128;
+const Q_XID: u8 = 129;
+
+/// Updated databases are not logged if there are more of them than the limit.
+const OVER_MAX_DBS_IN_EVENT_MTS: u8 = 254;
+
+/// Represents session state logged with a QueryEvent.
+/// Variables that were not logged are None.
+/// <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/classbinary__log_1_1Query__event.html">See more</a>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusVariables {
+    /// Gets flags of @@foreign_key_checks, @@unique_checks, @@autocommit, etc.
+    pub flags2: Option<u32>,
+
+    /// Gets bitmap of @@sql_mode.
+    pub sql_mode: Option<u64>,
+
+    /// Gets catalog name. Always "std".
+    pub catalog: Option<String>,
+
+    /// Gets @@auto_increment_increment.
+    pub auto_increment_increment: Option<u16>,
+
+    /// Gets @@auto_increment_offset.
+    pub auto_increment_offset: Option<u16>,
+
+    /// Gets collation id of @@character_set_client.
+    pub charset_client: Option<u16>,
+
+    /// Gets collation id of @@collation_connection.
+    pub collation_connection: Option<u16>,
+
+    /// Gets collation id of @@collation_server.
+    pub collation_server: Option<u16>,
+
+    /// Gets @@time_zone, e.g. SYSTEM or +03:00
+    pub time_zone: Option<String>,
+
+    /// Gets id of @@lc_time_names locale.
+    pub lc_time_names: Option<u16>,
+
+    /// Gets collation id of @@collation_database.
+    pub charset_database: Option<u16>,
+
+    /// Gets bitmap of tables updated by a multi-table update.
+    pub table_map_for_update: Option<u64>,
+
+    /// Gets the length of the original event written by the master.
+    pub master_data_written: Option<u32>,
+
+    /// Gets user of the definer of a stored routine, view or trigger.
+    pub invoker_user: Option<String>,
+
+    /// Gets host of the definer of a stored routine, view or trigger.
+    pub invoker_host: Option<String>,
+
+    /// Gets databases updated by the statement. Empty when there are too many of them.
+    pub updated_db_names: Option<Vec<String>>,
+
+    /// Gets microseconds part of the statement start time.
+    pub microseconds: Option<u32>,
+
+    /// Gets @@explicit_defaults_for_timestamp.
+    pub explicit_defaults_ts: Option<bool>,
+
+    /// Gets xid of DDL statement logged by a server with atomic DDL.
+    pub ddl_xid: Option<u64>,
+
+    /// Gets collation id of @@default_collation_for_utf8mb4.
+    pub default_collation_for_utf8mb4: Option<u16>,
+
+    /// Gets @@sql_require_primary_key.
+    pub sql_require_primary_key: Option<bool>,
+
+    /// Gets @@default_table_encryption.
+    pub default_table_encryption: Option<bool>,
+}
+
+impl StatusVariables {
+    /// Parses status variables. Decoding stops at an unknown or malformed variable
+    /// because the length of the following ones cannot be determined.
+    /// Variables decoded before it are kept; raw bytes are available in QueryEvent.
+    pub fn parse(slice: &[u8]) -> Self {
+        let mut cursor = Cursor::new(slice);
+        let mut variables = StatusVariables::default();
+
+        while cursor.position() < slice.len() as u64 {
+            match variables.parse_variable(&mut cursor) {
+                Ok(true) => {}
+                Ok(false) | Err(_) => break,
+            }
+        }
+        variables
+    }
+
+    /// Gets offset of the variable value in raw status variables.
+    /// Returns None if the variable is not logged or can't be decoded.
+    pub(crate) fn find_variable(slice: &[u8], code: u8) -> Option<usize> {
+        let mut cursor = Cursor::new(slice);
+        let mut variables = StatusVariables::default();
+
+        while cursor.position() < slice.len() as u64 {
+            let offset = cursor.position() as usize;
+            if !matches!(variables.parse_variable(&mut cursor), Ok(true)) {
+                return None;
+            }
+            if slice[offset] == code {
+                return Some(offset + 1);
+            }
+        }
+        None
+    }
+
+    /// Returns false if the variable is unknown.
+    fn parse_variable(&mut self, cursor: &mut Cursor<&[u8]>) -> Result<bool, Error> {
+        match cursor.read_u8()? {
+            Q_FLAGS2_CODE => self.flags2 = Some(cursor.read_u32::<LittleEndian>()?),
+            Q_SQL_MODE_CODE => self.sql_mode = Some(cursor.read_u64::<LittleEndian>()?),
+            Q_CATALOG_CODE => {
+                let catalog = read_short_string(cursor)?;
+                // Null terminated
+                cursor.read_u8()?;
+                self.catalog = Some(catalog);
+            }
+            Q_AUTO_INCREMENT => {
+                let increment = cursor.read_u16::<LittleEndian>()?;
+                let offset = cursor.read_u16::<LittleEndian>()?;
+                self.auto_increment_increment = Some(increment);
+                self.auto_increment_offset = Some(offset);
+            }
+            Q_CHARSET_CODE => {
+                let client = cursor.read_u16::<LittleEndian>()?;
+                let connection = cursor.read_u16::<LittleEndian>()?;
+                let server = cursor.read_u16::<LittleEndian>()?;
+                self.charset_client = Some(client);
+                self.collation_connection = Some(connection);
+                self.collation_server = Some(server);
+            }
+            Q_TIME_ZONE_CODE => self.time_zone = Some(read_short_string(cursor)?),
+            Q_CATALOG_NZ_CODE => self.catalog = Some(read_short_string(cursor)?),
+            Q_LC_TIME_NAMES_CODE => self.lc_time_names = Some(cursor.read_u16::<LittleEndian>()?),
+            Q_CHARSET_DATABASE_CODE => {
+                self.charset_database = Some(cursor.read_u16::<LittleEndian>()?)
+            }
+            Q_TABLE_MAP_FOR_UPDATE_CODE => {
+                self.table_map_for_update = Some(cursor.read_u64::<LittleEndian>()?)
+            }
+            Q_MASTER_DATA_WRITTEN_CODE => {
+                self.master_data_written = Some(cursor.read_u32::<LittleEndian>()?)
+            }
+            Q_INVOKER => {
+                let user = read_short_string(cursor)?;
+                let host = read_short_string(cursor)?;
+                self.invoker_user = Some(user);
+                self.invoker_host = Some(host);
+            }
+            Q_UPDATED_DB_NAMES => self.updated_db_names = Some(read_db_names(cursor)?),
+            Q_MICROSECONDS | Q_HRNOW => {
+                self.microseconds = Some(cursor.read_u24::<LittleEndian>()?)
+            }
+            Q_EXPLICIT_DEFAULTS_FOR_TIMESTAMP => {
+                self.explicit_defaults_ts = Some(cursor.read_u8()? != 0)
+            }
+            Q_DDL_LOGGED_WITH_XID | Q_XID => {
+                self.ddl_xid = Some(cursor.read_u64::<LittleEndian>()?)
+            }
+            Q_DEFAULT_COLLATION_FOR_UTF8MB4 => {
+                self.default_collation_for_utf8mb4 = Some(cursor.read_u16::<LittleEndian>()?)
+            }
+            Q_SQL_REQUIRE_PRIMARY_KEY => {
+                self.sql_require_primary_key = Some(cursor.read_u8()? != 0)
+            }
+            Q_DEFAULT_TABLE_ENCRYPTION => {
+                self.default_table_encryption = Some(cursor.read_u8()? != 0)
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn read_short_string(cursor: &mut Cursor<&[u8]>) -> Result<String, Error> {
+    let length = cursor.read_u8()?;
+    read_string(cursor, length as usize)
+}
+
+/// Database names are null terminated.
+fn read_db_names(cursor: &mut Cursor<&[u8]>) -> Result<Vec<String>, Error> {
+    let count = cursor.read_u8()?;
+    if count == OVER_MAX_DBS_IN_EVENT_MTS {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let mut name = Vec::new();
+        loop {
+            match cursor.read_u8()? {
+                0 => break,
+                x => name.push(x),
+            }
+        }
+        names.push(String::from_utf8(name)?);
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatusVariables;
+
+    #[test]
+    fn parse_status_variables() {
+        let data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, // flags2
+            0x01, 0x20, 0x00, 0xA0, 0x55, 0x00, 0x00, 0x00, 0x00, // sql_mode
+            0x06, 0x03, b's', b't', b'd', // catalog
+            0x04, 0xFF, 0x00, 0xFF, 0x00, 0x08, 0x00, // charset
+            0x05, 0x06, b'+', b'0', b'3', b':', b'0', b'0', // time_zone
+            0x0C, 0x02, b'a', 0x00, b'b', b'c', 0x00, // updated_db_names
+            0x11, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ddl_xid
+            0x12, 0xFF, 0x00, // default_collation_for_utf8mb4
+            0xFA, 0x01, 0x02, // unknown
+        ];
+        let variables = StatusVariables::parse(&data);
+
+        assert_eq!(Some(0), variables.flags2);
+        assert_eq!(Some(0x55A00020), variables.sql_mode);
+        assert_eq!(Some("std".to_string()), variables.catalog);
+        assert_eq!(Some(255), variables.charset_client);
+        assert_eq!(Some(255), variables.collation_connection);
+        assert_eq!(Some(8), variables.collation_server);
+        assert_eq!(Some("+03:00".to_string()), variables.time_zone);
+        assert_eq!(
+            Some(vec!["a".to_string(), "bc".to_string()]),
+            variables.updated_db_names
+        );
+        assert_eq!(Some(42), variables.ddl_xid);
+        assert_eq!(Some(255), variables.default_collation_for_utf8mb4);
+        assert_eq!(None, variables.microseconds);
+    }
+
+    #[test]
+    fn stop_at_malformed_variable() {
+        let data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, // flags2
+            0x0B, 0x04, b'r', b'o', b'o', b't', 0x09, b'l', b'o', // truncated invoker
+        ];
+        let variables = StatusVariables::parse(&data);
+
+        assert_eq!(Some(0), variables.flags2);
+        assert_eq!(None, variables.invoker_user);
+        assert_eq!(None, variables.invoker_host);
+    }
+}
@@ -1,13 +1,22 @@
 use crate::constants::column_type::ColumnType;
 use crate::errors::Error;
-use crate::extensions::{read_bitmap_little_endian, read_len_enc_num, read_string};
+use crate::events::row_events::actual_string_type::get_actual_string_type;
+use crate::extensions::{
+    read_bitmap_little_endian, read_len_enc_num, read_string, write_bitmap_little_endian,
+    write_len_enc_num, write_null_term_string,
+};
+use crate::metadata::default_charset::DefaultCharset;
 use crate::metadata::table_metadata::TableMetadata;
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+/// Binary collation of binary strings and BLOB columns.
+pub const BINARY_COLLATION: u32 = 63;
 
 /// The event has table defition for row events.
 /// <a href="https://mariadb.com/kb/en/library/table_map_event/">See more</a>
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableMapEvent {
     /// Gets id of the changed table
     pub table_id: u64,
@@ -75,6 +84,134 @@ impl TableMapEvent {
         })
     }
 
+    /// Serializes the event. Table metadata is written if present.
+    pub fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        cursor.write_u48::<LittleEndian>(self.table_id)?;
+
+        // Reserved bytes
+        cursor.write_u16::<LittleEndian>(0)?;
+
+        // Database and table names are null terminated
+        cursor.write_u8(self.database_name.len() as u8)?;
+        write_null_term_string(&mut cursor, &self.database_name)?;
+        cursor.write_u8(self.table_name.len() as u8)?;
+        write_null_term_string(&mut cursor, &self.table_name)?;
+
+        let columns_number = self.column_types.len();
+        write_len_enc_num(&mut cursor, columns_number)?;
+        cursor.write_all(&self.column_types)?;
+
+        let column_metadata =
+            TableMapEvent::serialize_metadata(&self.column_types, &self.column_metadata)?;
+        write_len_enc_num(&mut cursor, column_metadata.len())?;
+        cursor.write_all(&column_metadata)?;
+
+        // Columns are nullable unless specified otherwise like in columns().
+        let null_bitmap: Vec<bool> = (0..columns_number)
+            .map(|i| self.null_bitmap.get(i).cloned().unwrap_or(true))
+            .collect();
+        write_bitmap_little_endian(&mut cursor, &null_bitmap)?;
+
+        if let Some(table_metadata) = &self.table_metadata {
+            cursor.write_all(&table_metadata.serialize(&self.column_types)?)?;
+        }
+        Ok(vec)
+    }
+
+    /// Gets column definitions resolved from column types and optional table metadata.
+    pub fn columns(&self) -> Result<Vec<ColumnInfo>, Error> {
+        let metadata = self.table_metadata.as_ref();
+        let column_names = metadata.and_then(|x| x.column_names.as_ref());
+        let signedness = metadata.and_then(|x| x.signedness.as_ref());
+        let enum_values = metadata.and_then(|x| x.enum_string_values.as_ref());
+        let set_values = metadata.and_then(|x| x.set_string_values.as_ref());
+
+        let mut numeric_index = 0;
+        let mut character_index = 0;
+        let mut enum_and_set_index = 0;
+        let mut enum_index = 0;
+        let mut set_index = 0;
+        let mut columns = Vec::with_capacity(self.column_types.len());
+
+        for i in 0..self.column_types.len() {
+            let mut column_type = self.column_types[i];
+            let mut column_metadata = self.column_metadata[i];
+            if ColumnType::from_code(column_type)? == ColumnType::String {
+                get_actual_string_type(&mut column_type, &mut column_metadata);
+            }
+
+            let name = match column_names.and_then(|x| x.get(i)) {
+                Some(name) => name.clone(),
+                None => format!("@{}", i + 1),
+            };
+            let mut column = ColumnInfo {
+                name,
+                column_type,
+                metadata: column_metadata,
+                nullable: self.null_bitmap.get(i).cloned().unwrap_or(true),
+                unsigned: false,
+                labels: None,
+                collation: None,
+            };
+
+            match ColumnType::from_code(column_type)? {
+                ColumnType::Tiny
+                | ColumnType::Short
+                | ColumnType::Int24
+                | ColumnType::Long
+                | ColumnType::LongLong
+                | ColumnType::Float
+                | ColumnType::Double
+                | ColumnType::NewDecimal => {
+                    // Columns are signed by default when signedness is not logged.
+                    column.unsigned = signedness
+                        .and_then(|x| x.get(numeric_index))
+                        .cloned()
+                        .unwrap_or(false);
+                    numeric_index += 1;
+                }
+                ColumnType::String
+                | ColumnType::VarString
+                | ColumnType::VarChar
+                | ColumnType::Blob => {
+                    column.collation = metadata.and_then(|x| {
+                        get_collation(&x.default_charset, &x.column_charsets, character_index)
+                    });
+                    character_index += 1;
+                }
+                ColumnType::Enum => {
+                    column.labels = enum_values.and_then(|x| x.get(enum_index)).cloned();
+                    column.collation = metadata.and_then(|x| {
+                        get_collation(
+                            &x.enum_and_set_default_charset,
+                            &x.enum_and_set_column_charsets,
+                            enum_and_set_index,
+                        )
+                    });
+                    enum_index += 1;
+                    enum_and_set_index += 1;
+                }
+                ColumnType::Set => {
+                    column.labels = set_values.and_then(|x| x.get(set_index)).cloned();
+                    column.collation = metadata.and_then(|x| {
+                        get_collation(
+                            &x.enum_and_set_default_charset,
+                            &x.enum_and_set_column_charsets,
+                            enum_and_set_index,
+                        )
+                    });
+                    set_index += 1;
+                    enum_and_set_index += 1;
+                }
+                _ => {}
+            }
+            columns.push(column);
+        }
+        Ok(columns)
+    }
+
     fn parse_metadata(
         cursor: &mut Cursor<&[u8]>,
         column_types: &Vec<u8>,
@@ -111,4 +248,163 @@ impl TableMapEvent {
         }
         Ok(metadata)
     }
+
+    fn serialize_metadata(column_types: &[u8], column_metadata: &[u16]) -> Result<Vec<u8>, Error> {
+        if column_metadata.len() != column_types.len() {
+            return Err(Error::String(
+                "Column metadata doesn't match column types".to_string(),
+            ));
+        }
+
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        for i in 0..column_types.len() {
+            let metadata = column_metadata[i];
+            match ColumnType::from_code(column_types[i])? {
+                // 1 byte metadata
+                ColumnType::Geometry
+                | ColumnType::Json
+                | ColumnType::TinyBlob
+                | ColumnType::MediumBlob
+                | ColumnType::LongBlob
+                | ColumnType::Blob
+                | ColumnType::Float
+                | ColumnType::Double
+                | ColumnType::TimeStamp2
+                | ColumnType::DateTime2
+                | ColumnType::Time2 => cursor.write_u8(metadata as u8)?,
+                // 2 bytes little endian
+                ColumnType::Bit
+                | ColumnType::VarChar
+                | ColumnType::VarString
+                | ColumnType::NewDecimal => cursor.write_u16::<LittleEndian>(metadata)?,
+                // 2 bytes big endian
+                ColumnType::Enum | ColumnType::Set | ColumnType::String => {
+                    cursor.write_u16::<BigEndian>(metadata)?
+                }
+                _ => (),
+            }
+        }
+        Ok(vec)
+    }
+}
+
+/// Column definition resolved from TableMapEvent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnInfo {
+    /// Gets column name. Column index prefixed by '@' (starting from @1)
+    /// is used when column names are not logged (binlog_row_metadata=MINIMAL).
+    pub name: String,
+
+    /// Gets column type. ENUM and SET columns logged as STRING are resolved to their real type.
+    pub column_type: u8,
+
+    /// Gets column metadata, e.g. length or precision.
+    pub metadata: u16,
+
+    /// Gets whether the column accepts NULL values.
+    pub nullable: bool,
+
+    /// Gets whether a numeric column is UNSIGNED. False if signedness is not logged.
+    pub unsigned: bool,
+
+    /// Gets labels of ENUM and SET columns if logged.
+    pub labels: Option<Vec<String>>,
+
+    /// Gets collation of character, ENUM and SET columns if logged.
+    /// BINARY_COLLATION is used by binary strings and BLOB columns.
+    pub collation: Option<u32>,
+}
+
+/// Gets collation of the column with index among character columns.
+fn get_collation(
+    default_charset: &Option<DefaultCharset>,
+    column_charsets: &Option<Vec<u32>>,
+    index: usize,
+) -> Option<u32> {
+    if let Some(charsets) = column_charsets {
+        return charsets.get(index).cloned();
+    }
+    default_charset.as_ref().map(|x| {
+        x.charset_collations
+            .iter()
+            .find(|(column, _)| *column as usize == index)
+            .map(|(_, collation)| *collation)
+            .unwrap_or(x.default_charset_collation)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::TableMapEvent;
+    use crate::constants::column_type::ColumnType;
+    use crate::metadata::default_charset::DefaultCharset;
+    use crate::metadata::table_metadata::TableMetadata;
+
+    #[test]
+    fn serialize_table_with_metadata() {
+        let table = TableMapEvent {
+            table_id: 0x0102030405,
+            database_name: String::from("shop"),
+            table_name: String::from("items"),
+            column_types: vec![
+                ColumnType::LongLong as u8,
+                ColumnType::NewDecimal as u8,
+                ColumnType::VarChar as u8,
+                ColumnType::String as u8,
+                ColumnType::String as u8,
+                ColumnType::Blob as u8,
+                ColumnType::DateTime2 as u8,
+                ColumnType::Geometry as u8,
+                ColumnType::Bit as u8,
+            ],
+            column_metadata: vec![
+                0,
+                10 | 2 << 8,
+                1020,
+                (ColumnType::String as u16) << 8 | 40,
+                (ColumnType::Enum as u16) << 8 | 1,
+                2,
+                3,
+                4,
+                1 << 8 | 2,
+            ],
+            null_bitmap: vec![false, true, true, true, true, true, true, false, true],
+            table_metadata: Some(TableMetadata {
+                signedness: Some(vec![true, false]),
+                default_charset: Some(DefaultCharset::new(45, vec![(1, 8)])),
+                column_names: Some(
+                    [
+                        "id", "price", "name", "code", "size", "notes", "added", "place", "flags",
+                    ]
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect(),
+                ),
+                enum_string_values: Some(vec![vec![String::from("S"), String::from("L")]]),
+                geometry_types: Some(vec![1]),
+                simple_primary_keys: Some(vec![0]),
+                enum_and_set_default_charset: Some(DefaultCharset::new(8, Vec::new())),
+                column_visibility: Some(vec![true; 9]),
+                ..Default::default()
+            }),
+        };
+
+        let payload = table.serialize().unwrap();
+        let mut cursor = Cursor::new(payload.as_slice());
+        let parsed = TableMapEvent::parse(&mut cursor).unwrap();
+        assert_eq!(table, parsed);
+        assert_eq!(table.columns().unwrap(), parsed.columns().unwrap());
+
+        // Without table metadata like MySQL 5.7 with binlog_row_metadata=MINIMAL
+        let table = TableMapEvent {
+            table_metadata: None,
+            ..table
+        };
+        let payload = table.serialize().unwrap();
+        let mut cursor = Cursor::new(payload.as_slice());
+        assert_eq!(table, TableMapEvent::parse(&mut cursor).unwrap());
+    }
 }
@@ -5,7 +5,8 @@ use crate::{errors::Error, extensions::read_string};
 
 /// A USER_VAR_EVENT is written every time a statement uses a user defined variable.
 /// <a href="https://mariadb.com/kb/en/user_var_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserVarEvent {
     /// User variable name
     pub name: String,
@@ -15,7 +16,8 @@ pub struct UserVarEvent {
 }
 
 /// User variable value
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariableValue {
     /// Variable type
     pub var_type: u8,
@@ -1,11 +1,12 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::Cursor;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor};
 
 use crate::errors::Error;
 
 /// Represents a transaction commit event.
 /// <a href="https://mariadb.com/kb/en/library/xid_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XidEvent {
     /// Gets the XID transaction number
     pub xid: u64,
@@ -18,4 +19,13 @@ impl XidEvent {
 
         Ok(Self { xid })
     }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+
+        cursor.write_u64::<LittleEndian>(self.xid)?;
+
+        Ok(vec)
+    }
 }
@@ -95,6 +95,29 @@ pub fn read_len_enc_num(cursor: &mut Cursor<&[u8]>) -> Result<usize, Error> {
     }
 }
 
+/// Writes length-encoded integer. See read_len_enc_num.
+pub fn write_len_enc_num(cursor: &mut Cursor<&mut Vec<u8>>, value: usize) -> Result<(), io::Error> {
+    if value < 0xFB {
+        cursor.write_u8(value as u8)?;
+    } else if value <= 0xFFFF {
+        cursor.write_u8(0xFC)?;
+        cursor.write_u16::<LittleEndian>(value as u16)?;
+    } else if value <= 0xFF_FFFF {
+        cursor.write_u8(0xFD)?;
+        cursor.write_u24::<LittleEndian>(value as u32)?;
+    } else {
+        cursor.write_u8(0xFE)?;
+        cursor.write_u64::<LittleEndian>(value as u64)?;
+    }
+    Ok(())
+}
+
+/// Writes length-encoded string. See read_len_enc_str.
+pub fn write_len_enc_str(cursor: &mut Cursor<&mut Vec<u8>>, str: &str) -> Result<(), io::Error> {
+    write_len_enc_num(cursor, str.len())?;
+    cursor.write_all(str.as_bytes())
+}
+
 /// Reads bitmap in little-endian bytes order
 pub fn read_bitmap_little_endian(
     cursor: &mut Cursor<&[u8]>,
@@ -135,6 +158,42 @@ pub fn read_bitmap_big_endian(
     Ok(result)
 }
 
+/// Writes bitmap in little-endian bytes order
+pub fn write_bitmap_little_endian(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bitmap: &[bool],
+) -> Result<(), io::Error> {
+    for chunk in bitmap.chunks(8) {
+        let mut value = 0u8;
+        for (y, bit) in chunk.iter().enumerate() {
+            if *bit {
+                value |= 1 << y;
+            }
+        }
+        cursor.write_u8(value)?;
+    }
+    Ok(())
+}
+
+/// Writes bitmap in big-endian bytes order
+pub fn write_bitmap_big_endian(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bitmap: &[bool],
+) -> Result<(), io::Error> {
+    let bytes_number = bitmap.len().div_ceil(8);
+    for i in 0..bytes_number {
+        let mut value = 0u8;
+        for y in 0..8 {
+            let index = ((bytes_number - i - 1) << 3) + y;
+            if bitmap.get(index) == Some(&true) {
+                value |= 1 << y;
+            }
+        }
+        cursor.write_u8(value)?;
+    }
+    Ok(())
+}
+
 pub fn check_error_packet(packet: &[u8], message: &str) -> Result<(), Error> {
     if packet[0] == response_type::ERROR {
         let error = ErrorPacket::parse(&packet[1..])?;
@@ -143,3 +202,31 @@ pub fn check_error_packet(packet: &[u8], message: &str) -> Result<(), Error> {
     }
     Ok(())
 }
+
+/// Quotes MySQL identifier with backticks.
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Encodes bytes using standard base64 alphabet with padding.
+pub fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,175 @@
+//! Minimal stand-in MySQL server used in tests.
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use tokio::net::TcpListener;
+
+use crate::constants::capability_flags;
+use crate::events::event_header::EventHeader;
+use crate::packet_channel::PacketChannel;
+use crate::replica_options::ReplicaOptions;
+
+const COM_QUERY: u8 = 0x03;
+
+pub struct FakeServer {
+    listener: TcpListener,
+    pub port: u16,
+}
+
+impl FakeServer {
+    pub async fn bind() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        Self { listener, port }
+    }
+
+    pub fn options(&self) -> ReplicaOptions {
+        ReplicaOptions {
+            port: self.port,
+            hostname: String::from("127.0.0.1"),
+            ..Default::default()
+        }
+    }
+
+    /// Accepts a connection and authenticates the client with any credentials.
+    pub async fn accept(&self) -> PacketChannel {
+        let (stream, _) = self.listener.accept().await.unwrap();
+        let mut channel = PacketChannel::new(stream);
+        channel.write_packet(&handshake_packet(0), 0).await.unwrap();
+        let (_, seq_num) = channel.read_packet().await.unwrap();
+        write_ok(&mut channel, seq_num + 1).await;
+        channel
+    }
+}
+
+pub fn handshake_packet(extra_capabilities: u64) -> Vec<u8> {
+    let capabilities = capability_flags::LONG_FLAG
+        | capability_flags::PROTOCOL_41
+        | capability_flags::SECURE_CONNECTION
+        | capability_flags::PLUGIN_AUTH
+        | extra_capabilities;
+
+    let mut packet = vec![10];
+    packet.extend_from_slice(b"8.0.33\0");
+    packet.write_u32::<LittleEndian>(1).unwrap();
+    packet.extend_from_slice(b"abcdefgh\0");
+    packet
+        .write_u16::<LittleEndian>(capabilities as u16)
+        .unwrap();
+    packet.push(45);
+    packet.write_u16::<LittleEndian>(2).unwrap();
+    packet
+        .write_u16::<LittleEndian>((capabilities >> 16) as u16)
+        .unwrap();
+    packet.push(21);
+    packet.extend_from_slice(&[0; 10]);
+    packet.extend_from_slice(b"ijklmnopqrst\0");
+    packet.extend_from_slice(b"mysql_native_password\0");
+    packet
+}
+
+pub async fn write_ok(channel: &mut PacketChannel, seq_num: u8) {
+    channel
+        .write_packet(&[0, 0, 0, 2, 0, 0, 0], seq_num)
+        .await
+        .unwrap();
+}
+
+pub async fn write_eof(channel: &mut PacketChannel, seq_num: u8) {
+    channel
+        .write_packet(&[0xFE, 0, 0, 2, 0], seq_num)
+        .await
+        .unwrap();
+}
+
+/// Writes a text result set of VARCHAR columns.
+pub async fn write_result_set(channel: &mut PacketChannel, columns: &[&str], rows: &[Vec<&str>]) {
+    let mut seq_num = 1;
+    channel
+        .write_packet(&[columns.len() as u8], seq_num)
+        .await
+        .unwrap();
+
+    for column in columns {
+        seq_num += 1;
+        channel
+            .write_packet(&column_definition(column), seq_num)
+            .await
+            .unwrap();
+    }
+    seq_num += 1;
+    write_eof(channel, seq_num).await;
+
+    for row in rows {
+        let packet: Vec<u8> = row.iter().flat_map(|cell| len_enc_str(cell)).collect();
+        seq_num += 1;
+        channel.write_packet(&packet, seq_num).await.unwrap();
+    }
+    seq_num += 1;
+    write_eof(channel, seq_num).await;
+}
+
+fn column_definition(name: &str) -> Vec<u8> {
+    let mut packet = Vec::new();
+    for value in ["def", "", "", "", name, name] {
+        packet.extend(len_enc_str(value));
+    }
+    packet.push(0x0C);
+    // utf8mb4_general_ci, length, VAR_STRING type, flags, decimals
+    packet.write_u16::<LittleEndian>(45).unwrap();
+    packet.write_u32::<LittleEndian>(255).unwrap();
+    packet.push(253);
+    packet.write_u16::<LittleEndian>(0).unwrap();
+    packet.push(0);
+    packet.extend_from_slice(&[0, 0]);
+    packet
+}
+
+fn len_enc_str(value: &str) -> Vec<u8> {
+    assert!(value.len() < 0xFB);
+    let mut vec = vec![value.len() as u8];
+    vec.extend_from_slice(value.as_bytes());
+    vec
+}
+
+/// Answers the queries issued before binlog dump and returns the dump command.
+pub async fn serve_replication_setup(channel: &mut PacketChannel) -> Vec<u8> {
+    loop {
+        let (packet, _) = channel.read_packet().await.unwrap();
+        if packet[0] != COM_QUERY {
+            return packet;
+        }
+        let query = String::from_utf8(packet[1..].to_vec()).unwrap();
+        if query == "show master status" {
+            write_result_set(
+                channel,
+                &["File", "Position"],
+                &[vec!["mysql-bin.000001", "4"]],
+            )
+            .await;
+        } else if query == "SELECT @master_binlog_checksum" {
+            write_result_set(channel, &["@master_binlog_checksum"], &[vec!["NONE"]]).await;
+        } else {
+            write_ok(channel, 1).await;
+        }
+    }
+}
+
+pub async fn write_event(channel: &mut PacketChannel, header: &EventHeader, payload: &[u8]) {
+    let mut packet = vec![0];
+    packet.extend_from_slice(&header.serialize().unwrap());
+    packet.extend_from_slice(payload);
+    channel.write_packet(&packet, 1).await.unwrap();
+}
+
+/// Writes XidEvent ending at the specified position.
+pub async fn write_xid_event(channel: &mut PacketChannel, xid: u64, next_event_position: u32) {
+    let header = EventHeader {
+        timestamp: 0,
+        event_type: 16,
+        server_id: 1,
+        event_length: 27,
+        next_event_position,
+        event_flags: 0,
+    };
+    write_event(channel, &header, &xid.to_le_bytes()).await;
+}
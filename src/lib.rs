@@ -4,8 +4,6 @@
 //! ## Limitations
 //! Please note the lib currently has the following limitations:
 //! - Supports only standard auth plugins `mysql_native_password` and `caching_sha2_password`.
-//! - **Currently, the library doesn't support SSL encryption.**
-//! - **Doesn't handle split packets (16MB and more).**
 //!
 //! ## Binlog event stream replication
 //! Real-time replication client works the following way.
@@ -57,7 +55,7 @@
 //!         // You process an event here
 //!
 //!         // After you processed the event, you need to update replication position
-//!         client.commit(&header, &event);
+//!         client.commit(&header, &event)?;
 //!     }
 //!     Ok(())
 //! }
@@ -70,6 +68,20 @@
 //!    - One or many `DeleteRowsEvent` events.
 //! 3. `XidEvent` indicating commit of the transaction.
 //!
+//! `TransactionAssembler` groups the events into `Transaction` values with decoded rows.
+//! Pass the `replicate()` stream to `TransactionAssembler::stream` and commit each transaction with `BinlogClient::commit_transaction`.
+//! DDL statements are logged as `QueryEvent` with the parsed `SchemaChange` in its `schema_change` field.
+//! When the server logs column names only with `binlog_row_metadata=FULL`, keep a `SchemaHistory` loaded with `BinlogClient::load_schema` and pass it to `TransactionAssembler::with_schema_history`.
+//! The history is keyed by binlog position, not by GTID, so load it again after switching to another server.
+//!
+//! Use `BinlogClient::set_checkpoint_store` with `FileCheckpointStore` to save committed positions and resume from them after restart.
+//!
+//! `BinlogBackup` mirrors server binlog files to a local directory like `mysqlbinlog --raw --stop-never`.
+//! It writes the bytes of `BinlogClient::replicate_raw` events and resumes after the last complete event of the newest local file.
+//!
+//! To capture existing table contents, call `BinlogClient::snapshot` before `replicate()`.
+//! The snapshot rows are read in a consistent snapshot and replication continues from its binlog coordinates.
+//!
 //! **It's best practice to use GTID replication with the `from_gtid` method.** Using the approach you can correctly perform replication failover.
 //! Note that in GTID mode `from_gtid` has the following behavior:
 //! - `from_gtid(@@gtid_purged)` acts like `from_start()`
@@ -96,22 +108,69 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! `BinlogReader` accepts any `std::io::Read` stream, e.g. a memory buffer or a decompressor.
+//! Readers of seekable streams can `seek` to an event position or to a transaction with `seek_gtid` using `BinlogIndex`.
+//! `AsyncBinlogReader` reads a `tokio::io::AsyncRead` stream and returns events as a `Stream` like `BinlogClient::replicate`.
+//! `BinlogSequenceReader` reads the files of a `mysql-bin.index` file or a directory as one stream following `RotateEvent`s.
+//!
+//! `BinlogWriter` writes events into binlog files in MySQL 5.7 format readable by `BinlogReader` and `mysqlbinlog`.
+//! Event lengths, positions and CRC32 checksums are recomputed, `rotate` switches to the next file.
+//!
+//! ## Serialization
+//! Enable the `serde` feature to derive `Serialize` and `Deserialize` for events, rows and binlog positions.
+//! GTIDs are serialized as strings in the server format and `MySqlValue` as an object with `type` and `value` fields.
+//!
+//! The `debezium` feature adds `DebeziumConverter` that converts row events into JSON change events
+//! in the format of the Debezium MySQL connector.
+//!
+//! The `avro` feature adds `AvroEncoder` that encodes changed rows into Avro binary
+//! with record schemas generated from `TableMapEvent`.
 
+pub mod async_binlog_reader;
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod binlog_backup;
 pub mod binlog_client;
+pub mod binlog_index;
 pub mod binlog_options;
+pub mod binlog_position;
 pub mod binlog_reader;
+pub mod binlog_sequence_reader;
+pub mod binlog_writer;
+pub mod calendar;
+pub mod charset;
+pub mod checkpoint;
+pub mod ddl;
+#[cfg(feature = "debezium")]
+pub mod debezium;
 pub mod errors;
 pub mod events;
 pub mod metadata;
 pub mod providers;
+pub mod reader_options;
+pub mod reconnect_policy;
 pub mod replica_options;
+pub mod schema_history;
+pub mod snapshot;
 pub mod ssl_mode;
 pub mod starting_strategy;
+pub mod table_filter;
+pub mod transaction;
+pub mod writer_options;
 
 mod commands;
 mod configure;
 mod connect;
 mod constants;
 mod extensions;
+#[cfg(test)]
+mod fake_server;
+mod load_schema;
 mod packet_channel;
 mod responses;
+#[cfg(feature = "serde")]
+mod serde_string;
+mod ssl_stream;
+#[cfg(test)]
+mod test_binlogs;
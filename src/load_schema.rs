@@ -0,0 +1,138 @@
+use crate::binlog_client::BinlogClient;
+use crate::binlog_position::BinlogPosition;
+use crate::errors::Error;
+use crate::extensions::quote_identifier;
+use crate::packet_channel::PacketChannel;
+use crate::schema_history::{SchemaHistory, SchemaHistoryRecord};
+
+const SYSTEM_DATABASES: &str = "'mysql', 'information_schema', 'performance_schema', 'sys'";
+
+impl BinlogClient {
+    /// Records definitions of existing tables matching the table filter in the schema history.
+    /// The tables are recorded at the current master binlog position,
+    /// so the schema should be loaded when it is not being changed.
+    pub async fn load_schema(&self, history: &mut SchemaHistory) -> Result<(), Error> {
+        let (mut channel, _) = self.connect().await?;
+
+        let result_set = self.query(&mut channel, "show master status").await?;
+        if result_set.len() != 1 {
+            return Err(Error::String(
+                "Could not read master binlog position.".to_string(),
+            ));
+        }
+        let position = BinlogPosition::new(
+            result_set[0].cells[0].clone(),
+            result_set[0].cells[1].parse()?,
+        );
+
+        for (database, name) in self.list_tables(&mut channel).await? {
+            let query = format!(
+                "SHOW CREATE TABLE {}.{}",
+                quote_identifier(&database),
+                quote_identifier(&name)
+            );
+            let result_set = self.query(&mut channel, &query).await?;
+            // Tables with unsupported syntax are not recorded.
+            if let Some(ddl) = result_set.first().and_then(|x| x.cells.get(1)) {
+                history.record(SchemaHistoryRecord {
+                    position: position.clone(),
+                    gtid: None,
+                    database,
+                    ddl: ddl.clone(),
+                    sql_mode: 0,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets database and table names of user tables matching the table filter.
+    pub(crate) async fn list_tables(
+        &self,
+        channel: &mut PacketChannel,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let query = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME FROM information_schema.TABLES \
+            WHERE TABLE_TYPE = 'BASE TABLE' AND TABLE_SCHEMA NOT IN ({})",
+            SYSTEM_DATABASES
+        );
+        let tables = self
+            .query(channel, &query)
+            .await?
+            .into_iter()
+            .map(|x| (x.cells[0].clone(), x.cells[1].clone()))
+            .filter(|(database, table)| self.options.table_filter.matches(database, table))
+            .collect();
+        Ok(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binlog_client::BinlogClient;
+    use crate::fake_server::{write_result_set, FakeServer};
+    use crate::schema_history::SchemaHistory;
+    use crate::table_filter::{TableFilter, TablePattern};
+
+    #[tokio::test]
+    async fn load_existing_tables() {
+        let server = FakeServer::bind().await;
+        let mut options = server.options();
+        options.table_filter = TableFilter {
+            exclude: vec![TablePattern::new("shop", "tmp")],
+            ..Default::default()
+        };
+        let handle = tokio::spawn(async move {
+            let mut channel = server.accept().await;
+            let expected = [
+                "show master status",
+                "SELECT TABLE_SCHEMA",
+                "SHOW CREATE TABLE `shop`.`orders`",
+                "SHOW CREATE TABLE `shop`.`bad`",
+            ];
+            for query in expected {
+                let (packet, _) = channel.read_packet().await.unwrap();
+                let sql = String::from_utf8(packet[1..].to_vec()).unwrap();
+                assert!(sql.starts_with(query), "Unexpected query {}", sql);
+                let (columns, rows) = match query {
+                    "show master status" => (["File", "Position"], vec!["mysql-bin.000003", "154"]),
+                    "SELECT TABLE_SCHEMA" => {
+                        write_result_set(
+                            &mut channel,
+                            &["TABLE_SCHEMA", "TABLE_NAME"],
+                            &[
+                                vec!["shop", "orders"],
+                                vec!["shop", "bad"],
+                                vec!["shop", "tmp"],
+                            ],
+                        )
+                        .await;
+                        continue;
+                    }
+                    "SHOW CREATE TABLE `shop`.`orders`" => (
+                        ["Table", "Create Table"],
+                        vec!["orders", "CREATE TABLE `orders` (`id` int NOT NULL)"],
+                    ),
+                    // Unsupported syntax doesn't fail loading of other tables.
+                    _ => (
+                        ["Table", "Create Table"],
+                        vec!["bad", "CREATE TABLE `bad` (`id` int"],
+                    ),
+                };
+                write_result_set(&mut channel, &columns, &[rows]).await;
+            }
+        });
+
+        let client = BinlogClient::new(options);
+        let mut history = SchemaHistory::new();
+        client.load_schema(&mut history).await.unwrap();
+        handle.await.unwrap();
+
+        assert_eq!(1, history.records().len());
+        assert_eq!("mysql-bin.000003", history.records()[0].position.filename);
+        assert_eq!(154, history.records()[0].position.position);
+        assert!(history
+            .table(&history.records()[0].position, "shop", "orders")
+            .is_some());
+    }
+}
@@ -1,14 +1,15 @@
 use crate::constants::column_type::ColumnType;
 use crate::errors::Error;
-use crate::extensions::{read_len_enc_num, read_len_enc_str};
+use crate::extensions::{read_len_enc_num, read_len_enc_str, write_len_enc_num, write_len_enc_str};
 use crate::metadata::default_charset::DefaultCharset;
 use crate::metadata::metadata_type::MetadataType;
-use byteorder::ReadBytesExt;
-use std::io::{self, Cursor, Read};
+use byteorder::{ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Read, Write};
 
 /// Contains metadata for table columns.
 /// <a href="https://dev.mysql.com/doc/dev/mysql-server/latest/classbinary__log_1_1Table__map__event.html">See more</a>
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableMetadata {
     /// Gets signedness of numeric colums.
     pub signedness: Option<Vec<bool>>,
@@ -127,6 +128,79 @@ impl TableMetadata {
             column_visibility,
         })
     }
+
+    /// Serializes present fields in the order of metadata types.
+    pub fn serialize(&self, column_types: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut fields: Vec<(MetadataType, Vec<u8>)> = Vec::new();
+        let mut buffer = Vec::new();
+        if let Some(x) = &self.signedness {
+            let count = get_numeric_column_count(column_types)?;
+            write_bitmap_reverted(&mut Cursor::new(&mut buffer), x, count)?;
+            fields.push((MetadataType::Signedness, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.default_charset {
+            write_default_charset(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::DefaultCharset, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.column_charsets {
+            write_int_array(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::ColumnCharset, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.column_names {
+            write_string_array(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::ColumnName, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.set_string_values {
+            write_type_values(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::SetStrValue, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.enum_string_values {
+            write_type_values(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::EnumStrValue, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.geometry_types {
+            write_int_array(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::GeometryType, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.simple_primary_keys {
+            write_int_array(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((MetadataType::SimplePrimaryKey, std::mem::take(&mut buffer)));
+        }
+        if let Some(x) = &self.primary_keys_with_prefix {
+            write_int_map(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((
+                MetadataType::PrimaryKeyWithPrefix,
+                std::mem::take(&mut buffer),
+            ));
+        }
+        if let Some(x) = &self.enum_and_set_default_charset {
+            write_default_charset(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((
+                MetadataType::EnumAndSetDefaultCharset,
+                std::mem::take(&mut buffer),
+            ));
+        }
+        if let Some(x) = &self.enum_and_set_column_charsets {
+            write_int_array(&mut Cursor::new(&mut buffer), x)?;
+            fields.push((
+                MetadataType::EnumAndSetColumnCharset,
+                std::mem::take(&mut buffer),
+            ));
+        }
+        if let Some(x) = &self.column_visibility {
+            write_bitmap_reverted(&mut Cursor::new(&mut buffer), x, column_types.len())?;
+            fields.push((MetadataType::ColumnVisibility, std::mem::take(&mut buffer)));
+        }
+
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        for (metadata_type, metadata) in fields {
+            cursor.write_u8(metadata_type as u8)?;
+            write_len_enc_num(&mut cursor, metadata.len())?;
+            cursor.write_all(&metadata)?;
+        }
+        Ok(vec)
+    }
 }
 
 fn parse_int_array(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u32>, Error> {
@@ -200,6 +274,71 @@ fn read_bitmap_reverted(
     Ok(result)
 }
 
+fn write_int_array(cursor: &mut Cursor<&mut Vec<u8>>, values: &[u32]) -> Result<(), io::Error> {
+    for value in values {
+        write_len_enc_num(cursor, *value as usize)?;
+    }
+    Ok(())
+}
+
+fn write_string_array(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    values: &[String],
+) -> Result<(), io::Error> {
+    for value in values {
+        write_len_enc_str(cursor, value)?;
+    }
+    Ok(())
+}
+
+fn write_int_map(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    values: &[(u32, u32)],
+) -> Result<(), io::Error> {
+    for (key, value) in values {
+        write_len_enc_num(cursor, *key as usize)?;
+        write_len_enc_num(cursor, *value as usize)?;
+    }
+    Ok(())
+}
+
+fn write_type_values(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    values: &[Vec<String>],
+) -> Result<(), io::Error> {
+    for type_values in values {
+        write_len_enc_num(cursor, type_values.len())?;
+        write_string_array(cursor, type_values)?;
+    }
+    Ok(())
+}
+
+fn write_default_charset(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    charset: &DefaultCharset,
+) -> Result<(), io::Error> {
+    write_len_enc_num(cursor, charset.default_charset_collation as usize)?;
+    write_int_map(cursor, &charset.charset_collations)
+}
+
+/// Writes bits_number bits from the most significant bit. Missing bits are unset.
+fn write_bitmap_reverted(
+    cursor: &mut Cursor<&mut Vec<u8>>,
+    bitmap: &[bool],
+    bits_number: usize,
+) -> Result<(), io::Error> {
+    for i in 0..bits_number.div_ceil(8) {
+        let mut value = 0u8;
+        for y in 0..8 {
+            if bitmap.get((i << 3) + y) == Some(&true) && (i << 3) + y < bits_number {
+                value |= 1 << (7 - y);
+            }
+        }
+        cursor.write_u8(value)?;
+    }
+    Ok(())
+}
+
 fn get_numeric_column_count(column_types: &[u8]) -> Result<usize, Error> {
     let mut count = 0;
     for i in 0..column_types.len() {
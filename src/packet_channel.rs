@@ -2,44 +2,166 @@ use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
-use crate::constants::PACKET_HEADER_SIZE;
+use crate::constants::{MAX_BODY_LENGTH, PACKET_HEADER_SIZE};
+use crate::errors::Error;
 use crate::replica_options::ReplicaOptions;
+use crate::ssl_stream::SslStream;
+
+enum Stream {
+    Plain(TcpStream),
+    Ssl(Box<SslStream>),
+    Upgrading,
+}
+
+impl Stream {
+    async fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        match self {
+            Stream::Plain(stream) => stream.read_exact(buf).await.map(|_| ()),
+            Stream::Ssl(stream) => stream.read_exact(buf).await,
+            Stream::Upgrading => Err(io::Error::from(io::ErrorKind::NotConnected)),
+        }
+    }
+
+    async fn write_all(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        match self {
+            Stream::Plain(stream) => stream.write_all(buf).await,
+            Stream::Ssl(stream) => stream.write_all(buf).await,
+            Stream::Upgrading => Err(io::Error::from(io::ErrorKind::NotConnected)),
+        }
+    }
+}
 
 pub struct PacketChannel {
-    stream: TcpStream,
+    stream: Stream,
 }
 
 impl PacketChannel {
     pub async fn connect(options: &ReplicaOptions) -> Result<Self, io::Error> {
         let address: String = format!("{}:{}", options.hostname, options.port);
         let stream = TcpStream::connect(address).await?;
-        Ok(Self { stream })
+        Ok(Self::new(stream))
     }
 
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream: Stream::Plain(stream),
+        }
+    }
+
+    /// Reads a packet reassembling payloads split into chunks of 16MB and more.
+    /// Returns sequence number of the last chunk.
     pub async fn read_packet(&mut self) -> Result<(Vec<u8>, u8), io::Error> {
         let mut header_buffer = [0; PACKET_HEADER_SIZE];
+        let mut packet: Vec<u8> = Vec::new();
 
-        self.stream.read_exact(&mut header_buffer).await?;
-        let packet_size = (&header_buffer[0..3]).read_u24::<LittleEndian>()?;
-        let seq_num = header_buffer[3];
+        loop {
+            self.stream.read_exact(&mut header_buffer).await?;
+            let chunk_size = (&header_buffer[0..3]).read_u24::<LittleEndian>()? as usize;
+            let seq_num = header_buffer[3];
 
-        let mut packet: Vec<u8> = vec![0; packet_size as usize];
-        self.stream.read_exact(&mut packet).await?;
+            let offset = packet.len();
+            packet.resize(offset + chunk_size, 0);
+            self.stream.read_exact(&mut packet[offset..]).await?;
 
-        Ok((packet, seq_num))
+            if chunk_size < MAX_BODY_LENGTH {
+                return Ok((packet, seq_num));
+            }
+        }
     }
 
-    pub async fn write_packet(&mut self, packet: &[u8], seq_num: u8) -> Result<(), io::Error> {
-        let packet_len = packet.len() as u32;
+    /// Writes a packet splitting payloads of 16MB and more into chunks.
+    pub async fn write_packet(&mut self, packet: &[u8], mut seq_num: u8) -> Result<(), io::Error> {
+        let mut offset = 0;
+
+        // A chunk of the maximum length is always followed by another, possibly empty, chunk.
+        loop {
+            let chunk_size = MAX_BODY_LENGTH.min(packet.len() - offset);
+            self.write_chunk(&packet[offset..offset + chunk_size], seq_num)
+                .await?;
+            offset += chunk_size;
+
+            if chunk_size < MAX_BODY_LENGTH {
+                return Ok(());
+            }
+            seq_num = seq_num.wrapping_add(1);
+        }
+    }
+
+    async fn write_chunk(&mut self, chunk: &[u8], seq_num: u8) -> Result<(), io::Error> {
         let mut header = vec![];
-        header.write_u24::<LittleEndian>(packet_len)?;
+        header.write_u24::<LittleEndian>(chunk.len() as u32)?;
         byteorder::WriteBytesExt::write_u8(&mut header, seq_num)?;
         self.stream.write_all(&header).await?;
-        self.stream.write_all(packet).await?;
+        self.stream.write_all(chunk).await?;
         Ok(())
     }
 
-    pub fn upgrade_to_ssl(&mut self) {
-        unimplemented!();
+    /// Performs TLS handshake over the established connection.
+    pub async fn upgrade_to_ssl(&mut self, options: &ReplicaOptions) -> Result<(), Error> {
+        match std::mem::replace(&mut self.stream, Stream::Upgrading) {
+            Stream::Plain(stream) => {
+                let ssl_stream = SslStream::connect(stream, options).await?;
+                self.stream = Stream::Ssl(Box::new(ssl_stream));
+                Ok(())
+            }
+            stream => {
+                self.stream = stream;
+                Err(Error::String(
+                    "The channel is already encrypted".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::PacketChannel;
+    use crate::constants::MAX_BODY_LENGTH;
+
+    async fn channel_pair() -> (PacketChannel, PacketChannel) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (client, server) = tokio::join!(TcpStream::connect(address), listener.accept());
+        (
+            PacketChannel::new(client.unwrap()),
+            PacketChannel::new(server.unwrap().0),
+        )
+    }
+
+    async fn roundtrip(length: usize) {
+        let (mut client, mut server) = channel_pair().await;
+        let payload: Vec<u8> = (0..length).map(|i| i as u8).collect();
+
+        let sent = payload.clone();
+        let writer = tokio::spawn(async move { client.write_packet(&sent, 254).await.unwrap() });
+        let (packet, seq_num) = server.read_packet().await.unwrap();
+        writer.await.unwrap();
+
+        assert_eq!(payload, packet);
+        let chunks = length / MAX_BODY_LENGTH;
+        assert_eq!(254u8.wrapping_add(chunks as u8), seq_num);
+    }
+
+    #[tokio::test]
+    async fn small_packet_is_single_chunk() {
+        roundtrip(100).await;
+    }
+
+    #[tokio::test]
+    async fn empty_packet_is_single_chunk() {
+        roundtrip(0).await;
+    }
+
+    #[tokio::test]
+    async fn packet_of_max_length_is_followed_by_empty_chunk() {
+        roundtrip(MAX_BODY_LENGTH).await;
+    }
+
+    #[tokio::test]
+    async fn large_packet_is_reassembled() {
+        roundtrip(2 * MAX_BODY_LENGTH + 10).await;
     }
 }
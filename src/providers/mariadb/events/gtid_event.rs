@@ -1,11 +1,15 @@
 use crate::providers::mariadb::gtid::gtid::Gtid;
 use crate::{errors::Error, events::event_header::EventHeader};
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::Cursor;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Write};
+
+/// Flag of events followed by the commit id of a group commit.
+const FL_GROUP_COMMIT_ID: u8 = 2;
 
 /// Marks start of a new event group(transaction).
 /// <a href="https://mariadb.com/kb/en/gtid_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GtidEvent {
     /// Gets Global Transaction ID of the event group.
     pub gtid: Gtid,
@@ -24,4 +28,49 @@ impl GtidEvent {
         let gtid = Gtid::new(domain_id, header.server_id, sequence);
         Ok(Self { gtid, flags })
     }
+
+    /// Serializes the event without commit id of a group commit.
+    /// Server id is written in the event header.
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        cursor.write_u64::<LittleEndian>(self.gtid.sequence)?;
+        cursor.write_u32::<LittleEndian>(self.gtid.domain_id)?;
+        cursor.write_u8(self.flags & !FL_GROUP_COMMIT_ID)?;
+
+        // Reserved bytes
+        cursor.write_all(&[0u8; 6])?;
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::GtidEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::providers::mariadb::gtid::gtid::Gtid;
+
+    #[test]
+    fn serialize_event() {
+        let event = GtidEvent {
+            gtid: Gtid::new(1, 7, 42),
+            flags: 3,
+        };
+        let payload = event.serialize().unwrap();
+        assert_eq!(19, payload.len());
+
+        let header = EventHeader {
+            timestamp: 1689416430,
+            event_type: 162,
+            server_id: 7,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0,
+        };
+        let parsed = GtidEvent::parse(&mut Cursor::new(payload.as_slice()), &header).unwrap();
+        assert_eq!("1-7-42", parsed.gtid.to_string());
+        assert_eq!(1, parsed.flags);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::errors::Error;
 use std::fmt;
 
 /// MariaDB 10.0.2+ representation of Gtid.
@@ -21,6 +22,18 @@ impl Gtid {
             sequence,
         }
     }
+
+    /// Parses Gtid from `domain_id-server_id-sequence` string representation.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let components = value.trim().split('-').collect::<Vec<&str>>();
+        if components.len() != 3 {
+            return Err(Error::String(format!("Invalid gtid format {}", value)));
+        }
+        let domain_id: u32 = components[0].parse()?;
+        let server_id: u32 = components[1].parse()?;
+        let sequence: u64 = components[2].parse()?;
+        Ok(Self::new(domain_id, server_id, sequence))
+    }
 }
 
 impl fmt::Display for Gtid {
@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use std::fmt;
 
 /// Represents GtidList from MariaDB.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct GtidList {
     /// Gets a list of Gtids per each domain.
     pub gtids: Vec<Gtid>,
@@ -28,10 +28,8 @@ impl GtidList {
         let mut gtids = Vec::new();
 
         for gtid in gtid_list {
-            let components = gtid.split('-').collect::<Vec<&str>>();
-            let domain_id: u32 = components[0].parse()?;
-            let server_id: u32 = components[1].parse()?;
-            let sequence: u64 = components[2].parse()?;
+            let gtid = Gtid::parse(gtid)?;
+            let domain_id = gtid.domain_id;
 
             if domain_map.contains(&domain_id) {
                 return Err(Error::String(format!(
@@ -41,7 +39,7 @@ impl GtidList {
                 domain_map.insert(domain_id);
             }
 
-            gtids.push(Gtid::new(domain_id, server_id, sequence));
+            gtids.push(gtid);
         }
 
         Ok(Self { gtids })
@@ -58,6 +56,13 @@ impl GtidList {
         self.gtids.push(gtid);
         true
     }
+
+    /// Checks whether the gtid value is at or before the position of its domain.
+    pub fn contains(&self, gtid: &Gtid) -> bool {
+        self.gtids
+            .iter()
+            .any(|x| x.domain_id == gtid.domain_id && gtid.sequence <= x.sequence)
+    }
 }
 
 impl fmt::Display for GtidList {
@@ -1,11 +1,12 @@
 use crate::providers::mysql::gtid::uuid::Uuid;
 use crate::{errors::Error, providers::mysql::gtid::gtid::Gtid};
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Cursor, Read};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Cursor, Read, Write};
 
 /// Marks start of a new event group(transaction).
 /// <a href="https://mariadb.com/kb/en/gtid_event/">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GtidEvent {
     /// Gets Global Transaction ID of the event group.
     pub gtid: Gtid,
@@ -28,4 +29,38 @@ impl GtidEvent {
         let gtid = Gtid::new(source_id, transaction_id);
         Ok(Self { gtid, flags })
     }
+
+    /// Serializes the event in MySQL 5.6 format.
+    /// Logical timestamps of MySQL 5.7+ are not written as they are not parsed.
+    pub fn serialize(&self) -> Result<Vec<u8>, io::Error> {
+        let mut vec = Vec::new();
+        let mut cursor = Cursor::new(&mut vec);
+        cursor.write_u8(self.flags)?;
+        cursor.write_all(&self.gtid.source_id.data)?;
+        cursor.write_u64::<LittleEndian>(self.gtid.transaction_id)?;
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::GtidEvent;
+    use crate::providers::mysql::gtid::gtid::Gtid;
+    use crate::providers::mysql::gtid::uuid::Uuid;
+
+    #[test]
+    fn serialize_event() {
+        let event = GtidEvent {
+            gtid: Gtid::new(Uuid::new([7; 16]), 42),
+            flags: 1,
+        };
+        let payload = event.serialize().unwrap();
+        assert_eq!(25, payload.len());
+
+        let parsed = GtidEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap();
+        assert_eq!(event.gtid.to_string(), parsed.gtid.to_string());
+        assert_eq!(1, parsed.flags);
+    }
 }
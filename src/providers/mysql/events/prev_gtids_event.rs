@@ -6,7 +6,8 @@ use byteorder::{LittleEndian, ReadBytesExt};
 use std::io::{Cursor, Read};
 
 /// Used to record the gtid_executed of previous binlog files.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PreviousGtidsEvent {
     /// Gets GtidSet of previous files.
     pub gtid_set: GtidSet,
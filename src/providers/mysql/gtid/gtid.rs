@@ -1,3 +1,4 @@
+use crate::errors::Error;
 use crate::providers::mysql::gtid::uuid::Uuid;
 use std::fmt;
 
@@ -18,6 +19,17 @@ impl Gtid {
             transaction_id,
         }
     }
+
+    /// Parses Gtid from `source_id:transaction_id` string representation.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        match value.trim().split_once(':') {
+            Some((source_id, transaction_id)) => Ok(Self::new(
+                Uuid::parse(source_id.to_string())?,
+                transaction_id.parse()?,
+            )),
+            None => Err(Error::String(format!("Invalid gtid format {}", value))),
+        }
+    }
 }
 
 impl fmt::Display for Gtid {
@@ -10,7 +10,7 @@ const UUID_LENGTH: usize = 36;
 
 /// Represents GtidSet from MySQL 5.6 and above.
 /// <a href="https://dev.mysql.com/doc/refman/8.0/en/replication-gtids-concepts.html">See more</a>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct GtidSet {
     /// Gets a list of UuidSet parts in the GtidSet.
     pub uuid_sets: HashMap<String, UuidSet>,
@@ -65,6 +65,16 @@ impl GtidSet {
 
         uuid_set.add_gtid(gtid)
     }
+
+    /// Checks whether the gtid value belongs to the GtidSet.
+    pub fn contains(&self, gtid: &Gtid) -> bool {
+        self.uuid_sets.values().any(|x| {
+            x.source_id.data == gtid.source_id.data
+                && x.intervals
+                    .iter()
+                    .any(|i| i.start <= gtid.transaction_id && gtid.transaction_id <= i.end)
+        })
+    }
 }
 
 impl Default for GtidSet {
@@ -295,4 +305,14 @@ mod tests {
             gtid_set.to_string()
         );
     }
+
+    #[test]
+    fn contains_gtids_of_intervals() {
+        let gtid_set = GtidSet::parse(&format!("{}:1-3:11", SERVER_UUID1)).unwrap();
+
+        assert!(gtid_set.contains(&Gtid::new(create_uuid1(), 2)));
+        assert!(gtid_set.contains(&Gtid::new(create_uuid1(), 11)));
+        assert!(!gtid_set.contains(&Gtid::new(create_uuid1(), 4)));
+        assert!(!gtid_set.contains(&Gtid::new(create_uuid2(), 2)));
+    }
 }
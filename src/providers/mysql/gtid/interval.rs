@@ -1,7 +1,8 @@
 use std::fmt;
 
 /// Represents contiguous transaction interval in GtidSet.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interval {
     /// Gets first transaction id in the interval.
     pub start: u64,
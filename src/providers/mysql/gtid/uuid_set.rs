@@ -5,7 +5,8 @@ use crate::providers::mysql::gtid::uuid::Uuid;
 use std::fmt;
 
 /// Represents replication state for a specific server.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UuidSet {
     /// Gets server uuid of the UuidSet.
     pub source_id: Uuid,
@@ -0,0 +1,15 @@
+use crate::table_filter::TableFilter;
+
+/// Settings used to read binlog files.
+#[derive(Debug, Default)]
+pub struct ReaderOptions {
+    /// Name of the binlog file. Reported in checksum errors.
+    pub filename: String,
+
+    /// Defines whether CRC32 checksums of events are verified. Defaults to false.
+    pub verify_checksum: bool,
+
+    /// Defines tables whose row events are decoded.
+    /// Row events of other tables are returned as SkippedRowsEvent. Defaults to all tables.
+    pub table_filter: TableFilter,
+}
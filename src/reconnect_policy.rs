@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+/// Defines how the client reconnects when the replication connection is lost.
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    /// Maximum number of consecutive reconnect attempts. Defaults to 10.
+    pub max_attempts: u32,
+
+    /// Delay before the first reconnect attempt.
+    /// The delay is doubled after each failed attempt. Defaults to 1 second.
+    pub backoff: Duration,
+
+    /// Upper bound of the delay between reconnect attempts. Defaults to 60 seconds.
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> ReconnectPolicy {
+        ReconnectPolicy {
+            max_attempts: 10,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
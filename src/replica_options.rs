@@ -1,8 +1,11 @@
 use crate::binlog_options::BinlogOptions;
+use crate::reconnect_policy::ReconnectPolicy;
 use crate::ssl_mode::SslMode;
+use crate::table_filter::TableFilter;
 use std::time::Duration;
 
 /// Settings used to connect to MySQL/MariaDB.
+#[derive(Clone)]
 pub struct ReplicaOptions {
     /// Port number to connect. Defaults to 3306.
     pub port: u16,
@@ -13,6 +16,16 @@ pub struct ReplicaOptions {
     /// Defines whether SSL/TLS must be used. Defaults to SslMode.DISABLED.
     pub ssl_mode: SslMode,
 
+    /// Path to the PEM file with trusted certificate authorities.
+    /// Required for SslMode.REQUIRE_VERIFY_CA and SslMode.REQUIRE_VERIFY_FULL.
+    pub ssl_ca: Option<String>,
+
+    /// Path to the PEM file with the client certificate used for client authentication.
+    pub ssl_cert: Option<String>,
+
+    /// Path to the PEM file with the private key of the client certificate.
+    pub ssl_key: Option<String>,
+
     /// A database user which is used to register as a database slave.
     /// The user needs to have <c>REPLICATION SLAVE</c>, <c>REPLICATION CLIENT</c> privileges.
     pub username: String,
@@ -36,6 +49,19 @@ pub struct ReplicaOptions {
     /// Defaults to 30 seconds.
     pub heartbeat_interval: Duration,
 
+    /// Defines whether the client reconnects and resumes from the committed position
+    /// when the connection is lost or no heartbeat is received in time.
+    /// Defaults to None (the event stream fails).
+    pub reconnect: Option<ReconnectPolicy>,
+
+    /// Defines whether CRC32 checksums of received events are verified.
+    /// Defaults to false.
+    pub verify_checksum: bool,
+
+    /// Defines tables whose row events are decoded.
+    /// Row events of other tables are returned as SkippedRowsEvent. Defaults to all tables.
+    pub table_filter: TableFilter,
+
     /// Defines the binlog coordinates that replication should start from.
     /// Defaults to BinlogOptions.FromEnd()
     pub binlog: BinlogOptions,
@@ -47,12 +73,18 @@ impl Default for ReplicaOptions {
             port: 3306,
             hostname: String::from("localhost"),
             ssl_mode: SslMode::Disabled,
+            ssl_ca: None,
+            ssl_cert: None,
+            ssl_key: None,
             username: String::new(),
             password: String::new(),
             database: None,
             server_id: 65535,
             blocking: true,
             heartbeat_interval: Duration::from_secs(30),
+            reconnect: None,
+            verify_checksum: false,
+            table_filter: TableFilter::default(),
             binlog: BinlogOptions::from_end(),
         }
     }
@@ -0,0 +1,45 @@
+use crate::{
+    errors::Error,
+    extensions::{read_len_enc_num, read_len_enc_str},
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::Cursor;
+
+/// Describes a column of a result set.
+/// <a href="https://mariadb.com/kb/en/library/resultset/#column-definition-packet">See more</a>
+#[derive(Debug)]
+pub struct ColumnDefinitionPacket {
+    pub name: String,
+    pub collation: u16,
+    pub column_length: u32,
+    pub column_type: u8,
+    pub flags: u16,
+}
+
+impl ColumnDefinitionPacket {
+    pub fn parse(packet: &[u8]) -> Result<Self, Error> {
+        let mut cursor = Cursor::new(packet);
+
+        // Catalog, schema, table alias, table
+        for _ in 0..4 {
+            read_len_enc_str(&mut cursor)?;
+        }
+        let name = read_len_enc_str(&mut cursor)?;
+        let _original_name = read_len_enc_str(&mut cursor)?;
+
+        // Length of fixed fields
+        read_len_enc_num(&mut cursor)?;
+        let collation = cursor.read_u16::<LittleEndian>()?;
+        let column_length = cursor.read_u32::<LittleEndian>()?;
+        let column_type = cursor.read_u8()?;
+        let flags = cursor.read_u16::<LittleEndian>()?;
+
+        Ok(Self {
+            name,
+            collation,
+            column_length,
+            column_type,
+            flags,
+        })
+    }
+}
@@ -1,4 +1,5 @@
 pub mod auth_switch_packet;
+pub mod column_definition_packet;
 pub mod end_of_file_packet;
 pub mod error_packet;
 pub mod handshake_packet;
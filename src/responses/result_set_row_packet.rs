@@ -1,5 +1,8 @@
-use crate::{errors::Error, extensions::read_len_enc_str};
-use std::io::Cursor;
+use crate::{
+    errors::Error,
+    extensions::{read_len_enc_num, read_len_enc_str},
+};
+use std::io::{Cursor, Read};
 
 /// Returned in response to a QueryCommand.
 /// <a href="https://mariadb.com/kb/en/library/resultset/">See more</a>
@@ -22,3 +25,26 @@ impl ResultSetRowPacket {
         Ok(Self { cells })
     }
 }
+
+/// Parses text row keeping NULL values and binary strings.
+pub fn parse_row_values(packet: &[u8]) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let mut cursor = Cursor::new(packet);
+
+    let len = cursor.get_ref().len() as u64;
+    let mut values = Vec::new();
+
+    while cursor.position() < len {
+        // 0xFB is NULL value
+        if packet[cursor.position() as usize] == 0xFB {
+            cursor.set_position(cursor.position() + 1);
+            values.push(None);
+            continue;
+        }
+        let length = read_len_enc_num(&mut cursor)?;
+        let mut value = vec![0; length];
+        cursor.read_exact(&mut value)?;
+        values.push(Some(value));
+    }
+
+    Ok(values)
+}
@@ -0,0 +1,606 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::binlog_position::BinlogPosition;
+use crate::ddl::ddl_parser::parse_ddl_with_sql_mode;
+use crate::ddl::schema_change::{
+    AlterOperation, ColumnDefinition, ColumnPosition, IndexDefinition, IndexKind, SchemaChange,
+    TableName,
+};
+use crate::errors::Error;
+use crate::events::table_map_event::TableMapEvent;
+use crate::metadata::table_metadata::TableMetadata;
+
+/// Data types that have signedness in TableMetadata.
+const NUMERIC_TYPES: &[&str] = &[
+    "tinyint",
+    "bool",
+    "boolean",
+    "smallint",
+    "mediumint",
+    "int",
+    "integer",
+    "bigint",
+    "float",
+    "double",
+    "double precision",
+    "real",
+    "decimal",
+    "numeric",
+    "dec",
+    "fixed",
+];
+
+/// DDL statement stored in the schema history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaHistoryRecord {
+    /// Gets position of the QueryEvent or the position the initial schema was loaded at.
+    pub position: BinlogPosition,
+
+    /// Gets GTID of the transaction if gtid mode is enabled.
+    /// Stored for reference only, the history is looked up by position.
+    pub gtid: Option<String>,
+
+    /// Gets default database of the statement.
+    pub database: String,
+
+    /// Gets DDL statement.
+    pub ddl: String,
+
+    /// Gets sql_mode the statement was logged with. Defines how string literals are parsed.
+    pub sql_mode: u64,
+}
+
+/// Table definition in effect at some position of the binary log.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSchema {
+    /// Gets columns in table order.
+    pub columns: Vec<ColumnDefinition>,
+
+    /// Gets indexes of the table.
+    pub indexes: Vec<IndexDefinition>,
+}
+
+/// Tracks table definitions over the binary log so that row events can be decoded
+/// with the schema in effect at their position.
+/// The history is built from DDL statements and optionally persisted to a local file.
+/// Use `BinlogClient::load_schema` to record the initial schema of existing tables.
+///
+/// Table definitions are keyed by binlog position only, so a history can't be looked up by GTID.
+/// In GTID mode TransactionAssembler uses positions of event headers, which works
+/// while replicating from the same server. Positions of another server don't match,
+/// so after switching to it the schema must be loaded again.
+#[derive(Debug, Default)]
+pub struct SchemaHistory {
+    path: Option<PathBuf>,
+    records: Vec<SchemaHistoryRecord>,
+    tables: HashMap<TableName, Vec<(BinlogPosition, Option<TableSchema>)>>,
+}
+
+impl SchemaHistory {
+    /// Creates in-memory schema history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens schema history stored in the file. The file is created on the first record.
+    /// Lines that can't be parsed are skipped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut history = Self::new();
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines() {
+                if let Ok(record) = parse_record(line) {
+                    history.apply(record);
+                }
+            }
+        }
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// Gets recorded DDL statements in binlog order.
+    pub fn records(&self) -> &[SchemaHistoryRecord] {
+        &self.records
+    }
+
+    /// Records DDL statement and saves it to the history file.
+    /// Statements that don't change tables or can't be parsed are ignored, as are statements
+    /// at positions recorded before, so replication can be safely restarted from an earlier position.
+    /// Returns whether the statement was recorded. Fails only if the file can't be written.
+    pub fn record(&mut self, record: SchemaHistoryRecord) -> Result<bool, Error> {
+        if let Some(last) = self.records.last() {
+            let recorded = self
+                .records
+                .iter()
+                .rev()
+                .take_while(|x| x.position == record.position)
+                .any(|x| x.ddl == record.ddl);
+            if record.position < last.position || recorded {
+                return Ok(false);
+            }
+        }
+
+        let line = format_record(&record);
+        if !self.apply(record) {
+            return Ok(false);
+        }
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(line.as_bytes())?;
+            file.flush()?;
+        }
+        Ok(true)
+    }
+
+    /// Gets definition of the table in effect at the position.
+    /// Returns None if the table was dropped or is not known at the position.
+    pub fn table(
+        &self,
+        position: &BinlogPosition,
+        database: &str,
+        table: &str,
+    ) -> Option<&TableSchema> {
+        let versions = self.tables.get(&TableName::new(database, table))?;
+        let index = versions.partition_point(|(x, _)| x <= position);
+        match index {
+            0 => None,
+            _ => versions[index - 1].1.as_ref(),
+        }
+    }
+
+    /// Fills column names, signedness and ENUM/SET labels missing in TableMapEvent
+    /// (binlog_row_metadata=MINIMAL) from the schema in effect at the position.
+    /// Returns false if the table is unknown or its column count doesn't match.
+    pub fn fill_metadata(&self, position: &BinlogPosition, table_map: &mut TableMapEvent) -> bool {
+        let schema = match self.table(position, &table_map.database_name, &table_map.table_name) {
+            Some(x) if x.columns.len() == table_map.column_types.len() => x,
+            _ => return false,
+        };
+
+        let metadata = table_map
+            .table_metadata
+            .get_or_insert_with(TableMetadata::default);
+        if metadata.column_names.is_none() {
+            metadata.column_names = Some(schema.columns.iter().map(|x| x.name.clone()).collect());
+        }
+        if metadata.signedness.is_none() {
+            let signedness = schema
+                .columns
+                .iter()
+                .filter(|x| NUMERIC_TYPES.contains(&x.type_name()))
+                .map(|x| x.unsigned)
+                .collect();
+            metadata.signedness = Some(signedness);
+        }
+        let values = |type_name: &str| -> Vec<Vec<String>> {
+            schema
+                .columns
+                .iter()
+                .filter(|x| x.type_name() == type_name)
+                .map(|x| x.type_values())
+                .collect()
+        };
+        if metadata.enum_string_values.is_none() {
+            metadata.enum_string_values = Some(values("enum"));
+        }
+        if metadata.set_string_values.is_none() {
+            metadata.set_string_values = Some(values("set"));
+        }
+        true
+    }
+
+    /// Applies the statement. Returns false if it's not a DDL statement or has unsupported syntax.
+    fn apply(&mut self, record: SchemaHistoryRecord) -> bool {
+        let change = match parse_ddl_with_sql_mode(&record.ddl, &record.database, record.sql_mode) {
+            Ok(Some(x)) => x,
+            Ok(None) | Err(_) => return false,
+        };
+
+        let position = &record.position;
+        match change {
+            SchemaChange::CreateDatabase { .. } | SchemaChange::TruncateTable { .. } => {}
+            SchemaChange::DropDatabase { database } => {
+                let tables: Vec<TableName> = self
+                    .tables
+                    .keys()
+                    .filter(|x| x.database == database)
+                    .cloned()
+                    .collect();
+                for table in tables {
+                    self.set_table(position, table, None);
+                }
+            }
+            SchemaChange::CreateTable {
+                table,
+                columns,
+                indexes,
+                like,
+            } => {
+                let schema = match like {
+                    Some(source) => self.latest(&source),
+                    None => Some(TableSchema { columns, indexes }),
+                };
+                self.set_table(position, table, schema);
+            }
+            SchemaChange::AlterTable { table, operations } => {
+                if let Some(mut schema) = self.latest(&table) {
+                    let mut name = table.clone();
+                    for operation in &operations {
+                        match operation {
+                            AlterOperation::RenameTable { new_table } => name = new_table.clone(),
+                            _ => schema.alter(operation),
+                        }
+                    }
+                    if name != table {
+                        self.set_table(position, table, None);
+                    }
+                    self.set_table(position, name, Some(schema));
+                }
+            }
+            SchemaChange::DropTable { tables } => {
+                for table in tables {
+                    self.set_table(position, table, None);
+                }
+            }
+            SchemaChange::RenameTable { renames } => {
+                for (from, to) in renames {
+                    let schema = self.latest(&from);
+                    self.set_table(position, from, None);
+                    self.set_table(position, to, schema);
+                }
+            }
+        }
+        self.records.push(record);
+        true
+    }
+
+    fn latest(&self, table: &TableName) -> Option<TableSchema> {
+        self.tables
+            .get(table)
+            .and_then(|x| x.last())
+            .and_then(|(_, schema)| schema.clone())
+    }
+
+    fn set_table(
+        &mut self,
+        position: &BinlogPosition,
+        table: TableName,
+        schema: Option<TableSchema>,
+    ) {
+        let versions = self.tables.entry(table).or_default();
+        // Statements of the same position (initial schema) replace each other.
+        if let Some((last, _)) = versions.last() {
+            if last == position {
+                versions.pop();
+            }
+        }
+        versions.push((position.clone(), schema));
+    }
+}
+
+impl TableSchema {
+    /// Applies ALTER TABLE operation. Column names are case insensitive.
+    fn alter(&mut self, operation: &AlterOperation) {
+        match operation {
+            AlterOperation::AddColumn { column, position } => {
+                self.place(None, column.clone(), position.as_ref());
+            }
+            AlterOperation::DropColumn { name } => {
+                if let Some(i) = self.column(name) {
+                    self.columns.remove(i);
+                }
+                for index in &mut self.indexes {
+                    index.columns.retain(|x| !x.eq_ignore_ascii_case(name));
+                }
+                self.indexes.retain(|x| !x.columns.is_empty());
+            }
+            AlterOperation::ModifyColumn { column, position } => {
+                let index = self.column(&column.name);
+                self.place(index, column.clone(), position.as_ref());
+            }
+            AlterOperation::ChangeColumn {
+                old_name,
+                column,
+                position,
+            } => {
+                let index = self.column(old_name);
+                self.place(index, column.clone(), position.as_ref());
+                self.rename_index_columns(old_name, &column.name);
+            }
+            AlterOperation::RenameColumn { old_name, new_name } => {
+                if let Some(i) = self.column(old_name) {
+                    self.columns[i].name = new_name.clone();
+                }
+                self.rename_index_columns(old_name, new_name);
+            }
+            AlterOperation::AlterColumnDefault { name, default } => {
+                if let Some(i) = self.column(name) {
+                    self.columns[i].default = default.clone();
+                }
+            }
+            AlterOperation::AddIndex(index) => self.indexes.push(index.clone()),
+            AlterOperation::DropIndex { name } => {
+                self.indexes
+                    .retain(|x| !matches!(&x.name, Some(x) if x.eq_ignore_ascii_case(name)));
+            }
+            AlterOperation::DropPrimaryKey => self.indexes.retain(|x| x.kind != IndexKind::Primary),
+            AlterOperation::DropForeignKey { name } => self.indexes.retain(|x| {
+                x.kind != IndexKind::ForeignKey
+                    || !matches!(&x.name, Some(x) if x.eq_ignore_ascii_case(name))
+            }),
+            AlterOperation::RenameIndex { old_name, new_name } => {
+                for index in &mut self.indexes {
+                    if matches!(&index.name, Some(x) if x.eq_ignore_ascii_case(old_name)) {
+                        index.name = Some(new_name.clone());
+                    }
+                }
+            }
+            AlterOperation::RenameTable { .. } | AlterOperation::Other(_) => {}
+        }
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|x| x.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Replaces the column at the index or adds a new one, moving it to the position.
+    fn place(
+        &mut self,
+        index: Option<usize>,
+        column: ColumnDefinition,
+        position: Option<&ColumnPosition>,
+    ) {
+        let mut target = index.unwrap_or(self.columns.len());
+        if let Some(i) = index {
+            self.columns.remove(i);
+        }
+        match position {
+            Some(ColumnPosition::First) => target = 0,
+            Some(ColumnPosition::After(name)) => {
+                if let Some(i) = self.column(name) {
+                    target = i + 1;
+                }
+            }
+            None => {}
+        }
+        self.columns.insert(target.min(self.columns.len()), column);
+    }
+
+    fn rename_index_columns(&mut self, old_name: &str, new_name: &str) {
+        for index in &mut self.indexes {
+            for column in &mut index.columns {
+                if column.eq_ignore_ascii_case(old_name) {
+                    *column = new_name.to_string();
+                }
+            }
+        }
+    }
+}
+
+/// Formats record as a tab separated line.
+fn format_record(record: &SchemaHistoryRecord) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\n",
+        escape(&record.position.filename),
+        record.position.position,
+        escape(record.gtid.as_deref().unwrap_or("")),
+        escape(&record.database),
+        escape(&record.ddl),
+        record.sql_mode
+    )
+}
+
+/// Records written without sql_mode have 5 fields.
+fn parse_record(line: &str) -> Result<SchemaHistoryRecord, Error> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() != 5 && fields.len() != 6 {
+        return Err(Error::String(format!(
+            "Invalid schema history record: {}",
+            line
+        )));
+    }
+    let gtid = unescape(fields[2]);
+    Ok(SchemaHistoryRecord {
+        position: BinlogPosition::new(unescape(fields[0]), fields[1].parse()?),
+        gtid: if gtid.is_empty() { None } else { Some(gtid) },
+        database: unescape(fields[3]),
+        ddl: unescape(fields[4]),
+        sql_mode: match fields.get(5) {
+            Some(x) => x.parse()?,
+            None => 0,
+        },
+    })
+}
+
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SchemaHistory, SchemaHistoryRecord};
+    use crate::binlog_position::BinlogPosition;
+    use crate::constants::column_type::ColumnType;
+    use crate::events::table_map_event::TableMapEvent;
+
+    fn record(position: u32, ddl: &str) -> SchemaHistoryRecord {
+        SchemaHistoryRecord {
+            position: BinlogPosition::new(String::from("mysql-bin.000001"), position),
+            gtid: None,
+            database: String::from("shop"),
+            ddl: ddl.to_string(),
+            sql_mode: 0,
+        }
+    }
+
+    fn position(position: u32) -> BinlogPosition {
+        BinlogPosition::new(String::from("mysql-bin.000001"), position)
+    }
+
+    fn column_names(history: &SchemaHistory, position: u32) -> Option<Vec<String>> {
+        let schema = history.table(&self::position(position), "shop", "orders")?;
+        Some(schema.columns.iter().map(|x| x.name.clone()).collect())
+    }
+
+    #[test]
+    fn track_table_versions() {
+        let mut history = SchemaHistory::new();
+        assert!(history
+            .record(record(
+                100,
+                "CREATE TABLE orders (id int, total decimal(10,2))"
+            ))
+            .unwrap());
+        assert!(history
+            .record(record(
+                200,
+                "ALTER TABLE orders ADD status enum('new','paid') AFTER id, DROP total"
+            ))
+            .unwrap());
+        assert!(!history.record(record(250, "BEGIN")).unwrap());
+        assert!(history
+            .record(record(300, "RENAME TABLE orders TO archive"))
+            .unwrap());
+        // Replayed statement is ignored.
+        assert!(!history
+            .record(record(200, "ALTER TABLE orders DROP id"))
+            .unwrap());
+
+        assert_eq!(None, column_names(&history, 50));
+        assert_eq!(
+            Some(vec!["id".to_string(), "total".to_string()]),
+            column_names(&history, 150)
+        );
+        assert_eq!(
+            Some(vec!["id".to_string(), "status".to_string()]),
+            column_names(&history, 250)
+        );
+        assert_eq!(None, column_names(&history, 350));
+        assert!(history.table(&position(350), "shop", "archive").is_some());
+        assert_eq!(3, history.records().len());
+    }
+
+    #[test]
+    fn fill_missing_metadata() {
+        let mut history = SchemaHistory::new();
+        history
+            .record(record(
+                100,
+                "CREATE TABLE orders (id int unsigned, status enum('new','paid'))",
+            ))
+            .unwrap();
+
+        let mut table_map = TableMapEvent {
+            table_id: 1,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![ColumnType::Long as u8, ColumnType::String as u8],
+            column_metadata: vec![0, (ColumnType::Enum as u16) << 8 | 1],
+            null_bitmap: vec![true, true],
+            table_metadata: None,
+        };
+        assert!(history.fill_metadata(&position(150), &mut table_map));
+        let metadata = table_map.table_metadata.unwrap();
+        assert_eq!(
+            Some(vec!["id".to_string(), "status".to_string()]),
+            metadata.column_names
+        );
+        assert_eq!(Some(vec![true]), metadata.signedness);
+        assert_eq!(
+            Some(vec![vec!["new".to_string(), "paid".to_string()]]),
+            metadata.enum_string_values
+        );
+    }
+
+    #[test]
+    fn skip_unparsable_statements() {
+        let mut history = SchemaHistory::new();
+        assert!(!history
+            .record(record(100, "CREATE TABLE orders (id"))
+            .unwrap());
+        assert!(history
+            .record(record(200, "CREATE TABLE orders (id int)"))
+            .unwrap());
+        assert_eq!(1, history.records().len());
+
+        let path =
+            std::env::temp_dir().join(format!("schema_history_invalid_{}.log", std::process::id()));
+        std::fs::write(
+            &path,
+            "invalid\n\
+            mysql-bin.000001\tx\t\tshop\tCREATE TABLE a (id int)\n\
+            mysql-bin.000001\t100\t\tshop\tCREATE TABLE orders (id\n\
+            mysql-bin.000001\t200\t\tshop\tCREATE TABLE orders (id int)\n",
+        )
+        .unwrap();
+        let history = SchemaHistory::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(1, history.records().len());
+        assert_eq!(Some(vec!["id".to_string()]), column_names(&history, 200));
+    }
+
+    #[test]
+    fn persist_history() {
+        let path = std::env::temp_dir().join(format!("schema_history_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut history = SchemaHistory::open(&path).unwrap();
+        history
+            .record(record(
+                100,
+                "CREATE TABLE orders (\n\tid int COMMENT 'a\\\\b'\n)",
+            ))
+            .unwrap();
+        // Statement logged with NO_BACKSLASH_ESCAPES
+        let mut archive = record(200, "CREATE TABLE archive (id int COMMENT 'C:\\')");
+        archive.sql_mode = 1 << 20;
+        assert!(history.record(archive).unwrap());
+
+        let history = SchemaHistory::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(2, history.records().len());
+        assert_eq!(1 << 20, history.records()[1].sql_mode);
+        assert!(history.table(&position(200), "shop", "archive").is_some());
+        assert_eq!(
+            "CREATE TABLE orders (\n\tid int COMMENT 'a\\\\b'\n)",
+            history.records()[0].ddl
+        );
+        assert_eq!(Some(vec!["id".to_string()]), column_names(&history, 100));
+    }
+}
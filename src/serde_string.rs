@@ -0,0 +1,100 @@
+use crate::providers::mariadb::gtid::gtid::Gtid as MariaDbGtid;
+use crate::providers::mariadb::gtid::gtid_list::GtidList;
+use crate::providers::mysql::gtid::gtid::Gtid as MySqlGtid;
+use crate::providers::mysql::gtid::gtid_set::GtidSet;
+use crate::providers::mysql::gtid::uuid::Uuid;
+
+/// Serializes a type as its Display string and deserializes it with the parse function.
+macro_rules! impl_serde_string {
+    ($type:ty, $parse:expr) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                $parse(&value).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+// GTIDs are serialized in the canonical form used by the server, e.g. in @@gtid_executed.
+impl_serde_string!(Uuid, |x: &str| Uuid::parse(x.to_string()));
+impl_serde_string!(MySqlGtid, MySqlGtid::parse);
+impl_serde_string!(GtidSet, GtidSet::parse);
+impl_serde_string!(MariaDbGtid, MariaDbGtid::parse);
+impl_serde_string!(GtidList, GtidList::parse);
+
+#[cfg(test)]
+mod tests {
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::row_events::mysql_value::{Date, MySqlValue};
+    use crate::events::row_events::row_data::RowData;
+    use crate::events::row_events::write_rows_event::WriteRowsEvent;
+    use crate::providers::mariadb::gtid::gtid_list::GtidList;
+    use crate::providers::mysql::events::prev_gtids_event::PreviousGtidsEvent;
+    use crate::providers::mysql::gtid::gtid_set::GtidSet;
+
+    #[test]
+    fn gtids_serialized_as_strings() {
+        let gtid_set = "9b1ad0d4-19f4-11ee-9d6b-0242ac120002:1-10:15";
+        let event = BinlogEvent::MySqlPrevGtidsEvent(PreviousGtidsEvent {
+            gtid_set: GtidSet::parse(gtid_set).unwrap(),
+        });
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            format!(r#"{{"MySqlPrevGtidsEvent":{{"gtid_set":"{}"}}}}"#, gtid_set),
+            json
+        );
+
+        let event: BinlogEvent = serde_json::from_str(&json).unwrap();
+        match event {
+            BinlogEvent::MySqlPrevGtidsEvent(x) => assert_eq!(gtid_set, x.gtid_set.to_string()),
+            _ => panic!("Unexpected event {:?}", event),
+        }
+
+        let gtid_list: GtidList = serde_json::from_str(r#""0-1-270,1-2-55""#).unwrap();
+        assert_eq!("0-1-270,1-2-55", gtid_list.to_string());
+        assert!(serde_json::from_str::<GtidList>(r#""0-1""#).is_err());
+    }
+
+    #[test]
+    fn mysql_values_are_tagged() {
+        let value = MySqlValue::Date(Date {
+            year: 2023,
+            month: 7,
+            day: 15,
+        });
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            r#"{"type":"Date","value":{"year":2023,"month":7,"day":15}}"#,
+            json
+        );
+
+        let event = BinlogEvent::WriteRowsEvent(WriteRowsEvent {
+            table_id: 1,
+            flags: 0,
+            columns_number: 2,
+            columns_present: vec![true, true],
+            rows: vec![RowData::new(vec![
+                Some(MySqlValue::Int(1)),
+                Some(MySqlValue::String(String::from("Alice"))),
+            ])],
+        });
+        let json = serde_json::to_string(&event).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            BinlogEvent::WriteRowsEvent(x) => {
+                assert_eq!(Some(MySqlValue::Int(1)), x.rows[0].cells[0]);
+                assert_eq!(
+                    Some(MySqlValue::String(String::from("Alice"))),
+                    x.rows[0].cells[1]
+                );
+            }
+            _ => panic!("Unexpected event"),
+        }
+    }
+}
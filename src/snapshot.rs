@@ -0,0 +1,469 @@
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use async_stream::stream;
+use futures_core::Stream;
+
+use crate::binlog_client::BinlogClient;
+use crate::binlog_options::BinlogOptions;
+use crate::calendar::seconds_from_civil;
+use crate::commands::query_command::QueryCommand;
+use crate::constants::column_type::ColumnType;
+use crate::constants::database_provider::DatabaseProvider;
+use crate::errors::Error;
+use crate::events::row_events::geometry::Geometry;
+use crate::events::row_events::mysql_value::{Date, DateTime, MySqlValue, Time};
+use crate::events::row_events::typed_row::{ColumnValue, TypedColumn, TypedRow};
+use crate::extensions::{
+    check_error_packet, quote_identifier, read_bitmap_big_endian, read_len_enc_num,
+};
+use crate::packet_channel::PacketChannel;
+use crate::providers::mariadb::gtid::gtid_list::GtidList;
+use crate::providers::mysql::gtid::gtid_set::GtidSet;
+use crate::responses::column_definition_packet::ColumnDefinitionPacket;
+use crate::responses::response_type;
+use crate::responses::result_set_row_packet::parse_row_values;
+use crate::starting_strategy::StartingStrategy;
+
+const UNSIGNED_FLAG: u16 = 0x20;
+const ENUM_FLAG: u16 = 0x100;
+const SET_FLAG: u16 = 0x800;
+const BINARY_COLLATION: u16 = 63;
+
+/// Row of an existing table read by the initial snapshot.
+/// Snapshot rows should be handled as inserts preceding the replicated changes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotRow {
+    /// Gets database name of the table.
+    pub database: String,
+
+    /// Gets table name.
+    pub table: String,
+
+    /// Gets row values. Values are decoded from the text protocol,
+    /// TIMESTAMP values are read in UTC and JSON values are returned as strings.
+    pub row: TypedRow,
+}
+
+impl BinlogClient {
+    /// Reads existing rows of the tables matching the table filter in a consistent snapshot.
+    /// Replication options are set to the binlog coordinates of the snapshot
+    /// (GTID if the starting strategy is FromGtid), so `replicate()` called after
+    /// the snapshot continues from the changes that are not included in it.
+    /// Binlog coordinates are read under FLUSH TABLES WITH READ LOCK which requires the RELOAD privilege.
+    pub async fn snapshot(
+        &mut self,
+    ) -> Result<impl Stream<Item = Result<SnapshotRow, Error>>, Error> {
+        let (mut channel, provider) = self.connect().await?;
+
+        self.execute(&mut channel, "SET time_zone = '+00:00'")
+            .await?;
+        self.execute(
+            &mut channel,
+            "SET SESSION TRANSACTION ISOLATION LEVEL REPEATABLE READ",
+        )
+        .await?;
+        self.execute(&mut channel, "FLUSH TABLES WITH READ LOCK")
+            .await?;
+        self.execute(&mut channel, "START TRANSACTION WITH CONSISTENT SNAPSHOT")
+            .await?;
+        let binlog = self.read_snapshot_position(&mut channel, provider).await?;
+        self.execute(&mut channel, "UNLOCK TABLES").await?;
+
+        let tables = self.list_tables(&mut channel).await?;
+        self.options.binlog = binlog;
+
+        let mut reader = SnapshotReader {
+            session: BinlogClient::new(self.options.clone()),
+            channel,
+            tables: tables.into(),
+            current: None,
+            finished: false,
+        };
+        Ok(stream! {
+            loop {
+                match reader.next_row().await {
+                    Ok(Some(row)) => yield Ok(row),
+                    Ok(None) => break,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    async fn read_snapshot_position(
+        &self,
+        channel: &mut PacketChannel,
+        provider: DatabaseProvider,
+    ) -> Result<BinlogOptions, Error> {
+        let result_set = self.query(channel, "show master status").await?;
+        if result_set.len() != 1 {
+            return Err(Error::String(
+                "Could not read master binlog position.".to_string(),
+            ));
+        }
+        let filename = result_set[0].cells[0].clone();
+        let position = result_set[0].cells[1].parse()?;
+
+        if self.options.binlog.starting_strategy != StartingStrategy::FromGtid {
+            return Ok(BinlogOptions::from_position(filename, position));
+        }
+        match provider {
+            DatabaseProvider::MySQL => {
+                let gtid_set = result_set[0].cells.get(4).cloned().unwrap_or_default();
+                Ok(BinlogOptions::from_mysql_gtid(GtidSet::parse(&gtid_set)?))
+            }
+            DatabaseProvider::MariaDB => {
+                let query = format!(
+                    "SELECT BINLOG_GTID_POS('{}', {})",
+                    filename.replace('\'', "''"),
+                    position
+                );
+                let result_set = self.query(channel, &query).await?;
+                Ok(BinlogOptions::from_mariadb_gtid(GtidList::parse(
+                    &result_set[0].cells[0],
+                )?))
+            }
+        }
+    }
+}
+
+/// Reads tables one by one using the snapshot transaction.
+struct SnapshotReader {
+    session: BinlogClient,
+    channel: PacketChannel,
+    tables: VecDeque<(String, String)>,
+    current: Option<(String, String, Vec<ColumnDefinitionPacket>)>,
+    finished: bool,
+}
+
+impl SnapshotReader {
+    async fn next_row(&mut self) -> Result<Option<SnapshotRow>, Error> {
+        loop {
+            if self.finished {
+                return Ok(None);
+            }
+
+            let (database, table, columns) = match &self.current {
+                Some(x) => x,
+                None => {
+                    match self.tables.pop_front() {
+                        Some((database, table)) => {
+                            let columns = self.select(&database, &table).await?;
+                            self.current = Some((database, table, columns));
+                        }
+                        None => {
+                            self.session.execute(&mut self.channel, "COMMIT").await?;
+                            self.finished = true;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let (packet, _) = self.channel.read_packet().await?;
+            check_error_packet(&packet, "Reading snapshot error.")?;
+            if packet[0] == response_type::END_OF_FILE && packet.len() < 9 {
+                self.current = None;
+                continue;
+            }
+
+            let values = parse_row_values(&packet)?;
+            let row = decode_row(columns, values)?;
+            return Ok(Some(SnapshotRow {
+                database: database.clone(),
+                table: table.clone(),
+                row,
+            }));
+        }
+    }
+
+    /// Sends SELECT query and reads column definitions of the result set.
+    async fn select(
+        &mut self,
+        database: &str,
+        table: &str,
+    ) -> Result<Vec<ColumnDefinitionPacket>, Error> {
+        let sql = format!(
+            "SELECT * FROM {}.{}",
+            quote_identifier(database),
+            quote_identifier(table)
+        );
+        let command = QueryCommand::new(sql);
+        self.channel.write_packet(&command.serialize()?, 0).await?;
+
+        let (packet, _) = self.channel.read_packet().await?;
+        check_error_packet(&packet, "Snapshot query error.")?;
+        let column_count = read_len_enc_num(&mut Cursor::new(packet.as_slice()))?;
+
+        let mut columns = Vec::with_capacity(column_count);
+        for _ in 0..column_count {
+            let (packet, _) = self.channel.read_packet().await?;
+            columns.push(ColumnDefinitionPacket::parse(&packet)?);
+        }
+        // End of column definitions
+        self.channel.read_packet().await?;
+        Ok(columns)
+    }
+}
+
+fn decode_row(
+    columns: &[ColumnDefinitionPacket],
+    values: Vec<Option<Vec<u8>>>,
+) -> Result<TypedRow, Error> {
+    if columns.len() != values.len() {
+        return Err(Error::String(format!(
+            "Row has {} values while result set has {} columns",
+            values.len(),
+            columns.len()
+        )));
+    }
+
+    let mut result = Vec::with_capacity(columns.len());
+    for (column, value) in columns.iter().zip(values) {
+        let value = match value {
+            Some(x) => Some(decode_value(column, x)?),
+            None => None,
+        };
+        result.push(TypedColumn {
+            name: column.name.clone(),
+            value,
+        });
+    }
+    Ok(TypedRow { columns: result })
+}
+
+/// Converts text protocol value to the value returned for row events.
+fn decode_value(column: &ColumnDefinitionPacket, bytes: Vec<u8>) -> Result<ColumnValue, Error> {
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    let value = match ColumnType::from_code(column.column_type)? {
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Int24
+        | ColumnType::Long
+        | ColumnType::LongLong => {
+            if column.flags & UNSIGNED_FLAG != 0 {
+                return Ok(ColumnValue::Unsigned(parse(&text)?));
+            }
+            return Ok(ColumnValue::Signed(parse(&text)?));
+        }
+        ColumnType::Float => MySqlValue::Float(parse(&text)?),
+        ColumnType::Double => MySqlValue::Double(parse(&text)?),
+        ColumnType::Decimal | ColumnType::NewDecimal => MySqlValue::Decimal(text),
+        ColumnType::Year => MySqlValue::Year(parse(&text)?),
+        ColumnType::Date | ColumnType::NewDate => MySqlValue::Date(parse_date(&text)?),
+        ColumnType::Time | ColumnType::Time2 => MySqlValue::Time(parse_time(&text)?),
+        ColumnType::DateTime | ColumnType::DateTime2 => {
+            MySqlValue::DateTime(parse_datetime(&text)?)
+        }
+        ColumnType::TimeStamp | ColumnType::TimeStamp2 => {
+            MySqlValue::Timestamp(to_unix_millis(&parse_datetime(&text)?))
+        }
+        ColumnType::Bit => {
+            let length = column.column_length as usize;
+            let mut cursor = Cursor::new(bytes.as_slice());
+            let mut bits = read_bitmap_big_endian(&mut cursor, length)?;
+            bits.reverse();
+            MySqlValue::Bit(bits)
+        }
+        ColumnType::Geometry => MySqlValue::Geometry(Geometry::parse(&bytes)?),
+        ColumnType::Enum => return Ok(ColumnValue::Enum(text)),
+        ColumnType::Set => return Ok(ColumnValue::Set(parse_set(&text))),
+        _ if column.flags & ENUM_FLAG != 0 => return Ok(ColumnValue::Enum(text)),
+        _ if column.flags & SET_FLAG != 0 => return Ok(ColumnValue::Set(parse_set(&text))),
+        _ if column.collation == BINARY_COLLATION => MySqlValue::Blob(bytes),
+        _ => MySqlValue::String(text),
+    };
+    Ok(ColumnValue::Value(value))
+}
+
+fn parse<T: FromStr>(text: &str) -> Result<T, Error> {
+    text.parse()
+        .map_err(|_| Error::String(format!("Could not parse snapshot value '{}'", text)))
+}
+
+fn parse_set(text: &str) -> Vec<String> {
+    match text.is_empty() {
+        true => Vec::new(),
+        false => text.split(',').map(|x| x.to_string()).collect(),
+    }
+}
+
+/// Parses YYYY-MM-DD value.
+fn parse_date(text: &str) -> Result<Date, Error> {
+    let parts: Vec<&str> = text.split('-').collect();
+    if parts.len() != 3 {
+        return Err(Error::String(format!("Invalid date value '{}'", text)));
+    }
+    Ok(Date {
+        year: parse(parts[0])?,
+        month: parse(parts[1])?,
+        day: parse(parts[2])?,
+    })
+}
+
+/// Parses [-]HHH:MM:SS[.ffffff] value.
+fn parse_time(text: &str) -> Result<Time, Error> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, text),
+    };
+    let (time, millis) = split_fraction(text)?;
+    let parts: Vec<&str> = time.split(':').collect();
+    if parts.len() != 3 {
+        return Err(Error::String(format!("Invalid time value '{}'", text)));
+    }
+    let hour: i16 = parse(parts[0])?;
+    Ok(Time {
+        hour: if negative { -hour } else { hour },
+        minute: parse(parts[1])?,
+        second: parse(parts[2])?,
+        millis,
+    })
+}
+
+/// Parses YYYY-MM-DD HH:MM:SS[.ffffff] value.
+fn parse_datetime(text: &str) -> Result<DateTime, Error> {
+    let (date, time) = match text.split_once(' ') {
+        Some(x) => x,
+        None => return Err(Error::String(format!("Invalid datetime value '{}'", text))),
+    };
+    let date = parse_date(date)?;
+    let time = parse_time(time)?;
+    Ok(DateTime {
+        year: date.year,
+        month: date.month,
+        day: date.day,
+        hour: time.hour as u8,
+        minute: time.minute,
+        second: time.second,
+        millis: time.millis,
+    })
+}
+
+/// Splits fractional seconds and converts them to milliseconds.
+fn split_fraction(text: &str) -> Result<(&str, u32), Error> {
+    match text.split_once('.') {
+        Some((time, fraction)) => {
+            let digits: String = fraction.chars().chain("000".chars()).take(3).collect();
+            Ok((time, parse(&digits)?))
+        }
+        None => Ok((text, 0)),
+    }
+}
+
+/// Converts UTC datetime to milliseconds from Unix epoch.
+fn to_unix_millis(value: &DateTime) -> u64 {
+    // Zero timestamp '0000-00-00 00:00:00'
+    if value.year == 0 {
+        return 0;
+    }
+    let seconds = seconds_from_civil(
+        value.year as i64,
+        value.month as i64,
+        value.day as i64,
+        value.hour as i64,
+        value.minute as i64,
+        value.second as i64,
+    );
+    (seconds * 1000 + value.millis as i64).max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::{decode_value, parse_datetime, to_unix_millis};
+    use crate::binlog_client::BinlogClient;
+    use crate::binlog_options::BinlogOptions;
+    use crate::constants::column_type::ColumnType;
+    use crate::events::row_events::mysql_value::MySqlValue;
+    use crate::events::row_events::typed_row::ColumnValue;
+    use crate::fake_server::{write_ok, write_result_set, FakeServer};
+    use crate::responses::column_definition_packet::ColumnDefinitionPacket;
+    use crate::starting_strategy::StartingStrategy;
+
+    fn column(column_type: ColumnType, flags: u16, collation: u16) -> ColumnDefinitionPacket {
+        ColumnDefinitionPacket {
+            name: String::from("c"),
+            collation,
+            column_length: 10,
+            column_type: column_type as u8,
+            flags,
+        }
+    }
+
+    #[test]
+    fn decode_text_values() {
+        let value = |column, text: &str| decode_value(&column, text.as_bytes().to_vec()).unwrap();
+        assert_eq!(
+            ColumnValue::Unsigned(4294967295),
+            value(column(ColumnType::Long, 0x20, 63), "4294967295")
+        );
+        assert_eq!(
+            ColumnValue::Signed(-5),
+            value(column(ColumnType::Tiny, 0, 63), "-5")
+        );
+        assert_eq!(
+            ColumnValue::Enum(String::from("paid")),
+            value(column(ColumnType::String, 0x100, 45), "paid")
+        );
+        assert_eq!(
+            ColumnValue::Value(MySqlValue::Blob(vec![0x61])),
+            value(column(ColumnType::VarString, 0x80, 63), "a")
+        );
+        assert_eq!(
+            1_700_000_000_123,
+            to_unix_millis(&parse_datetime("2023-11-14 22:13:20.123456").unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshot_sets_replication_position() {
+        let server = FakeServer::bind().await;
+        let mut options = server.options();
+        options.binlog = BinlogOptions::from_start();
+        let handle = tokio::spawn(async move {
+            let mut channel = server.accept().await;
+            loop {
+                let (packet, _) = channel.read_packet().await.unwrap();
+                let sql = String::from_utf8(packet[1..].to_vec()).unwrap();
+                if sql == "show master status" {
+                    let rows = [vec!["mysql-bin.000002", "1200"]];
+                    write_result_set(&mut channel, &["File", "Position"], &rows).await;
+                } else if sql.starts_with("SELECT TABLE_SCHEMA") {
+                    let rows = [vec!["shop", "orders"]];
+                    write_result_set(&mut channel, &["TABLE_SCHEMA", "TABLE_NAME"], &rows).await;
+                } else if sql == "SELECT * FROM `shop`.`orders`" {
+                    let rows = [vec!["1", "new"], vec!["2", "paid"]];
+                    write_result_set(&mut channel, &["id", "status"], &rows).await;
+                } else {
+                    write_ok(&mut channel, 1).await;
+                    if sql == "COMMIT" {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut client = BinlogClient::new(options);
+        let rows: Vec<_> = client.snapshot().await.unwrap().collect().await;
+        handle.await.unwrap();
+
+        assert_eq!(2, rows.len());
+        let row = rows[1].as_ref().unwrap();
+        assert_eq!("orders", row.table);
+        assert_eq!(2, row.row.columns.len());
+        assert_eq!("mysql-bin.000002", client.options.binlog.filename);
+        assert_eq!(1200, client.options.binlog.position);
+        assert_eq!(
+            StartingStrategy::FromPosition,
+            client.options.binlog.starting_strategy
+        );
+    }
+}
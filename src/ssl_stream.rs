@@ -0,0 +1,345 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls::{
+    Certificate, CertificateError, ClientConfig, ClientConnection, Connection, PrivateKey,
+    RootCertStore, ServerName,
+};
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::errors::Error;
+use crate::replica_options::ReplicaOptions;
+use crate::ssl_mode::SslMode;
+
+const TLS_BUFFER_SIZE: usize = 16 * 1024;
+
+/// TLS session driven on top of a plain TCP stream.
+pub struct SslStream {
+    stream: TcpStream,
+    connection: Connection,
+    buffer: Vec<u8>,
+}
+
+impl SslStream {
+    /// Performs client TLS handshake according to the ssl options.
+    pub async fn connect(stream: TcpStream, options: &ReplicaOptions) -> Result<Self, Error> {
+        let config = create_client_config(options)?;
+        let server_name = ServerName::try_from(options.hostname.as_str())
+            .map_err(|_| Error::String(format!("Invalid ssl server name {}", options.hostname)))?;
+        let connection = ClientConnection::new(Arc::new(config), server_name)?;
+        Self::handshake(stream, Connection::from(connection)).await
+    }
+
+    /// Drives TLS handshake of a client or a server connection.
+    pub async fn handshake(stream: TcpStream, connection: Connection) -> Result<Self, Error> {
+        let mut ssl_stream = Self {
+            stream,
+            connection,
+            buffer: vec![0; TLS_BUFFER_SIZE],
+        };
+        while ssl_stream.connection.is_handshaking() {
+            ssl_stream.write_tls().await?;
+            if ssl_stream.connection.is_handshaking() && ssl_stream.connection.wants_read() {
+                ssl_stream.read_tls().await?;
+            }
+        }
+        ssl_stream.write_tls().await?;
+        Ok(ssl_stream)
+    }
+
+    pub async fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            match self.connection.reader().read(&mut buf[offset..]) {
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                Ok(read) => offset += read,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => self.read_tls().await?,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn write_all(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        self.connection.writer().write_all(buf)?;
+        self.write_tls().await
+    }
+
+    /// Reads encrypted records from the socket and decrypts them.
+    async fn read_tls(&mut self) -> Result<(), io::Error> {
+        let read = self.stream.read(&mut self.buffer).await?;
+        if read == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        let mut records = &self.buffer[..read];
+        while !records.is_empty() {
+            self.connection.read_tls(&mut records)?;
+            self.connection
+                .process_new_packets()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(())
+    }
+
+    /// Sends pending encrypted records to the socket.
+    async fn write_tls(&mut self) -> Result<(), io::Error> {
+        while self.connection.wants_write() {
+            let mut records = Vec::new();
+            self.connection.write_tls(&mut records)?;
+            self.stream.write_all(&records).await?;
+        }
+        Ok(())
+    }
+}
+
+fn create_client_config(options: &ReplicaOptions) -> Result<ClientConfig, Error> {
+    let builder = ClientConfig::builder().with_safe_defaults();
+
+    let builder = match options.ssl_mode {
+        SslMode::RequireVerifyFull => {
+            let verifier = WebPkiVerifier::new(load_root_store(options)?, None);
+            builder.with_custom_certificate_verifier(Arc::new(verifier))
+        }
+        SslMode::RequireVerifyCa => {
+            let verifier = CaVerifier(WebPkiVerifier::new(load_root_store(options)?, None));
+            builder.with_custom_certificate_verifier(Arc::new(verifier))
+        }
+        _ => builder.with_custom_certificate_verifier(Arc::new(NoVerifier)),
+    };
+
+    let config = match (&options.ssl_cert, &options.ssl_key) {
+        (Some(cert), Some(key)) => {
+            builder.with_client_auth_cert(load_certificates(cert)?, load_private_key(key)?)?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(Error::String(
+                "Both ssl_cert and ssl_key must be specified for client authentication".to_string(),
+            ))
+        }
+    };
+    Ok(config)
+}
+
+fn load_root_store(options: &ReplicaOptions) -> Result<RootCertStore, Error> {
+    let ssl_ca = options.ssl_ca.as_ref().ok_or_else(|| {
+        Error::String(format!(
+            "ssl_ca is required for {:?} ssl mode",
+            options.ssl_mode
+        ))
+    })?;
+
+    let mut store = RootCertStore::empty();
+    for certificate in load_certificates(ssl_ca)? {
+        store.add(&certificate)?;
+    }
+    Ok(store)
+}
+
+fn load_certificates(path: &str) -> Result<Vec<Certificate>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certificates: Vec<Certificate> = rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+
+    if certificates.is_empty() {
+        return Err(Error::String(format!("No certificates found in {}", path)));
+    }
+    Ok(certificates)
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err(Error::String(format!("No private key found in {}", path)))
+}
+
+/// Accepts any server certificate. Used in IfAvailable and Require modes.
+struct NoVerifier;
+
+impl ServerCertVerifier for NoVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Verifies the certificate chain but not the host name. Used in RequireVerifyCa mode.
+struct CaVerifier(WebPkiVerifier);
+
+impl ServerCertVerifier for CaVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // WebPkiVerifier checks the chain before the host name.
+        match self.0.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        ) {
+            Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+    use rustls::{ServerConfig, ServerConnection};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::{load_certificates, load_private_key, SslStream};
+    use crate::binlog_client::BinlogClient;
+    use crate::constants::capability_flags;
+    use crate::fake_server::handshake_packet;
+    use crate::replica_options::ReplicaOptions;
+    use crate::ssl_mode::SslMode;
+
+    const CA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ssl/ca.pem");
+    const CERT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ssl/server.pem");
+    const KEY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/ssl/server-key.pem"
+    );
+
+    fn frame(packet: &[u8], seq_num: u8) -> Vec<u8> {
+        let mut vec = vec![];
+        vec.write_u24::<LittleEndian>(packet.len() as u32).unwrap();
+        vec.push(seq_num);
+        vec.extend_from_slice(packet);
+        vec
+    }
+
+    async fn read_frame(stream: &mut TcpStream) -> Vec<u8> {
+        use tokio::io::AsyncReadExt;
+        let mut header = [0; 4];
+        stream.read_exact(&mut header).await.unwrap();
+        let mut packet = vec![0; header[0] as usize + ((header[1] as usize) << 8)];
+        stream.read_exact(&mut packet).await.unwrap();
+        packet
+    }
+
+    /// Stand-in server that requires TLS and accepts any credentials.
+    async fn serve(listener: TcpListener) -> u32 {
+        use tokio::io::AsyncWriteExt;
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream
+            .write_all(&frame(&handshake_packet(capability_flags::SSL), 0))
+            .await
+            .unwrap();
+
+        let ssl_request = read_frame(&mut stream).await;
+        let capabilities = u32::from_le_bytes(ssl_request[0..4].try_into().unwrap());
+        assert_ne!(0, capabilities as u64 & capability_flags::SSL);
+
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                load_certificates(CERT).unwrap(),
+                load_private_key(KEY).unwrap(),
+            )
+            .unwrap();
+        let connection = ServerConnection::new(Arc::new(config)).unwrap();
+        let mut ssl_stream = match SslStream::handshake(stream, connection.into()).await {
+            Ok(ssl_stream) => ssl_stream,
+            Err(_) => return 0,
+        };
+
+        let mut header = [0; 4];
+        ssl_stream.read_exact(&mut header).await.unwrap();
+        let mut response = vec![0; header[0] as usize];
+        ssl_stream.read_exact(&mut response).await.unwrap();
+
+        ssl_stream
+            .write_all(&frame(&[0, 0, 0, 2, 0, 0, 0], header[3] + 1))
+            .await
+            .unwrap();
+        u32::from_le_bytes(response[0..4].try_into().unwrap())
+    }
+
+    async fn connect(ssl_mode: SslMode, hostname: &str) -> (bool, u32) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(serve(listener));
+
+        let client = BinlogClient::new(ReplicaOptions {
+            port,
+            hostname: hostname.to_string(),
+            ssl_mode,
+            ssl_ca: Some(CA.to_string()),
+            ..Default::default()
+        });
+        let connected = client.connect().await.is_ok();
+        (connected, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn connect_with_verify_full() {
+        let (connected, capabilities) = connect(SslMode::RequireVerifyFull, "localhost").await;
+        assert!(connected);
+        assert_ne!(0, capabilities as u64 & capability_flags::SSL);
+    }
+
+    #[tokio::test]
+    async fn connect_with_verify_ca_ignores_host() {
+        let (connected, _) = connect(SslMode::RequireVerifyCa, "127.0.0.1").await;
+        assert!(connected);
+    }
+
+    #[tokio::test]
+    async fn connect_with_verify_full_rejects_host() {
+        let (connected, _) = connect(SslMode::RequireVerifyFull, "127.0.0.1").await;
+        assert!(!connected);
+    }
+
+    #[tokio::test]
+    async fn connect_with_require_skips_verification() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(serve(listener));
+
+        let client = BinlogClient::new(ReplicaOptions {
+            port,
+            hostname: String::from("127.0.0.1"),
+            ssl_mode: SslMode::Require,
+            ..Default::default()
+        });
+        assert!(client.connect().await.is_ok());
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+/// Database and table name pattern.
+/// Supports `*` matching any sequence of characters and `?` matching a single character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TablePattern {
+    /// Gets database name pattern.
+    pub database: String,
+
+    /// Gets table name pattern.
+    pub table: String,
+}
+
+impl TablePattern {
+    pub fn new(database: &str, table: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    /// Checks whether the pattern matches the table. Names are case sensitive.
+    pub fn matches(&self, database: &str, table: &str) -> bool {
+        matches_wildcard(&self.database, database) && matches_wildcard(&self.table, table)
+    }
+}
+
+/// Defines tables whose row events are parsed.
+/// Row events of other tables are returned as SkippedRowsEvent without decoding the rows.
+#[derive(Clone, Debug, Default)]
+pub struct TableFilter {
+    /// Gets tables to include. All tables are included if empty.
+    pub include: Vec<TablePattern>,
+
+    /// Gets tables to exclude. Takes precedence over include.
+    pub exclude: Vec<TablePattern>,
+}
+
+impl TableFilter {
+    /// Checks whether row events of the table are parsed.
+    pub fn matches(&self, database: &str, table: &str) -> bool {
+        let included =
+            self.include.is_empty() || self.include.iter().any(|x| x.matches(database, table));
+        included && !self.exclude.iter().any(|x| x.matches(database, table))
+    }
+}
+
+pub(crate) fn matches_wildcard(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    // Position after the last `*` to backtrack to.
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut v) = (0, 0);
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, v));
+            p += 1;
+        } else if let Some((star_p, star_v)) = star {
+            p = star_p;
+            v = star_v + 1;
+            star = Some((star_p, star_v + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|x| *x == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TableFilter, TablePattern};
+
+    #[test]
+    fn match_patterns() {
+        assert!(TablePattern::new("shop", "orders").matches("shop", "orders"));
+        assert!(!TablePattern::new("shop", "orders").matches("shop", "orders2"));
+        assert!(TablePattern::new("shop_*", "order?").matches("shop_eu", "orders"));
+        assert!(TablePattern::new("*", "*_log").matches("crm", "audit_log"));
+        assert!(!TablePattern::new("*", "*_log").matches("crm", "audit_logs"));
+    }
+
+    #[test]
+    fn exclude_takes_precedence() {
+        let filter = TableFilter {
+            include: vec![TablePattern::new("shop", "*")],
+            exclude: vec![TablePattern::new("shop", "tmp_*")],
+        };
+        assert!(filter.matches("shop", "orders"));
+        assert!(!filter.matches("shop", "tmp_orders"));
+        assert!(!filter.matches("crm", "orders"));
+        assert!(TableFilter::default().matches("crm", "orders"));
+    }
+}
@@ -0,0 +1,44 @@
+//! Binlog files and directories shared by tests.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::binlog_writer::BinlogWriter;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::xid_event::XidEvent;
+
+/// Creates an empty temporary directory unique to the test process.
+pub fn create_directory(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mysql_cdc_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir(&path).unwrap();
+    path
+}
+
+/// Event header without length and position that are filled by BinlogWriter.
+pub fn create_header(event_type: u8) -> EventHeader {
+    EventHeader {
+        timestamp: 1689416430,
+        event_type,
+        server_id: 1,
+        event_length: 0,
+        next_event_position: 0,
+        event_flags: 0,
+    }
+}
+
+pub fn write_xid<W: Write>(writer: &mut BinlogWriter<W>, xid: u64) {
+    let event = BinlogEvent::XidEvent(XidEvent { xid });
+    writer.write_event(&create_header(16), &event).unwrap();
+}
+
+/// Writes in-memory binlog of a transaction per xid.
+pub fn create_binlog(xids: &[u64]) -> BinlogWriter<Vec<u8>> {
+    let mut writer = BinlogWriter::new(Vec::new()).unwrap();
+    for xid in xids {
+        write_xid(&mut writer, *xid);
+    }
+    writer
+}
@@ -0,0 +1,472 @@
+use std::fmt;
+
+use async_stream::stream;
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+use crate::binlog_position::BinlogPosition;
+use crate::errors::Error;
+use crate::events::binlog_event::BinlogEvent;
+use crate::events::event_header::EventHeader;
+use crate::events::query_event::QueryEvent;
+use crate::events::row_events::row_decoder::{RowChange, RowDecoder};
+use crate::events::table_map_event::TableMapEvent;
+use crate::providers::mariadb::gtid::gtid::Gtid as MariaDbGtid;
+use crate::providers::mysql::gtid::gtid::Gtid as MySqlGtid;
+use crate::schema_history::{SchemaHistory, SchemaHistoryRecord};
+
+/// MariaDB GtidEvent flag of a statement that is not wrapped in BEGIN/COMMIT.
+const FL_STANDALONE: u8 = 1;
+
+/// Global Transaction ID of a transaction.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionGtid {
+    MySql(MySqlGtid),
+    MariaDb(MariaDbGtid),
+}
+
+impl fmt::Display for TransactionGtid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionGtid::MySql(x) => x.fmt(f),
+            TransactionGtid::MariaDb(x) => x.fmt(f),
+        }
+    }
+}
+
+/// Rows changed by a single row event.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableChange {
+    /// Gets the TableMapEvent the row event refers to.
+    pub table: TableMapEvent,
+
+    /// Gets changed rows.
+    pub rows: Vec<RowChange>,
+}
+
+/// Group of events committed together.
+/// DDL and other statements logged outside of BEGIN/COMMIT are returned as separate transactions.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transaction {
+    /// Gets GTID of the transaction. None if GTID mode is disabled.
+    pub gtid: Option<TransactionGtid>,
+
+    /// Gets commit time in seconds from Unix.
+    pub timestamp: u32,
+
+    /// Gets position of the first event of the transaction.
+    pub start: BinlogPosition,
+
+    /// Gets position after the last event of the transaction.
+    /// Replication resumed from the position starts from the next transaction.
+    pub end: BinlogPosition,
+
+    /// Gets row changes in the order they were logged.
+    pub changes: Vec<TableChange>,
+
+    /// Gets DDL and statement based DML of the transaction.
+    pub queries: Vec<QueryEvent>,
+}
+
+/// Groups events of a binlog event stream into transactions.
+/// Events must be passed in the order they are read.
+pub struct TransactionAssembler {
+    decoder: RowDecoder,
+    filename: String,
+    gtid: Option<TransactionGtid>,
+    gtid_position: u32,
+    current: Option<Transaction>,
+    schema_history: Option<SchemaHistory>,
+}
+
+impl TransactionAssembler {
+    /// Creates assembler for events starting in the binlog file.
+    /// The file name is updated on RotateEvent.
+    pub fn new(filename: String) -> Self {
+        Self {
+            decoder: RowDecoder::new(),
+            filename,
+            gtid: None,
+            gtid_position: 0,
+            current: None,
+            schema_history: None,
+        }
+    }
+
+    /// Creates assembler that records DDL statements in the schema history
+    /// and decodes rows using column names of the schema in effect at the row event.
+    pub fn with_schema_history(filename: String, schema_history: SchemaHistory) -> Self {
+        Self {
+            schema_history: Some(schema_history),
+            ..Self::new(filename)
+        }
+    }
+
+    /// Gets schema history updated by the assembler.
+    pub fn schema_history(&self) -> Option<&SchemaHistory> {
+        self.schema_history.as_ref()
+    }
+
+    /// Adds next event. Returns the transaction completed by the event.
+    pub fn push(
+        &mut self,
+        header: &EventHeader,
+        event: BinlogEvent,
+    ) -> Result<Option<Transaction>, Error> {
+        let position = header
+            .next_event_position
+            .saturating_sub(header.event_length);
+
+        match event {
+            BinlogEvent::RotateEvent(x) => {
+                self.filename = x.binlog_filename;
+            }
+            BinlogEvent::MySqlGtidEvent(x) => {
+                self.gtid = Some(TransactionGtid::MySql(x.gtid));
+                self.gtid_position = position;
+            }
+            BinlogEvent::MariaDbGtidEvent(x) => {
+                self.gtid = Some(TransactionGtid::MariaDb(x.gtid));
+                self.gtid_position = position;
+                // MariaDB doesn't log BEGIN after GtidEvent.
+                if x.flags & FL_STANDALONE == 0 {
+                    self.begin(position);
+                }
+            }
+            BinlogEvent::TableMapEvent(mut x) => {
+                if let Some(history) = &self.schema_history {
+                    let position = BinlogPosition::new(self.filename.clone(), position);
+                    history.fill_metadata(&position, &mut x);
+                }
+                self.decoder.decode(&BinlogEvent::TableMapEvent(x))?;
+            }
+            BinlogEvent::WriteRowsEvent(_)
+            | BinlogEvent::UpdateRowsEvent(_)
+            | BinlogEvent::DeleteRowsEvent(_)
+            | BinlogEvent::MySqlPartialUpdateRowsEvent(_) => {
+                let rows = self.decoder.decode(&event)?;
+                let table_id = match &event {
+                    BinlogEvent::WriteRowsEvent(x) => x.table_id,
+                    BinlogEvent::UpdateRowsEvent(x) => x.table_id,
+                    BinlogEvent::DeleteRowsEvent(x) => x.table_id,
+                    BinlogEvent::MySqlPartialUpdateRowsEvent(x) => x.table_id,
+                    _ => unreachable!(),
+                };
+                // RowDecoder fails if the table is unknown.
+                let table = self.decoder.table(table_id).unwrap().clone();
+                self.begin(position);
+                if let Some(transaction) = &mut self.current {
+                    transaction.changes.push(TableChange { table, rows });
+                }
+            }
+            BinlogEvent::XidEvent(_) => return Ok(self.commit(header)),
+            BinlogEvent::QueryEvent(x) => match x.sql_statement.as_str() {
+                "BEGIN" => self.begin(position),
+                "COMMIT" | "ROLLBACK" => return Ok(self.commit(header)),
+                _ => {
+                    let standalone = self.current.is_none();
+                    self.begin(position);
+                    let mut result = Ok(false);
+                    if let Some(transaction) = &mut self.current {
+                        // Statements that are not DDL or can't be parsed are not recorded.
+                        if let (Some(history), Some(_)) =
+                            (&mut self.schema_history, &x.schema_change)
+                        {
+                            result = history.record(SchemaHistoryRecord {
+                                position: BinlogPosition::new(self.filename.clone(), position),
+                                gtid: transaction.gtid.as_ref().map(|x| x.to_string()),
+                                database: x.database_name.clone(),
+                                ddl: x.sql_statement.clone(),
+                                sql_mode: x.status.sql_mode.unwrap_or(0),
+                            });
+                        }
+                        transaction.queries.push(x);
+                    }
+                    // The statement is committed even if the history file can't be written.
+                    let transaction = if standalone {
+                        self.commit(header)
+                    } else {
+                        None
+                    };
+                    result?;
+                    return Ok(transaction);
+                }
+            },
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Starts a transaction if there is no open one.
+    fn begin(&mut self, position: u32) {
+        if self.current.is_some() {
+            return;
+        }
+
+        let position = match self.gtid {
+            Some(_) => self.gtid_position,
+            None => position,
+        };
+        self.current = Some(Transaction {
+            gtid: self.gtid.take(),
+            timestamp: 0,
+            start: BinlogPosition::new(self.filename.clone(), position),
+            end: BinlogPosition::default(),
+            changes: Vec::new(),
+            queries: Vec::new(),
+        });
+    }
+
+    fn commit(&mut self, header: &EventHeader) -> Option<Transaction> {
+        // Commit of a transaction started before the first event is skipped.
+        self.gtid = None;
+        let mut transaction = self.current.take()?;
+        transaction.timestamp = header.timestamp;
+        transaction.end = BinlogPosition::new(self.filename.clone(), header.next_event_position);
+        Some(transaction)
+    }
+
+    /// Groups events of the stream returned by BinlogClient::replicate.
+    pub fn stream<S>(mut self, events: S) -> impl Stream<Item = Result<Transaction, Error>>
+    where
+        S: Stream<Item = Result<(EventHeader, BinlogEvent), Error>>,
+    {
+        stream! {
+            let mut events = Box::pin(events);
+            while let Some(result) = events.next().await {
+                match result {
+                    Ok((header, event)) => match self.push(&header, event) {
+                        Ok(Some(transaction)) => yield Ok(transaction),
+                        Ok(None) => {}
+                        Err(e) => yield Err(e),
+                    },
+                    Err(e) => yield Err(e),
+                }
+            }
+        }
+    }
+
+    /// Groups events of an iterator, e.g. BinlogReader.
+    pub fn iter<I>(mut self, mut events: I) -> impl Iterator<Item = Result<Transaction, Error>>
+    where
+        I: Iterator<Item = Result<(EventHeader, BinlogEvent), Error>>,
+    {
+        std::iter::from_fn(move || loop {
+            match events.next()? {
+                Ok((header, event)) => match self.push(&header, event) {
+                    Ok(Some(transaction)) => return Some(Ok(transaction)),
+                    Ok(None) => {}
+                    Err(e) => return Some(Err(e)),
+                },
+                Err(e) => return Some(Err(e)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{TransactionAssembler, TransactionGtid};
+    use crate::events::binlog_event::BinlogEvent;
+    use crate::events::event_header::EventHeader;
+    use crate::events::query_event::QueryEvent;
+    use crate::events::row_events::row_data::RowData;
+    use crate::events::row_events::row_decoder::RowChange;
+    use crate::events::row_events::write_rows_event::WriteRowsEvent;
+    use crate::events::table_map_event::TableMapEvent;
+    use crate::events::xid_event::XidEvent;
+    use crate::providers::mysql::events::gtid_event::GtidEvent;
+    use crate::providers::mysql::gtid::gtid::Gtid;
+    use crate::providers::mysql::gtid::uuid::Uuid;
+    use crate::schema_history::SchemaHistory;
+
+    fn header(timestamp: u32, event_length: u32, next_event_position: u32) -> EventHeader {
+        EventHeader {
+            timestamp,
+            event_type: 0,
+            server_id: 1,
+            event_length,
+            next_event_position,
+            event_flags: 0,
+        }
+    }
+
+    fn query(sql: &str) -> BinlogEvent {
+        let mut payload = vec![0; 13];
+        payload.extend_from_slice(&[0]);
+        payload.extend_from_slice(sql.as_bytes());
+        BinlogEvent::QueryEvent(QueryEvent::parse(&mut Cursor::new(payload.as_slice())).unwrap())
+    }
+
+    fn gtid(transaction_id: u64) -> BinlogEvent {
+        BinlogEvent::MySqlGtidEvent(GtidEvent {
+            gtid: Gtid::new(Uuid::new([1; 16]), transaction_id),
+            flags: 0,
+        })
+    }
+
+    #[test]
+    fn assemble_row_transaction() {
+        let mut assembler = TransactionAssembler::new(String::from("mysql-bin.000001"));
+        let table = TableMapEvent {
+            table_id: 7,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![3],
+            column_metadata: vec![0],
+            null_bitmap: vec![false],
+            table_metadata: None,
+        };
+        let rows = WriteRowsEvent {
+            table_id: 7,
+            flags: 0,
+            columns_number: 1,
+            columns_present: vec![true],
+            rows: vec![RowData::new(vec![None])],
+        };
+
+        let events = vec![
+            (header(1, 65, 200), gtid(5)),
+            (header(1, 50, 250), query("BEGIN")),
+            (header(1, 40, 290), BinlogEvent::TableMapEvent(table)),
+            (header(1, 30, 320), BinlogEvent::WriteRowsEvent(rows)),
+            (
+                header(2, 31, 351),
+                BinlogEvent::XidEvent(XidEvent { xid: 9 }),
+            ),
+        ];
+        let mut transactions = Vec::new();
+        for (header, event) in events {
+            if let Some(x) = assembler.push(&header, event).unwrap() {
+                transactions.push(x);
+            }
+        }
+
+        assert_eq!(1, transactions.len());
+        let transaction = &transactions[0];
+        assert!(
+            matches!(&transaction.gtid, Some(TransactionGtid::MySql(x)) if x.transaction_id == 5)
+        );
+        assert_eq!(2, transaction.timestamp);
+        assert_eq!(135, transaction.start.position);
+        assert_eq!(351, transaction.end.position);
+        assert_eq!("mysql-bin.000001", transaction.end.filename);
+        assert_eq!(1, transaction.changes.len());
+        assert_eq!("orders", transaction.changes[0].table.table_name);
+        assert!(matches!(
+            transaction.changes[0].rows[0],
+            RowChange::Insert(_)
+        ));
+    }
+
+    #[test]
+    fn assemble_standalone_statement() {
+        let mut assembler = TransactionAssembler::new(String::from("mysql-bin.000001"));
+        assert!(assembler
+            .push(&header(1, 65, 200), gtid(6))
+            .unwrap()
+            .is_none());
+
+        let ddl = query("CREATE TABLE t (id INT)");
+        let transaction = assembler.push(&header(3, 80, 280), ddl).unwrap().unwrap();
+        assert!(transaction.gtid.is_some());
+        assert_eq!(135, transaction.start.position);
+        assert_eq!(280, transaction.end.position);
+        assert_eq!(
+            "CREATE TABLE t (id INT)",
+            transaction.queries[0].sql_statement
+        );
+        assert!(transaction.changes.is_empty());
+    }
+
+    #[test]
+    fn unparsable_statements_do_not_fail_stream() {
+        let history = SchemaHistory::new();
+        let mut assembler =
+            TransactionAssembler::with_schema_history(String::from("mysql-bin.000001"), history);
+
+        let statements = [
+            "CREATE TABLE t (id INT",
+            "INSERT INTO t VALUES ('C:\\')",
+            "CREATE TABLE t (id INT)",
+        ];
+        let mut position = 200;
+        for (i, sql) in statements.iter().enumerate() {
+            assembler
+                .push(&header(1, 65, position), gtid(i as u64 + 1))
+                .unwrap();
+            let transaction = assembler
+                .push(&header(1, 80, position + 80), query(sql))
+                .unwrap()
+                .unwrap();
+            assert!(
+                matches!(&transaction.gtid, Some(TransactionGtid::MySql(x)) if x.transaction_id == i as u64 + 1)
+            );
+            assert_eq!(*sql, transaction.queries[0].sql_statement);
+            position += 145;
+        }
+
+        let records = assembler.schema_history().unwrap().records();
+        assert_eq!(1, records.len());
+        assert_eq!("CREATE TABLE t (id INT)", records[0].ddl);
+    }
+
+    #[test]
+    fn resolve_schema_by_position_in_gtid_mode() {
+        let history = SchemaHistory::new();
+        let mut assembler =
+            TransactionAssembler::with_schema_history(String::from("mysql-bin.000001"), history);
+        let table = TableMapEvent {
+            table_id: 7,
+            database_name: String::from("shop"),
+            table_name: String::from("orders"),
+            column_types: vec![3],
+            column_metadata: vec![0],
+            null_bitmap: vec![true],
+            table_metadata: None,
+        };
+        let rows = WriteRowsEvent {
+            table_id: 7,
+            flags: 0,
+            columns_number: 1,
+            columns_present: vec![true],
+            rows: vec![RowData::new(vec![None])],
+        };
+
+        let events = vec![
+            (header(1, 65, 200), gtid(1)),
+            (
+                header(1, 80, 280),
+                query("CREATE TABLE shop.orders (id int)"),
+            ),
+            (header(2, 65, 345), gtid(2)),
+            (header(2, 50, 395), query("BEGIN")),
+            (header(2, 40, 435), BinlogEvent::TableMapEvent(table)),
+            (header(2, 30, 465), BinlogEvent::WriteRowsEvent(rows)),
+            (
+                header(2, 31, 496),
+                BinlogEvent::XidEvent(XidEvent { xid: 9 }),
+            ),
+        ];
+        let mut transactions = Vec::new();
+        for (header, event) in events {
+            if let Some(x) = assembler.push(&header, event).unwrap() {
+                transactions.push(x);
+            }
+        }
+
+        let records = assembler.schema_history().unwrap().records();
+        assert_eq!(200, records[0].position.position);
+        let gtid = transactions[0].gtid.as_ref().unwrap().to_string();
+        assert_eq!(Some(gtid), records[0].gtid);
+        match &transactions[1].changes[0].rows[0] {
+            RowChange::Insert(x) => assert_eq!("id", x.columns[0].name),
+            x => panic!("Unexpected {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+/// Settings used to write binlog files.
+#[derive(Debug)]
+pub struct WriterOptions {
+    /// Gets id of the server written to FormatDescriptionEvent and RotateEvent headers.
+    pub server_id: u32,
+
+    /// Gets server version written to FormatDescriptionEvent.
+    /// Defaults to MySQL 5.7 whose post-header lengths are used by the writer.
+    pub server_version: String,
+
+    /// Defines whether CRC32 checksums are appended to events. Defaults to true.
+    pub checksum: bool,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            server_id: 1,
+            server_version: String::from("5.7.44-log"),
+            checksum: true,
+        }
+    }
+}